@@ -801,6 +801,20 @@ pub enum EthRequest {
     /// Set the executor (sponsor) wallet
     #[cfg_attr(feature = "serde", serde(rename = "anvil_setExecutor", with = "sequence"))]
     AnvilSetExecutor(String),
+
+    /// Registers a credible-layer assertion contract, given by its creation code, for an adopter
+    /// address, with an optional label and the optional signatures of its assertion functions.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_registerAssertion"))]
+    AnvilRegisterAssertion(
+        Address,
+        Bytes,
+        #[cfg_attr(feature = "serde", serde(default))] Option<String>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<Vec<String>>,
+    ),
+
+    /// Removes the credible-layer assertion contract registered for an adopter address.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_removeAssertion", with = "sequence"))]
+    AnvilRemoveAssertion(Address),
 }
 
 /// Represents ethereum JSON-RPC API
@@ -1639,6 +1653,46 @@ true}]}"#;
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_anvil_register_assertion() {
+        let s = r#"{"method": "anvil_registerAssertion", "params": ["0x364d6D0333432C3Ac016Ca832fb8594A8cE43Ca6", "0x6080", "MockAssertion"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilRegisterAssertion(_, code, label, signatures) => {
+                assert_eq!(code, Bytes::from_static(&[0x60, 0x80]));
+                assert_eq!(label, Some("MockAssertion".to_string()));
+                assert_eq!(signatures, None);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_registerAssertion", "params": ["0x364d6D0333432C3Ac016Ca832fb8594A8cE43Ca6", "0x6080"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilRegisterAssertion(_, _, label, signatures) => {
+                assert_eq!(label, None);
+                assert_eq!(signatures, None);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_registerAssertion", "params": ["0x364d6D0333432C3Ac016Ca832fb8594A8cE43Ca6", "0x6080", "MockAssertion", ["assertIsOne()"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilRegisterAssertion(_, _, _, signatures) => {
+                assert_eq!(signatures, Some(vec!["assertIsOne()".to_string()]));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_removeAssertion", "params": ["0x364d6D0333432C3Ac016Ca832fb8594A8cE43Ca6"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_anvil_reorg() {
         // TransactionData::JSON
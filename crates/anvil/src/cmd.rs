@@ -1,7 +1,16 @@
 use crate::{
     config::{ForkChoice, DEFAULT_MNEMONIC},
-    eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
+    eth::{
+        backend::{
+            assertions::RegisteredAssertion,
+            db::SerializableState,
+            dump::{self, DumpChunk, StateFormat},
+        },
+        pool::transactions::TransactionOrder,
+        EthApi,
+    },
     hardfork::OptimismHardfork,
+    relay::{try_spawn_l2s, L2Chain},
     AccountGenerator, EthereumHardfork, NodeConfig, CHAIN_ID,
 };
 use alloy_genesis::Genesis;
@@ -10,12 +19,14 @@ use alloy_signer_local::coins_bip39::{English, Mnemonic};
 use anvil_server::ServerConfig;
 use clap::Parser;
 use core::fmt;
+use eyre::Context as _;
 use foundry_common::shell;
-use foundry_config::{Chain, Config, FigmentProviders};
+use foundry_config::{Chain, Config, CredibleConfig, FigmentProviders};
 use futures::FutureExt;
 use rand::{rngs::StdRng, SeedableRng};
 use std::{
     future::Future,
+    io::Write,
     net::IpAddr,
     path::{Path, PathBuf},
     pin::Pin,
@@ -155,6 +166,14 @@ pub struct NodeArgs {
     #[arg(long, conflicts_with = "init", default_value = "false")]
     pub preserve_historical_states: bool,
 
+    /// The format the state is dumped in: `json`, `binary` or `incremental`.
+    ///
+    /// The binary format is a compact, versioned encoding of the state. In the incremental format,
+    /// each periodic dump only appends the blocks and state changes since the previous dump.
+    /// States are loaded from any of the formats.
+    #[arg(long, value_name = "FORMAT", default_value_t = StateFormat::Json)]
+    pub state_format: StateFormat,
+
     /// Initialize the chain from a previously saved state snapshot.
     #[arg(
         long,
@@ -193,6 +212,50 @@ pub struct NodeArgs {
     /// Path to the cache directory where states are stored.    
     #[arg(long, value_name = "PATH")]
     pub cache_path: Option<PathBuf>,
+
+    /// Persist the chain in an on-disk database at the given directory.
+    ///
+    /// The state changes of every mined block are written to the database, along with the block
+    /// and its receipts, and the state is read from it on demand. The states at the last
+    /// `--max-persisted-states` blocks are kept. If the database exists, the chain is restored
+    /// from it.
+    ///
+    /// In forking mode, the state read from the fork is persisted too. A forked database can only
+    /// be reopened with the same fork block, see --fork-block-number.
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::DirPath)]
+    pub db_path: Option<PathBuf>,
+
+    /// Registers a credible-layer assertion contract for an adopter address.
+    ///
+    /// Transactions which invalidate a registered assertion are rejected and not mined. The
+    /// optional `;`-separated assertion function signatures name the functions that rejected them.
+    ///
+    /// Example: --assertion
+    /// 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:0x6080...:MyAssertion:assertOwner()
+    #[arg(
+        long = "assertion",
+        value_name = "ADOPTER:CREATION_CODE[:LABEL[:SIGNATURES]]",
+        help_heading = "Credible layer options"
+    )]
+    pub assertions: Vec<RegisteredAssertion>,
+
+    /// The gas limit for executing each assertion function.
+    #[arg(
+        long,
+        value_name = "GAS",
+        default_value_t = CredibleConfig::default().assertion_gas_limit,
+        help_heading = "Credible layer options"
+    )]
+    pub assertion_gas_limit: u64,
+
+    /// Hosts an OP-stack L2 chain next to the chain of the node, with its own chain id and port.
+    ///
+    /// Deposits of the `OptimismPortal` at the given L1 address are relayed to the L2, and
+    /// withdrawals of the L2 are executed on L1 by the portal.
+    ///
+    /// Example: --l2 901:9546:0x5FbDB2315678afecb367f032d93F642f64180aa3
+    #[arg(long = "l2", value_name = "CHAIN_ID:PORT:PORTAL", help_heading = "Multi-chain options")]
+    pub l2_chains: Vec<L2Chain>,
 }
 
 #[cfg(windows)]
@@ -254,6 +317,7 @@ impl NodeArgs {
             .fork_retry_backoff(self.evm_opts.fork_retry_backoff.map(Duration::from_millis))
             .fork_compute_units_per_second(compute_units_per_second)
             .with_eth_rpc_url(self.evm_opts.fork_url.map(|fork| fork.url))
+            .with_fork_fallback_urls(self.evm_opts.fork_fallback_urls)
             .with_base_fee(self.evm_opts.block_base_fee_per_gas)
             .disable_min_priority_fee(self.evm_opts.disable_min_priority_fee)
             .with_storage_caching(self.evm_opts.no_storage_caching)
@@ -279,7 +343,11 @@ impl NodeArgs {
             .with_disable_default_create2_deployer(self.evm_opts.disable_default_create2_deployer)
             .with_slots_in_an_epoch(self.slots_in_an_epoch)
             .with_memory_limit(self.evm_opts.memory_limit)
-            .with_cache_path(self.cache_path))
+            .with_cache_path(self.cache_path)
+            .with_db_path(self.db_path)
+            .with_state_format(self.state_format)
+            .with_assertions(self.assertions)
+            .with_assertion_gas_limit(self.assertion_gas_limit))
     }
 
     fn account_generator(&self) -> AccountGenerator {
@@ -314,13 +382,20 @@ impl NodeArgs {
     /// Starts the node
     ///
     /// See also [crate::spawn()]
-    pub async fn run(self) -> eyre::Result<()> {
+    pub async fn run(mut self) -> eyre::Result<()> {
         let dump_state = self.dump_state_path();
         let dump_interval =
             self.state_interval.map(Duration::from_secs).unwrap_or(DEFAULT_DUMP_INTERVAL);
         let preserve_historical_states = self.preserve_historical_states;
+        let state_format = self.state_format;
+        let l2_chains = std::mem::take(&mut self.l2_chains);
 
-        let (api, mut handle) = crate::try_spawn(self.into_node_config()?).await?;
+        let config = self.into_node_config()?;
+        let (api, mut handle) = crate::try_spawn(config.clone()).await?;
+
+        // the L2 nodes shut down when their handles are dropped, so they are kept until the L1
+        // node exits
+        let _l2_nodes = try_spawn_l2s(&api, &config, &l2_chains).await?;
 
         // sets the signal handler to gracefully shutdown.
         let mut fork = api.get_fork();
@@ -333,8 +408,13 @@ impl NodeArgs {
         let task_manager = handle.task_manager();
         let mut on_shutdown = task_manager.on_shutdown();
 
-        let mut state_dumper =
-            PeriodicStateDumper::new(api, dump_state, dump_interval, preserve_historical_states);
+        let mut state_dumper = PeriodicStateDumper::new(
+            api,
+            dump_state,
+            dump_interval,
+            preserve_historical_states,
+            state_format,
+        );
 
         task_manager.spawn(async move {
             // wait for the SIGTERM signal on unix systems
@@ -411,6 +491,21 @@ pub struct AnvilEvmArgs {
     )]
     pub fork_url: Option<ForkUrl>,
 
+    /// Additional endpoints of the forked chain.
+    ///
+    /// Requests are load-balanced across these and `--fork-url`, failing over to the next
+    /// endpoint if one is unreachable or answers with a server error. Transactions are only ever
+    /// sent to a single endpoint. All endpoints must agree on the hash of the fork block.
+    ///
+    /// See --fork-url.
+    #[arg(
+        long = "fork-fallback-url",
+        value_name = "URL",
+        help_heading = "Fork config",
+        requires = "fork_url"
+    )]
+    pub fork_fallback_urls: Vec<String>,
+
     /// Headers to use for the rpc client, e.g. "User-Agent: test-agent"
     ///
     /// See --fork-url.
@@ -598,6 +693,11 @@ impl AnvilEvmArgs {
             if let Some(Ok(url)) = config.get_rpc_url_with_alias(&fork_url.url) {
                 self.fork_url = Some(ForkUrl { url: url.to_string(), block: fork_url.block });
             }
+            for fallback_url in &mut self.fork_fallback_urls {
+                if let Some(Ok(url)) = config.get_rpc_url_with_alias(fallback_url) {
+                    *fallback_url = url.to_string();
+                }
+            }
         }
     }
 }
@@ -608,6 +708,7 @@ struct PeriodicStateDumper {
     api: EthApi,
     dump_state: Option<PathBuf>,
     preserve_historical_states: bool,
+    state_format: StateFormat,
     interval: Interval,
 }
 
@@ -617,6 +718,7 @@ impl PeriodicStateDumper {
         dump_state: Option<PathBuf>,
         interval: Duration,
         preserve_historical_states: bool,
+        state_format: StateFormat,
     ) -> Self {
         let dump_state = dump_state.map(|mut dump_state| {
             if dump_state.is_dir() {
@@ -627,30 +729,84 @@ impl PeriodicStateDumper {
 
         // periodically flush the state
         let interval = tokio::time::interval_at(Instant::now() + interval, interval);
-        Self { in_progress_dump: None, api, dump_state, preserve_historical_states, interval }
+        Self {
+            in_progress_dump: None,
+            api,
+            dump_state,
+            preserve_historical_states,
+            state_format,
+            interval,
+        }
     }
 
     async fn dump(&self) {
         if let Some(state) = self.dump_state.clone() {
-            Self::dump_state(self.api.clone(), state, self.preserve_historical_states).await
+            Self::dump_state(
+                self.api.clone(),
+                state,
+                self.preserve_historical_states,
+                self.state_format,
+            )
+            .await
         }
     }
 
     /// Infallible state dump
-    async fn dump_state(api: EthApi, dump_state: PathBuf, preserve_historical_states: bool) {
+    async fn dump_state(
+        api: EthApi,
+        dump_state: PathBuf,
+        preserve_historical_states: bool,
+        state_format: StateFormat,
+    ) {
         trace!(path=?dump_state, "Dumping state on shutdown");
-        match api.serialized_state(preserve_historical_states).await {
-            Ok(state) => {
-                if let Err(err) = foundry_common::fs::write_json_file(&dump_state, &state) {
-                    error!(?err, "Failed to dump state");
-                } else {
-                    trace!(path=?dump_state, "Dumped state on shutdown");
-                }
-            }
-            Err(err) => {
-                error!(?err, "Failed to extract state");
+        if let Err(err) =
+            Self::write_state(&api, &dump_state, preserve_historical_states, state_format).await
+        {
+            error!(?err, "Failed to dump state");
+        } else {
+            trace!(path=?dump_state, "Dumped state on shutdown");
+        }
+    }
+
+    /// Writes the state to the file at `path` in the given format.
+    ///
+    /// In the incremental format, only the changes since the last dump are appended to the file.
+    async fn write_state(
+        api: &EthApi,
+        path: &Path,
+        preserve_historical_states: bool,
+        state_format: StateFormat,
+    ) -> eyre::Result<()> {
+        if state_format == StateFormat::Incremental {
+            let chunk = api
+                .backend
+                .incremental_dump(preserve_historical_states)
+                .await
+                .map_err(|err| eyre::eyre!("failed to extract state: {err}"))?;
+            let result = match chunk {
+                DumpChunk::Full(buf) => std::fs::write(path, buf),
+                DumpChunk::Delta(buf) if buf.is_empty() => Ok(()),
+                DumpChunk::Delta(buf) => std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(path)
+                    .and_then(|mut file| file.write_all(&buf)),
+            };
+            if result.is_err() {
+                // the next dump starts over with the complete state
+                api.backend.reset_incremental_dump();
             }
+            return result.wrap_err_with(|| format!("failed to write state to {}", path.display()))
+        }
+
+        let state = api
+            .serialized_state(preserve_historical_states)
+            .await
+            .map_err(|err| eyre::eyre!("failed to extract state: {err}"))?;
+        match state_format {
+            StateFormat::Binary => foundry_common::fs::write(path, dump::encode(state)?)?,
+            _ => foundry_common::fs::write_json_file(path, &state)?,
         }
+        Ok(())
     }
 }
 
@@ -680,8 +836,12 @@ impl Future for PeriodicStateDumper {
             if this.interval.poll_tick(cx).is_ready() {
                 let api = this.api.clone();
                 let path = this.dump_state.clone().expect("exists; see above");
-                this.in_progress_dump =
-                    Some(Box::pin(Self::dump_state(api, path, this.preserve_historical_states)));
+                this.in_progress_dump = Some(Box::pin(Self::dump_state(
+                    api,
+                    path,
+                    this.preserve_historical_states,
+                    this.state_format,
+                )));
             } else {
                 break
             }
@@ -851,6 +1011,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_parse_fork_fallback_urls() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--fork-url",
+            "http://localhost:8545",
+            "--fork-fallback-url",
+            "http://localhost:8546",
+            "--fork-fallback-url",
+            "http://localhost:8547",
+        ]);
+        assert_eq!(
+            args.evm_opts.fork_fallback_urls,
+            vec!["http://localhost:8546", "http://localhost:8547"]
+        );
+
+        let res =
+            NodeArgs::try_parse_from(["anvil", "--fork-fallback-url", "http://localhost:8546"]);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn can_parse_prune_config() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--prune-history"]);
@@ -866,6 +1047,15 @@ mod tests {
         assert_eq!(args.max_persisted_states, (Some(500)));
     }
 
+    #[test]
+    fn can_parse_state_format() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil"]);
+        assert_eq!(args.state_format, StateFormat::Json);
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--state-format", "incremental"]);
+        assert_eq!(args.state_format, StateFormat::Incremental);
+        assert!(NodeArgs::try_parse_from(["anvil", "--state-format", "yaml"]).is_err());
+    }
+
     #[test]
     fn can_parse_disable_block_gas_limit() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--disable-block-gas-limit"]);
@@ -2,7 +2,9 @@ use crate::{
     cmd::StateFile,
     eth::{
         backend::{
+            assertions::RegisteredAssertion,
             db::{Db, SerializableState},
+            dump::StateFormat,
             fork::{ClientFork, ClientForkConfig},
             genesis::GenesisConfig,
             mem::fork_db::ForkedDatabase,
@@ -12,13 +14,18 @@ use crate::{
         pool::transactions::{PoolTransaction, TransactionOrder},
     },
     hardfork::{ChainHardfork, OptimismHardfork},
-    mem::{self, in_memory_db::MemDb},
+    mem::{
+        self,
+        disk_db::{DiskDb, DiskDbFork},
+        in_memory_db::MemDb,
+        storage::DEFAULT_HISTORY_LIMIT,
+    },
     EthereumHardfork, FeeManager, PrecompileFactory,
 };
 use alloy_consensus::BlockHeader;
 use alloy_genesis::Genesis;
 use alloy_network::{AnyNetwork, TransactionResponse};
-use alloy_primitives::{hex, map::HashMap, utils::Unit, BlockNumber, TxHash, U256};
+use alloy_primitives::{hex, map::HashMap, utils::Unit, BlockNumber, TxHash, B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{Block, BlockNumberOrTag};
 use alloy_signer::Signer;
@@ -33,9 +40,9 @@ use foundry_common::{
     provider::{ProviderBuilder, RetryProvider},
     ALCHEMY_FREE_TIER_CUPS, NON_ARCHIVE_NODE_WARNING, REQUEST_TIMEOUT,
 };
-use foundry_config::Config;
+use foundry_config::{Config, CredibleConfig};
 use foundry_evm::{
-    backend::{BlockchainDb, BlockchainDbMeta, SharedBackend},
+    backend::{BlockchainDb, BlockchainDbMeta, SharedBackend, StateSnapshot},
     constants::DEFAULT_CREATE2_DEPLOYER,
     revm::primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId, TxEnv},
     utils::apply_chain_and_block_specific_env_changes,
@@ -43,7 +50,7 @@ use foundry_evm::{
 use itertools::Itertools;
 use parking_lot::RwLock;
 use rand::thread_rng;
-use revm::primitives::BlobExcessGasAndPrice;
+use revm::{db::AccountState, primitives::BlobExcessGasAndPrice};
 use serde_json::{json, Value};
 use std::{
     fmt::Write as FmtWrite,
@@ -127,6 +134,8 @@ pub struct NodeConfig {
     pub max_transactions: usize,
     /// url of the rpc server that should be used for any rpc calls
     pub eth_rpc_url: Option<String>,
+    /// additional endpoints of the forked chain that requests are load-balanced across
+    pub fork_fallback_urls: Vec<String>,
     /// pins the block number or transaction hash for the state fork
     pub fork_choice: Option<ForkChoice>,
     /// headers to use with `eth_rpc_url`
@@ -193,6 +202,14 @@ pub struct NodeConfig {
     pub silent: bool,
     /// The path where states are cached.
     pub cache_path: Option<PathBuf>,
+    /// The path of the on-disk database the chain is persisted in.
+    pub db_path: Option<PathBuf>,
+    /// The format the state is dumped in.
+    pub state_format: StateFormat,
+    /// Credible-layer assertion contracts transactions are validated against.
+    pub assertions: Vec<RegisteredAssertion>,
+    /// The gas limit for executing each assertion function.
+    pub assertion_gas_limit: u64,
 }
 
 impl NodeConfig {
@@ -434,6 +451,7 @@ impl Default for NodeConfig {
             // TODO make this something dependent on block capacity
             max_transactions: 1_000,
             eth_rpc_url: None,
+            fork_fallback_urls: vec![],
             fork_choice: None,
             account_generator: None,
             base_fee: None,
@@ -470,6 +488,10 @@ impl Default for NodeConfig {
             odyssey: false,
             silent: false,
             cache_path: None,
+            db_path: None,
+            state_format: StateFormat::default(),
+            assertions: vec![],
+            assertion_gas_limit: CredibleConfig::default().assertion_gas_limit,
         }
     }
 }
@@ -804,6 +826,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets additional endpoints of the forked chain to fail over to from `eth_rpc_url`
+    #[must_use]
+    pub fn with_fork_fallback_urls(mut self, urls: Vec<String>) -> Self {
+        self.fork_fallback_urls = urls;
+        self
+    }
+
     /// Sets the `fork_headers` to use with `eth_rpc_url`
     #[must_use]
     pub fn with_fork_headers(mut self, headers: Vec<String>) -> Self {
@@ -923,7 +952,8 @@ impl NodeConfig {
     ///
     /// See also [ Config::foundry_block_cache_file()]
     pub fn block_cache_path(&self, block: u64) -> Option<PathBuf> {
-        if self.no_storage_caching || self.eth_rpc_url.is_none() {
+        // the state read from the fork is persisted in the on-disk database instead
+        if self.no_storage_caching || self.eth_rpc_url.is_none() || self.db_path.is_some() {
             return None;
         }
         let chain_id = self.get_chain_id();
@@ -978,10 +1008,38 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the path of the on-disk database the chain is persisted in
+    #[must_use]
+    pub fn with_db_path(mut self, db_path: Option<PathBuf>) -> Self {
+        self.db_path = db_path;
+        self
+    }
+
+    /// Sets the format the state is dumped in
+    #[must_use]
+    pub fn with_state_format(mut self, state_format: StateFormat) -> Self {
+        self.state_format = state_format;
+        self
+    }
+
+    /// Sets the credible-layer assertion contracts transactions are validated against
+    #[must_use]
+    pub fn with_assertions(mut self, assertions: Vec<RegisteredAssertion>) -> Self {
+        self.assertions = assertions;
+        self
+    }
+
+    /// Sets the gas limit for executing each assertion function
+    #[must_use]
+    pub fn with_assertion_gas_limit(mut self, assertion_gas_limit: u64) -> Self {
+        self.assertion_gas_limit = assertion_gas_limit;
+        self
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
-    /// *Note*: only memory based backend for now
+    /// The state is kept in memory, or on disk if a database path is configured.
     pub(crate) async fn setup(&mut self) -> Result<mem::Backend> {
         // configure the revm environment
 
@@ -1078,6 +1136,74 @@ impl NodeConfig {
             backend.load_state(state).await.wrap_err("failed to load init state")?;
         }
 
+        // persist the chain on disk, if configured
+        if let Some(db_path) = self.db_path.clone() {
+            let max_states = self.max_persisted_states.unwrap_or(DEFAULT_HISTORY_LIMIT);
+            let fork = backend.get_fork().map(|fork| {
+                let config = fork.config.read();
+                let meta = BlockchainDbMeta::new(
+                    *backend.env().read().env.clone(),
+                    config.eth_rpc_url.clone(),
+                );
+                let db = SharedBackend::spawn_backend_thread(
+                    Arc::clone(&config.provider),
+                    BlockchainDb::new(meta, None),
+                    Some(config.block_number.into()),
+                );
+                DiskDbFork {
+                    db: Arc::new(db),
+                    block_number: config.block_number,
+                    block_hash: config.block_hash,
+                }
+            });
+            let mut disk_db = DiskDb::open(&db_path, max_states, fork)
+                .wrap_err_with(|| format!("failed to open database at {}", db_path.display()))?;
+            let chain = disk_db.persisted_chain().wrap_err("failed to read persisted chain")?;
+            let mut db = backend.get_db().write().await;
+            if let Some(chain) = chain {
+                if self.init_state.is_some() {
+                    warn!(target: "node", "ignoring the init state, restoring the persisted chain");
+                }
+                *db = Box::new(disk_db);
+                drop(db);
+                backend.restore_persisted_chain(chain).await;
+            } else if self.eth_rpc_url.is_some() {
+                // write the state changed since forking on disk, the state read from the fork so
+                // far is fetched again when needed and persisted then
+                let accounts = db
+                    .maybe_as_full_db()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, account)| account.account_state != AccountState::NotExisting)
+                    .map(|(address, account)| (*address, account.clone()))
+                    .collect::<Vec<_>>();
+                disk_db.init_from_state_snapshot(StateSnapshot {
+                    storage: accounts
+                        .iter()
+                        .map(|(address, account)| (*address, account.storage.clone()))
+                        .collect(),
+                    accounts: accounts
+                        .into_iter()
+                        .map(|(address, account)| (address, account.info))
+                        .collect(),
+                    block_hashes: Default::default(),
+                });
+                disk_db
+                    .persist_fork_block(backend.env().read().block.clone())
+                    .wrap_err("failed to persist fork block")?;
+                *db = Box::new(disk_db);
+            } else {
+                // write the genesis state on disk
+                disk_db.init_from_state_snapshot(db.clear_into_state_snapshot());
+                *db = Box::new(disk_db);
+                drop(db);
+                backend
+                    .persist_block(backend.best_hash())
+                    .await
+                    .wrap_err("failed to persist genesis block")?;
+            }
+        }
+
         Ok(backend)
     }
 
@@ -1099,6 +1225,63 @@ impl NodeConfig {
         Ok((db, Some(fork)))
     }
 
+    /// Returns a [ProviderBuilder] for the given fork endpoint, configured with the fork request
+    /// settings.
+    fn fork_provider_builder(&self, url: &str) -> ProviderBuilder {
+        ProviderBuilder::new(url)
+            .timeout(self.fork_request_timeout)
+            .initial_backoff(self.fork_retry_backoff.as_millis() as u64)
+            .compute_units_per_second(self.compute_units_per_second)
+            .max_retry(self.fork_request_retries)
+            .initial_backoff(1000)
+            .headers(self.fork_headers.clone())
+    }
+
+    /// Ensures that every fork endpoint agrees on the hash of the block we fork off, so requests
+    /// can be load-balanced across them.
+    ///
+    /// Endpoints that are unreachable or don't have the block yet are only warned about, they are
+    /// taken out of rotation by the provider if they keep failing.
+    async fn verify_fork_block_hash(
+        &self,
+        urls: &[&str],
+        fork_block_number: u64,
+        block_hash: B256,
+    ) -> Result<()> {
+        for url in urls {
+            let provider = self
+                .fork_provider_builder(url)
+                .build()
+                .wrap_err_with(|| format!("failed to establish provider to fork url {url}"))?;
+            match provider
+                .get_block(BlockNumberOrTag::Number(fork_block_number).into(), false.into())
+                .await
+            {
+                Ok(Some(block)) => {
+                    if block.header.hash != block_hash {
+                        eyre::bail!(
+                            "fork endpoints disagree on the hash of block {fork_block_number}: \
+                             {url} returned {}, expected {block_hash}",
+                            block.header.hash
+                        );
+                    }
+                }
+                Ok(None) => {
+                    warn!(
+                        target: "node",
+                        %url,
+                        fork_block_number,
+                        "fork endpoint doesn't have the fork block"
+                    );
+                }
+                Err(err) => {
+                    warn!(target: "node", %url, %err, "failed to fetch fork block from endpoint");
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
     ///  - returning a tuple of a [ForkedDatabase] and [ClientForkConfig] which can be used to build
     ///    a [ClientFork] to fork from.
@@ -1110,15 +1293,18 @@ impl NodeConfig {
         env: &mut EnvWithHandlerCfg,
         fees: &FeeManager,
     ) -> Result<(ForkedDatabase, ClientForkConfig)> {
+        // the fallback endpoints belong to the configured fork url, not to urls passed when
+        // resetting the fork
+        let fallback_urls = if self.eth_rpc_url.as_ref() == Some(&eth_rpc_url) {
+            self.fork_fallback_urls.clone()
+        } else {
+            vec![]
+        };
+
         // TODO make provider agnostic
         let provider = Arc::new(
-            ProviderBuilder::new(&eth_rpc_url)
-                .timeout(self.fork_request_timeout)
-                .initial_backoff(self.fork_retry_backoff.as_millis() as u64)
-                .compute_units_per_second(self.compute_units_per_second)
-                .max_retry(self.fork_request_retries)
-                .initial_backoff(1000)
-                .headers(self.fork_headers.clone())
+            self.fork_provider_builder(&eth_rpc_url)
+                .fallback_urls(&fallback_urls)
                 .build()
                 .wrap_err("failed to establish provider to fork url")?,
         );
@@ -1232,6 +1418,13 @@ latest block number: {latest_block}"
 
         let block_hash = block.header.hash;
 
+        if !fallback_urls.is_empty() {
+            let urls: Vec<_> = std::iter::once(eth_rpc_url.as_str())
+                .chain(fallback_urls.iter().map(String::as_str))
+                .collect();
+            self.verify_fork_block_hash(&urls, fork_block_number, block_hash).await?;
+        }
+
         let chain_id = if let Some(chain_id) = self.chain_id {
             chain_id
         } else {
@@ -6,6 +6,7 @@ use crate::{
     eth::{
         backend::{
             self,
+            assertions::RegisteredAssertion,
             db::SerializableState,
             mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
             notifications::NewBlockNotifications,
@@ -464,6 +465,12 @@ impl EthApi {
             EthRequest::AnvilSetExecutor(executor_pk) => {
                 self.anvil_set_executor(executor_pk).to_rpc_result()
             }
+            EthRequest::AnvilRegisterAssertion(adopter, code, label, signatures) => {
+                self.anvil_register_assertion(adopter, code, label, signatures).to_rpc_result()
+            }
+            EthRequest::AnvilRemoveAssertion(adopter) => {
+                self.anvil_remove_assertion(adopter).to_rpc_result()
+            }
         }
     }
 
@@ -1949,6 +1956,38 @@ impl EthApi {
         Ok(())
     }
 
+    /// Registers a credible-layer assertion contract for an adopter address.
+    ///
+    /// Transactions which invalidate the assertion are rejected and not mined. The optional
+    /// function signatures are used to name the assertion functions a transaction invalidated.
+    ///
+    /// Handler for RPC call: `anvil_registerAssertion`
+    pub fn anvil_register_assertion(
+        &self,
+        adopter: Address,
+        code: Bytes,
+        label: Option<String>,
+        signatures: Option<Vec<String>>,
+    ) -> Result<()> {
+        node_info!("anvil_registerAssertion");
+        let mut assertion = RegisteredAssertion::new(adopter, code, label);
+        for signature in signatures.unwrap_or_default() {
+            assertion.add_function(&signature).map_err(RpcError::invalid_params)?;
+        }
+        self.backend.register_assertion(assertion).map_err(RpcError::invalid_params)?;
+        Ok(())
+    }
+
+    /// Removes the credible-layer assertion contract registered for an adopter address.
+    ///
+    /// Returns `true` if an assertion was registered for the adopter.
+    ///
+    /// Handler for RPC call: `anvil_removeAssertion`
+    pub fn anvil_remove_assertion(&self, adopter: Address) -> Result<bool> {
+        node_info!("anvil_removeAssertion");
+        Ok(self.backend.assertions().remove(&adopter))
+    }
+
     /// Reorg the chain to a specific depth and mine new blocks back to the canonical height.
     ///
     /// e.g depth = 3
@@ -0,0 +1,366 @@
+//! Support for enforcing credible-layer assertions on transactions
+
+use crate::eth::error::InvalidTransactionError;
+use alloy_json_abi::Function;
+use alloy_primitives::{
+    map::{AddressHashMap, B256HashMap},
+    Address, Bytes, Selector, TxHash, B256,
+};
+use assertion_executor::{
+    db::fork_db::ForkDb,
+    store::{AssertionState, AssertionStore},
+    ExecutorConfig, ExecutorError,
+};
+use foundry_evm::{
+    backend::DatabaseError,
+    decode::RevertDecoder,
+    revm::{
+        primitives::{EnvWithHandlerCfg, ResultAndState, SpecId},
+        DatabaseRef,
+    },
+};
+use parking_lot::RwLock;
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// An assertion contract registered for an adopter address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredAssertion {
+    /// The address of the contract the assertions protect.
+    pub adopter: Address,
+    /// The creation code of the assertion contract, including constructor arguments.
+    pub code: Bytes,
+    /// The label of the assertion contract, used when reporting rejected transactions.
+    pub label: String,
+    /// The known assertion functions of the contract, used to name the functions a rejected
+    /// transaction invalidated.
+    pub functions: Vec<Function>,
+}
+
+impl RegisteredAssertion {
+    /// Creates a new assertion, labeled with the adopter address if no label is given.
+    pub fn new(adopter: Address, code: Bytes, label: Option<String>) -> Self {
+        let label = label.unwrap_or_else(|| adopter.to_string());
+        Self { adopter, code, label, functions: Vec::new() }
+    }
+
+    /// Adds an assertion function, given by its signature, e.g. `assertOwner()`.
+    pub fn add_function(&mut self, signature: &str) -> Result<(), String> {
+        let function = Function::parse(signature)
+            .map_err(|err| format!("invalid assertion function signature `{signature}`: {err}"))?;
+        self.functions.push(function);
+        Ok(())
+    }
+
+    /// Returns the name of the assertion function with the given selector, falling back to the
+    /// selector if the function is unknown.
+    fn function_name(&self, selector: Selector) -> String {
+        self.functions
+            .iter()
+            .find(|function| function.selector() == selector)
+            .map_or_else(|| selector.to_string(), |function| function.signature())
+    }
+}
+
+impl FromStr for RegisteredAssertion {
+    type Err = String;
+
+    /// Parses an assertion in the form `<ADOPTER>:<CREATION_CODE>[:<LABEL>[:<SIGNATURES>]]`,
+    /// where the assertion function signatures are separated by `;`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(4, ':');
+        let (Some(adopter), Some(code)) = (parts.next(), parts.next()) else {
+            return Err(format!(
+                "expected `<ADOPTER>:<CREATION_CODE>[:<LABEL>[:<SIGNATURES>]]`, got `{s}`"
+            ));
+        };
+        let adopter =
+            adopter.parse().map_err(|err| format!("invalid adopter address `{adopter}`: {err}"))?;
+        let code = code.parse().map_err(|err| format!("invalid assertion creation code: {err}"))?;
+        let mut assertion = Self::new(adopter, code, parts.next().map(str::to_string));
+        for signature in parts.next().into_iter().flat_map(|s| s.split(';')) {
+            assertion.add_function(signature)?;
+        }
+        Ok(assertion)
+    }
+}
+
+/// Manages the assertion contracts that transactions are validated against
+///
+/// A transaction which invalidates any of the registered assertions is rejected, the way a
+/// credible-layer sequencer would drop it.
+#[derive(Clone, Debug)]
+pub struct AssertionsManager {
+    /// shareable state
+    state: Arc<RwLock<AssertionsState>>,
+}
+
+impl AssertionsManager {
+    /// Creates a new manager which deploys the assertion contracts for the given spec and chain id
+    /// and executes each assertion function with the given gas limit
+    pub fn new(spec_id: SpecId, chain_id: u64, assertion_gas_limit: u64) -> Self {
+        let state = AssertionsState {
+            assertions: Default::default(),
+            config: ExecutorConfig { spec_id, chain_id, assertion_gas_limit },
+            store: None,
+            validated: Default::default(),
+        };
+        Self { state: Arc::new(RwLock::new(state)) }
+    }
+
+    /// Registers the assertion contract for its adopter and deploys it
+    ///
+    /// Returns the assertion previously registered for the adopter, if any, or an error if the
+    /// assertion contract can't be deployed, in which case the previous assertion is kept
+    pub fn register(
+        &self,
+        assertion: RegisteredAssertion,
+    ) -> Result<Option<RegisteredAssertion>, String> {
+        trace!(target: "assertions", adopter=?assertion.adopter, label=%assertion.label, "registering assertion");
+        let mut state = self.state.write();
+        state.validated.clear();
+        let previous = state.assertions.insert(assertion.adopter, assertion.clone());
+        if previous.is_some() {
+            // the store can't replace the assertion of an adopter, so all of them are redeployed
+            state.store = None;
+        }
+        let deployed = match state.store.clone() {
+            Some(store) => deploy(&store, &assertion, &state.config),
+            None => state.store().map(drop),
+        };
+        if let Err(err) = deployed {
+            match previous {
+                Some(previous) => state.assertions.insert(assertion.adopter, previous),
+                None => state.assertions.remove(&assertion.adopter),
+            };
+            state.store = None;
+            return Err(err)
+        }
+        Ok(previous)
+    }
+
+    /// Removes the assertion contract registered for the adopter
+    ///
+    /// Returns `true` if an assertion was registered for the adopter
+    pub fn remove(&self, adopter: &Address) -> bool {
+        trace!(target: "assertions", ?adopter, "removing assertion");
+        let mut state = self.state.write();
+        state.validated.clear();
+        let removed = state.assertions.remove(adopter).is_some();
+        if removed {
+            // the store can't remove the assertion of an adopter, the remaining ones are redeployed
+            // when the next transaction is validated
+            state.store = None;
+        }
+        removed
+    }
+
+    /// Returns all registered assertion contracts
+    pub fn assertions(&self) -> Vec<RegisteredAssertion> {
+        self.state.read().assertions.values().cloned().collect()
+    }
+
+    /// Returns true if no assertion contracts are registered
+    pub fn is_empty(&self) -> bool {
+        self.state.read().assertions.is_empty()
+    }
+
+    /// Records that the transaction passed the assertions on top of the block with the given hash,
+    /// so that it is not validated again if it is the first transaction mined on top of it.
+    pub fn mark_validated(&self, tx_hash: TxHash, parent_hash: B256) {
+        self.state.write().validated.insert(tx_hash, parent_hash);
+    }
+
+    /// Returns true if the transaction already passed the assertions on top of the block with the
+    /// given hash.
+    pub fn is_validated(&self, tx_hash: &TxHash, parent_hash: B256) -> bool {
+        self.state.read().validated.get(tx_hash) == Some(&parent_hash)
+    }
+
+    /// Forgets all transactions that passed the assertions, as they were validated on top of a
+    /// state that is no longer the latest.
+    pub fn clear_validated(&self) {
+        self.state.write().validated.clear();
+    }
+
+    /// Returns the executor settings and the store of the deployed assertion contracts for the
+    /// spec and chain id of `env`, or `None` if no assertions are registered.
+    ///
+    /// The assertion contracts are only redeployed if an assertion was removed or the spec or
+    /// chain id changed since they were deployed.
+    fn deployed(
+        &self,
+        env: &EnvWithHandlerCfg,
+    ) -> Result<Option<(ExecutorConfig, AssertionStore)>, String> {
+        let (spec_id, chain_id) = (env.handler_cfg.spec_id, env.cfg.chain_id);
+        {
+            let state = self.state.read();
+            if state.assertions.is_empty() {
+                return Ok(None)
+            }
+            if state.config.spec_id == spec_id && state.config.chain_id == chain_id {
+                if let Some(store) = &state.store {
+                    return Ok(Some((state.config.clone(), store.clone())))
+                }
+            }
+        }
+
+        let mut state = self.state.write();
+        if state.assertions.is_empty() {
+            return Ok(None)
+        }
+        if state.config.spec_id != spec_id || state.config.chain_id != chain_id {
+            state.config.spec_id = spec_id;
+            state.config.chain_id = chain_id;
+            state.store = None;
+        }
+        let store = state.store()?.clone();
+        Ok(Some((state.config.clone(), store)))
+    }
+
+    /// Validates the transaction configured in `env` against the registered assertions on top of
+    /// the given database, without committing it.
+    ///
+    /// Returns the result and state changes of the transaction, which is executed by the assertion
+    /// executor, or `None` if no assertions are registered. Returns an
+    /// [`InvalidTransactionError::AssertionFailed`] naming the assertion functions that the
+    /// transaction invalidated, or an [`InvalidTransactionError::AssertionValidation`] if the
+    /// transaction could not be validated at all.
+    pub fn validate<DB>(
+        &self,
+        db: DB,
+        env: &EnvWithHandlerCfg,
+    ) -> Result<Option<ResultAndState>, InvalidTransactionError>
+    where
+        DB: DatabaseRef<Error = DatabaseError> + Clone + fmt::Debug + Send + Sync,
+    {
+        // Transactions are rejected whenever the assertions can't be checked, so that an assertion
+        // can never be bypassed by a transaction that fails to validate.
+        let Some((config, store)) = self.deployed(env).map_err(|err| {
+            error!(target: "assertions", %err, "failed to deploy assertion contracts");
+            InvalidTransactionError::AssertionValidation(err)
+        })?
+        else {
+            return Ok(None)
+        };
+
+        let mut executor = config.build(db, store);
+        let mut fork_db = ForkDb::new(executor.db.clone());
+        let result = executor
+            .validate_transaction(env.block.clone(), env.tx.clone(), &mut fork_db)
+            .map_err(|err| {
+                let message = match err {
+                    ExecutorError::TxError(err) => {
+                        format!("failed to execute transaction: {err:?}")
+                    }
+                    err => format!("failed to read assertions: {err:?}"),
+                };
+                debug!(target: "assertions", %message, "failed to validate transaction");
+                InvalidTransactionError::AssertionValidation(message)
+            })?;
+
+        if result.is_valid() {
+            return Ok(Some(result.result_and_state))
+        }
+
+        let state = self.state.read();
+        let decoder = RevertDecoder::new();
+        let mut failures = Vec::new();
+        for assertion_contract in &result.assertions_executions {
+            let assertion = state.assertions.get(&assertion_contract.adopter);
+            let label = assertion
+                .map_or_else(|| assertion_contract.adopter.to_string(), |a| a.label.clone());
+            for assertion_fn in
+                assertion_contract.assertion_fns_results.iter().filter(|f| !f.is_success())
+            {
+                let selector = assertion_fn.id.fn_selector;
+                let name =
+                    assertion.map_or_else(|| selector.to_string(), |a| a.function_name(selector));
+                let output = assertion_fn.as_result().clone().into_output().unwrap_or_default();
+                failures
+                    .push(format!("{label}::{name} reverted: {}", decoder.decode(&output, None)));
+            }
+        }
+        Err(InvalidTransactionError::AssertionFailed(failures.join(", ")))
+    }
+}
+
+/// Container type for all the state variables
+#[derive(Clone, Debug)]
+pub struct AssertionsState {
+    /// The assertion contracts registered by adopter address
+    pub assertions: AddressHashMap<RegisteredAssertion>,
+    /// The settings the assertion contracts are deployed and executed with
+    pub config: ExecutorConfig,
+    /// The registered assertion contracts deployed with `config`, if they are up to date
+    store: Option<AssertionStore>,
+    /// The transactions that passed the assertions when they were added to the pool, with the hash
+    /// of the block they were validated on top of
+    pub validated: B256HashMap<B256>,
+}
+
+impl AssertionsState {
+    /// Returns the store of the deployed assertion contracts, deploying all registered assertion
+    /// contracts if it is not up to date
+    fn store(&mut self) -> Result<&AssertionStore, String> {
+        if self.store.is_none() {
+            let store = AssertionStore::new_ephemeral()
+                .map_err(|err| format!("failed to create assertion store: {err}"))?;
+            for assertion in self.assertions.values() {
+                deploy(&store, assertion, &self.config)?;
+            }
+            self.store = Some(store);
+        }
+        Ok(self.store.as_ref().expect("deployed above"))
+    }
+}
+
+/// Deploys the assertion contract into the store
+fn deploy(
+    store: &AssertionStore,
+    assertion: &RegisteredAssertion,
+    config: &ExecutorConfig,
+) -> Result<(), String> {
+    let assertion_state =
+        AssertionState::new_active(assertion.code.clone(), config).map_err(|err| {
+            format!("failed to deploy assertion contract {}: {err:?}", assertion.label)
+        })?;
+    store
+        .insert(assertion.adopter, assertion_state)
+        .map_err(|err| format!("failed to register assertion contract {}: {err}", assertion.label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_parse_registered_assertion() {
+        let adopter = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+
+        let assertion: RegisteredAssertion =
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:0x6080:MockAssertion".parse().unwrap();
+        assert_eq!(assertion.adopter, adopter);
+        assert_eq!(assertion.code, Bytes::from_static(&[0x60, 0x80]));
+        assert_eq!(assertion.label, "MockAssertion");
+        assert!(assertion.functions.is_empty());
+
+        let assertion: RegisteredAssertion =
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:0x6080".parse().unwrap();
+        assert_eq!(assertion.label, adopter.to_string());
+
+        let assertion: RegisteredAssertion =
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:0x6080:MockAssertion:assertIsOne();assertAtMost(uint256,address)"
+                .parse()
+                .unwrap();
+        let assert_is_one = Function::parse("assertIsOne()").unwrap().selector();
+        assert_eq!(assertion.function_name(assert_is_one), "assertIsOne()");
+        assert_eq!(assertion.function_name(Selector::ZERO), Selector::ZERO.to_string());
+        assert_eq!(assertion.functions[1].signature(), "assertAtMost(uint256,address)");
+
+        assert!("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            .parse::<RegisteredAssertion>()
+            .is_err());
+        assert!("0xd8dA:0x6080".parse::<RegisteredAssertion>().is_err());
+    }
+}
@@ -1,6 +1,6 @@
 //! Helper types for working with [revm](foundry_evm::revm)
 
-use crate::{mem::storage::MinedTransaction, revm::primitives::AccountInfo};
+use crate::{eth::backend::dump, mem::storage::MinedTransaction, revm::primitives::AccountInfo};
 use alloy_consensus::Header;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U64};
 use alloy_rpc_types::BlockId;
@@ -24,7 +24,7 @@ use serde::{
     de::{MapAccess, Visitor},
     Deserialize, Deserializer, Serialize,
 };
-use std::{collections::BTreeMap, fmt, path::Path};
+use std::{collections::BTreeMap, fmt, io, path::Path};
 
 /// Helper trait get access to the full state data of the database
 pub trait MaybeFullDatabase: DatabaseRef<Error = DatabaseError> {
@@ -192,6 +192,25 @@ pub trait Db:
 
     /// Returns the current, standalone state of the Db
     fn current_state(&self) -> StateDb;
+
+    /// Persists a mined block and its transactions, and the state changed by the block.
+    ///
+    /// Does nothing unless the database stores the chain on disk.
+    fn persist_block(
+        &mut self,
+        _at: BlockEnv,
+        _block: SerializableBlock,
+        _transactions: Vec<SerializableTransaction>,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Unwinds the persisted blocks after the block `number`, and their state changes.
+    ///
+    /// Does nothing unless the database stores the chain on disk.
+    fn unwind_persisted(&mut self, _number: u64) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl dyn Db {
@@ -401,13 +420,17 @@ pub struct SerializableState {
 }
 
 impl SerializableState {
-    /// Loads the `Genesis` object from the given json file path
+    /// Loads the state from the given json or binary file path
     pub fn load(path: impl AsRef<Path>) -> Result<Self, FsPathError> {
-        let path = path.as_ref();
+        let mut path = path.as_ref().to_path_buf();
         if path.is_dir() {
-            foundry_common::fs::read_json_file(&path.join("state.json"))
+            path = path.join("state.json");
+        }
+        let buf = foundry_common::fs::read(&path)?;
+        if dump::is_binary(&buf) {
+            dump::decode(&buf).map_err(|err| FsPathError::read(err, path))
         } else {
-            foundry_common::fs::read_json_file(path)
+            serde_json::from_slice(&buf).map_err(|source| FsPathError::ReadJson { source, path })
         }
     }
 
@@ -0,0 +1,667 @@
+//! Binary and incremental encodings of [`SerializableState`] dumps
+//!
+//! A binary dump starts with [`MAGIC`] and the [`VERSION`] of the encoding, followed by frames.
+//! Each frame is the kind of the frame, the length of its payload and the gzip compressed payload.
+//! The first frame holds the complete state, each following frame holds the changes since the
+//! frame before it, so that incremental dumps only append to the file. Blocks, transactions and
+//! the block environment are encoded as CBOR, accounts and states as fixed size fields.
+
+use crate::eth::backend::db::{
+    SerializableAccountRecord, SerializableBlock, SerializableHistoricalStates, SerializableState,
+    SerializableTransaction,
+};
+use alloy_primitives::{
+    map::{AddressHashMap, B256HashSet, HashSet},
+    Address, Bytes, B256, U256, U64,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use foundry_evm::{
+    backend::StateSnapshot,
+    revm::primitives::{Account, AccountInfo, BlockEnv, Bytecode, HashMap},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Read, Write},
+    str::FromStr,
+};
+
+/// The bytes binary state dumps start with.
+pub const MAGIC: [u8; 8] = *b"ANVLSTAT";
+
+/// The version of the binary encoding.
+pub const VERSION: u8 = 2;
+
+/// A frame holding the complete state.
+const FULL_FRAME: u8 = 0;
+/// A frame holding the changes since the previous frame.
+const DELTA_FRAME: u8 = 1;
+
+/// The format the state is dumped in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StateFormat {
+    /// The whole state as JSON, gzip compressed when returned by `anvil_dumpState`.
+    #[default]
+    Json,
+    /// The whole state in the binary encoding.
+    Binary,
+    /// The binary encoding, where each periodic dump appends the changes since the last dump.
+    Incremental,
+}
+
+impl FromStr for StateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "binary" | "bin" => Ok(Self::Binary),
+            "incremental" => Ok(Self::Incremental),
+            _ => Err(format!("unknown state format `{s}`, expected json, binary or incremental")),
+        }
+    }
+}
+
+impl fmt::Display for StateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => f.write_str("json"),
+            Self::Binary => f.write_str("binary"),
+            Self::Incremental => f.write_str("incremental"),
+        }
+    }
+}
+
+/// Returns true if `buf` is a binary state dump.
+pub fn is_binary(buf: &[u8]) -> bool {
+    buf.starts_with(&MAGIC)
+}
+
+/// Encodes the complete state as a binary dump.
+pub fn encode(state: SerializableState) -> io::Result<Vec<u8>> {
+    let SerializableState {
+        block,
+        accounts,
+        best_block_number,
+        blocks,
+        transactions,
+        historical_states,
+    } = state;
+    let delta = StateDelta {
+        block,
+        best_block_number,
+        accounts: accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let SerializableAccountRecord { nonce, balance, code, storage } = account;
+                (
+                    address,
+                    Some(AccountDelta { nonce, balance, code: Some(code), wipe: true, storage }),
+                )
+            })
+            .collect(),
+        pruned: Vec::new(),
+        blocks,
+        transactions,
+        historical_states: historical_states.into_iter().flatten().collect(),
+    };
+
+    let mut frame = Writer::default();
+    frame.put(&MAGIC);
+    frame.u8(VERSION);
+    frame.frame(FULL_FRAME, &delta)?;
+    Ok(frame.0)
+}
+
+/// Encodes the changes since the previous dump as a frame to append to a binary dump.
+pub fn encode_delta(delta: &StateDelta) -> io::Result<Vec<u8>> {
+    let mut frame = Writer::default();
+    frame.frame(DELTA_FRAME, delta)?;
+    Ok(frame.0)
+}
+
+/// Decodes a binary dump, applying the changes of all its frames.
+///
+/// A truncated last frame, left by an interrupted incremental dump, is skipped.
+pub fn decode(buf: &[u8]) -> io::Result<SerializableState> {
+    let mut reader = Reader(buf);
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(invalid_data("not a binary state dump"))
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(invalid_data(format!("unsupported state dump version {version}")))
+    }
+
+    let mut state = SerializableState::default();
+    let mut states = Vec::new();
+    while !reader.0.is_empty() {
+        let (kind, payload) = match (reader.u8(), reader.bytes()) {
+            (Ok(kind), Ok(payload)) => (kind, payload),
+            _ => {
+                warn!(target: "backend", "skipping truncated frame of state dump");
+                break
+            }
+        };
+        let mut body = Vec::new();
+        GzDecoder::new(payload).read_to_end(&mut body)?;
+        match kind {
+            FULL_FRAME => {
+                state = SerializableState::default();
+                states.clear();
+            }
+            DELTA_FRAME => {}
+            _ => return Err(invalid_data(format!("unknown state dump frame {kind}"))),
+        }
+        apply_frame(&mut Reader(&body), &mut state, &mut states)?;
+    }
+    if !states.is_empty() {
+        state.historical_states = Some(SerializableHistoricalStates::new(states));
+    }
+    Ok(state)
+}
+
+/// An encoded dump to write.
+#[derive(Debug)]
+pub enum DumpChunk {
+    /// A complete dump, which replaces the file.
+    Full(Vec<u8>),
+    /// A frame to append to the file, empty if nothing changed.
+    Delta(Vec<u8>),
+}
+
+/// The accounts, blocks and states which changed since the last incremental dump.
+///
+/// The backend records them as blocks are mined and the state is modified, so that a dump only
+/// reads what changed instead of comparing the whole state with the previous dump.
+#[derive(Clone, Debug, Default)]
+pub struct StateChanges {
+    /// The changed accounts.
+    pub accounts: AddressHashMap<ChangedAccount>,
+    /// The hashes of the mined blocks, in order.
+    pub blocks: Vec<B256>,
+    /// The hashes of the blocks whose transactions were pruned.
+    pub pruned: Vec<B256>,
+    /// The hashes of the blocks whose states were added to the historical states.
+    pub states: Vec<B256>,
+    /// Whether the state was replaced, e.g. by reverting to a state snapshot or by a reorg, so
+    /// that the next dump must be a complete one.
+    pub replaced: bool,
+}
+
+/// The changes of an account since the last incremental dump.
+#[derive(Clone, Debug, Default)]
+pub struct ChangedAccount {
+    /// The changed storage slots.
+    pub slots: HashSet<U256>,
+    /// Whether the code changed.
+    pub code: bool,
+    /// Whether the previous storage was wiped, i.e. the account was created or destroyed.
+    pub wipe: bool,
+}
+
+impl StateChanges {
+    /// Returns the changes before the first dump, which is a complete one.
+    pub fn new() -> Self {
+        Self { replaced: true, ..Default::default() }
+    }
+
+    /// Returns true if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        !self.replaced &&
+            self.accounts.is_empty() &&
+            self.blocks.is_empty() &&
+            self.pruned.is_empty() &&
+            self.states.is_empty()
+    }
+
+    /// Records that the whole state was replaced.
+    pub fn replace(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns the changed account, or `None` if the state was replaced anyway.
+    pub fn account(&mut self, address: Address) -> Option<&mut ChangedAccount> {
+        (!self.replaced).then(|| self.accounts.entry(address).or_default())
+    }
+
+    /// Records the changes committed by a transaction.
+    pub fn commit(&mut self, changes: &HashMap<Address, Account>) {
+        for (address, account) in changes {
+            if !account.is_touched() {
+                continue
+            }
+            let Some(changed) = self.account(*address) else { return };
+            let created = account.is_created() || account.is_selfdestructed();
+            changed.code |= created;
+            changed.wipe |= created;
+            changed.slots.extend(
+                account.storage.iter().filter(|(_, slot)| slot.is_changed()).map(|(key, _)| *key),
+            );
+        }
+    }
+
+    /// Adds the changes recorded after these.
+    pub fn extend(&mut self, changes: Self) {
+        if changes.replaced {
+            self.replace();
+        }
+        if self.replaced {
+            return
+        }
+        for (address, account) in changes.accounts {
+            let changed = self.accounts.entry(address).or_default();
+            // the slots changed before a wipe are wiped as well
+            if account.wipe {
+                changed.slots.clear();
+            }
+            changed.slots.extend(account.slots);
+            changed.code |= account.code;
+            changed.wipe |= account.wipe;
+        }
+        self.blocks.extend(changes.blocks);
+        self.pruned.extend(changes.pruned);
+        self.states.extend(changes.states);
+    }
+}
+
+/// The changes since the previous dump, as encoded in a frame.
+#[derive(Debug, Default)]
+pub struct StateDelta {
+    /// The block environment.
+    pub block: Option<BlockEnv>,
+    /// The number of the best block.
+    pub best_block_number: Option<U64>,
+    /// The changed accounts, `None` for removed accounts.
+    pub accounts: Vec<(Address, Option<AccountDelta>)>,
+    /// The hashes of the blocks whose transactions were pruned.
+    pub pruned: Vec<B256>,
+    /// The new blocks.
+    pub blocks: Vec<SerializableBlock>,
+    /// The transactions of the new blocks.
+    pub transactions: Vec<SerializableTransaction>,
+    /// The new historical states.
+    pub historical_states: Vec<(B256, StateSnapshot)>,
+}
+
+/// The changes of an account since the previous dump.
+#[derive(Debug)]
+pub struct AccountDelta {
+    /// The nonce.
+    pub nonce: u64,
+    /// The balance.
+    pub balance: U256,
+    /// The code, if it changed.
+    pub code: Option<Bytes>,
+    /// Whether the previous storage is cleared before applying `storage`.
+    pub wipe: bool,
+    /// The changed storage slots.
+    pub storage: BTreeMap<B256, B256>,
+}
+
+/// Applies the changes of a frame to `state` and the historical `states`.
+fn apply_frame(
+    reader: &mut Reader<'_>,
+    state: &mut SerializableState,
+    states: &mut Vec<(B256, StateSnapshot)>,
+) -> io::Result<()> {
+    if let Some(block) = reader.cbor()? {
+        state.block = Some(block);
+    }
+    let best_block_number = reader.u64()?;
+    if best_block_number != u64::MAX {
+        state.best_block_number = Some(U64::from(best_block_number));
+    }
+
+    for _ in 0..reader.u64()? {
+        let address = Address::from_slice(reader.take(20)?);
+        if reader.u8()? == 0 {
+            state.accounts.remove(&address);
+            continue
+        }
+        let account = state.accounts.entry(address).or_insert_with(|| SerializableAccountRecord {
+            nonce: 0,
+            balance: U256::ZERO,
+            code: Bytes::new(),
+            storage: BTreeMap::new(),
+        });
+        account.nonce = reader.u64()?;
+        account.balance = reader.word()?;
+        if reader.u8()? != 0 {
+            account.code = reader.bytes()?.to_vec().into();
+        }
+        if reader.u8()? != 0 {
+            account.storage.clear();
+        }
+        for _ in 0..reader.u64()? {
+            let slot = B256::from_slice(reader.take(32)?);
+            let value = B256::from_slice(reader.take(32)?);
+            if value.is_zero() {
+                account.storage.remove(&slot);
+            } else {
+                account.storage.insert(slot, value);
+            }
+        }
+    }
+
+    let mut pruned = B256HashSet::default();
+    for _ in 0..reader.u64()? {
+        pruned.insert(B256::from_slice(reader.take(32)?));
+    }
+    if !pruned.is_empty() {
+        state.transactions.retain(|transaction| !pruned.contains(&transaction.block_hash));
+        for block in &mut state.blocks {
+            if pruned.contains(&block.header.hash_slow()) {
+                block.transactions.clear();
+            }
+        }
+    }
+
+    for _ in 0..reader.u64()? {
+        state.blocks.push(reader.cbor()?.ok_or_else(|| invalid_data("block"))?);
+    }
+    for _ in 0..reader.u64()? {
+        state.transactions.push(reader.cbor()?.ok_or_else(|| invalid_data("transaction"))?);
+    }
+    for _ in 0..reader.u64()? {
+        let hash = B256::from_slice(reader.take(32)?);
+        states.push((hash, reader.snapshot()?));
+    }
+    Ok(())
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid state dump: {}", msg.into()))
+}
+
+/// Writes the binary encoding.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn put(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.put(&value.to_be_bytes());
+    }
+
+    fn word(&mut self, value: U256) {
+        self.put(&value.to_be_bytes::<32>());
+    }
+
+    /// Writes length prefixed bytes.
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.u64(bytes.len() as u64);
+        self.put(bytes);
+    }
+
+    /// Writes a value as length prefixed CBOR, or an empty value for `None`.
+    fn cbor<T: Serialize>(&mut self, value: Option<&T>) -> io::Result<()> {
+        match value {
+            Some(value) => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).map_err(io::Error::other)?;
+                self.bytes(&buf)
+            }
+            None => self.bytes(&[]),
+        }
+        Ok(())
+    }
+
+    /// Writes a frame of the given kind holding `delta`.
+    fn frame(&mut self, kind: u8, delta: &StateDelta) -> io::Result<()> {
+        let mut body = Self::default();
+        body.cbor(delta.block.as_ref())?;
+        body.u64(delta.best_block_number.map(|number| number.to::<u64>()).unwrap_or(u64::MAX));
+
+        body.u64(delta.accounts.len() as u64);
+        for (address, account) in &delta.accounts {
+            body.put(address.as_slice());
+            let Some(account) = account else {
+                body.u8(0);
+                continue
+            };
+            body.u8(1);
+            body.u64(account.nonce);
+            body.word(account.balance);
+            match &account.code {
+                Some(code) => {
+                    body.u8(1);
+                    body.bytes(code);
+                }
+                None => body.u8(0),
+            }
+            body.u8(u8::from(account.wipe));
+            body.u64(account.storage.len() as u64);
+            for (slot, value) in &account.storage {
+                body.put(slot.as_slice());
+                body.put(value.as_slice());
+            }
+        }
+
+        body.u64(delta.pruned.len() as u64);
+        for hash in &delta.pruned {
+            body.put(hash.as_slice());
+        }
+        body.u64(delta.blocks.len() as u64);
+        for block in &delta.blocks {
+            body.cbor(Some(block))?;
+        }
+        body.u64(delta.transactions.len() as u64);
+        for transaction in &delta.transactions {
+            body.cbor(Some(transaction))?;
+        }
+        body.u64(delta.historical_states.len() as u64);
+        for (hash, snapshot) in &delta.historical_states {
+            body.put(hash.as_slice());
+            body.snapshot(snapshot);
+        }
+
+        self.u8(kind);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body.0)?;
+        self.bytes(&encoder.finish()?);
+        Ok(())
+    }
+
+    fn snapshot(&mut self, snapshot: &StateSnapshot) {
+        self.u64(snapshot.accounts.len() as u64);
+        for (address, info) in &snapshot.accounts {
+            self.put(address.as_slice());
+            self.u64(info.nonce);
+            self.word(info.balance);
+            self.put(info.code_hash.as_slice());
+            match &info.code {
+                Some(code) => {
+                    self.u8(1);
+                    self.bytes(&code.original_bytes());
+                }
+                None => self.u8(0),
+            }
+        }
+        self.u64(snapshot.storage.len() as u64);
+        for (address, storage) in &snapshot.storage {
+            self.put(address.as_slice());
+            self.u64(storage.len() as u64);
+            for (slot, value) in storage {
+                self.word(*slot);
+                self.word(*value);
+            }
+        }
+        self.u64(snapshot.block_hashes.len() as u64);
+        for (number, hash) in &snapshot.block_hashes {
+            self.word(*number);
+            self.put(hash.as_slice());
+        }
+    }
+}
+
+/// Reads the binary encoding.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("unexpected end of data"))
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().expect("8 bytes")))
+    }
+
+    fn word(&mut self) -> io::Result<U256> {
+        Ok(U256::from_be_slice(self.take(32)?))
+    }
+
+    fn bytes(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u64()?;
+        self.take(usize::try_from(len).map_err(|_| invalid_data("length overflow"))?)
+    }
+
+    fn cbor<T: DeserializeOwned>(&mut self) -> io::Result<Option<T>> {
+        let bytes = self.bytes()?;
+        if bytes.is_empty() {
+            return Ok(None)
+        }
+        Ok(Some(ciborium::from_reader(bytes).map_err(|err| invalid_data(err.to_string()))?))
+    }
+
+    fn snapshot(&mut self) -> io::Result<StateSnapshot> {
+        let mut accounts = AddressHashMap::default();
+        for _ in 0..self.u64()? {
+            let address = Address::from_slice(self.take(20)?);
+            let nonce = self.u64()?;
+            let balance = self.word()?;
+            let code_hash = B256::from_slice(self.take(32)?);
+            let code = if self.u8()? != 0 {
+                Some(Bytecode::new_raw(self.bytes()?.to_vec().into()))
+            } else {
+                None
+            };
+            accounts.insert(address, AccountInfo { balance, nonce, code_hash, code });
+        }
+        let mut storage = AddressHashMap::default();
+        for _ in 0..self.u64()? {
+            let address = Address::from_slice(self.take(20)?);
+            let mut slots = HashMap::default();
+            for _ in 0..self.u64()? {
+                slots.insert(self.word()?, self.word()?);
+            }
+            storage.insert(address, slots);
+        }
+        let mut block_hashes = HashMap::default();
+        for _ in 0..self.u64()? {
+            block_hashes.insert(self.word()?, B256::from_slice(self.take(32)?));
+        }
+        Ok(StateSnapshot { accounts, storage, block_hashes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(balance: u64, storage: &[(u8, u8)]) -> SerializableAccountRecord {
+        SerializableAccountRecord {
+            nonce: 1,
+            balance: U256::from(balance),
+            code: Bytes::from_static(&[0x60, 0x00]),
+            storage: storage
+                .iter()
+                .map(|(slot, value)| (B256::with_last_byte(*slot), B256::with_last_byte(*value)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_incremental_dump_reload_cycle() {
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let state = SerializableState {
+            best_block_number: Some(U64::from(1)),
+            accounts: BTreeMap::from([
+                (alice, account(10, &[(1, 1), (2, 2)])),
+                (bob, account(5, &[(1, 1)])),
+            ]),
+            ..Default::default()
+        };
+        let mut buf = encode(state).unwrap();
+        assert!(is_binary(&buf));
+
+        let delta = StateDelta {
+            best_block_number: Some(U64::from(2)),
+            accounts: vec![
+                (
+                    alice,
+                    Some(AccountDelta {
+                        nonce: 2,
+                        balance: U256::from(20),
+                        code: None,
+                        wipe: false,
+                        storage: BTreeMap::from([
+                            (B256::with_last_byte(1), B256::with_last_byte(3)),
+                            (B256::with_last_byte(2), B256::ZERO),
+                        ]),
+                    }),
+                ),
+                (bob, None),
+            ],
+            ..Default::default()
+        };
+        let delta = encode_delta(&delta).unwrap();
+        buf.extend(&delta);
+
+        let loaded = decode(&buf).unwrap();
+        assert_eq!(loaded.best_block_number, Some(U64::from(2)));
+        assert_eq!(loaded.accounts.len(), 1);
+        let loaded_alice = &loaded.accounts[&alice];
+        assert_eq!(loaded_alice.nonce, 2);
+        assert_eq!(loaded_alice.balance, U256::from(20));
+        assert_eq!(loaded_alice.code, Bytes::from_static(&[0x60, 0x00]));
+        assert_eq!(
+            loaded_alice.storage,
+            BTreeMap::from([(B256::with_last_byte(1), B256::with_last_byte(3))])
+        );
+
+        // an interrupted append leaves the dump readable
+        buf.extend(&delta[..delta.len() / 2]);
+        assert_eq!(decode(&buf).unwrap().best_block_number, Some(U64::from(2)));
+    }
+
+    #[test]
+    fn test_state_changes_wipe_earlier_slots() {
+        let alice = Address::repeat_byte(1);
+        let mut changes = StateChanges::default();
+        changes.account(alice).unwrap().slots.insert(U256::from(1));
+
+        let mut later = StateChanges::default();
+        let changed = later.account(alice).unwrap();
+        changed.wipe = true;
+        changed.slots.insert(U256::from(2));
+        later.blocks.push(B256::with_last_byte(1));
+        changes.extend(later);
+
+        let changed = &changes.accounts[&alice];
+        assert!(changed.wipe);
+        assert_eq!(changed.slots, HashSet::from_iter([U256::from(2)]));
+        assert_eq!(changes.blocks, vec![B256::with_last_byte(1)]);
+
+        changes.replace();
+        assert!(changes.account(alice).is_none());
+        assert!(!changes.is_empty());
+    }
+}
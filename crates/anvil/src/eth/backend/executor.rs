@@ -1,6 +1,9 @@
 use crate::{
     eth::{
-        backend::{db::Db, validate::TransactionValidator},
+        backend::{
+            assertions::AssertionsManager, db::Db, dump::StateChanges,
+            validate::TransactionValidator,
+        },
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
@@ -24,7 +27,7 @@ use foundry_evm::{
         interpreter::InstructionResult,
         primitives::{
             BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult, Output,
-            SpecId,
+            ResultAndState, SpecId,
         },
     },
     traces::CallTraceNode,
@@ -87,6 +90,8 @@ pub struct ExecutedTransactions {
     /// All transactions that were invalid at the point of their execution and were not included in
     /// the block
     pub invalid: Vec<Arc<PoolTransaction>>,
+    /// The accounts and storage slots changed by the included transactions
+    pub changes: StateChanges,
 }
 
 /// An executor for a series of transactions
@@ -110,6 +115,10 @@ pub struct TransactionExecutor<'a, Db: ?Sized, V: TransactionValidator> {
     pub print_logs: bool,
     /// Precompiles to inject to the EVM.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Credible-layer assertions the transactions must not invalidate.
+    pub assertions: &'a AssertionsManager,
+    /// The accounts and storage slots changed by the executed transactions.
+    pub changes: StateChanges,
 }
 
 impl<DB: Db + ?Sized, V: TransactionValidator> TransactionExecutor<'_, DB, V> {
@@ -237,7 +246,8 @@ impl<DB: Db + ?Sized, V: TransactionValidator> TransactionExecutor<'_, DB, V> {
 
         let block = Block::new(partial_header, transactions.clone());
         let block = BlockInfo { block, transactions: transaction_infos, receipts };
-        ExecutedTransactions { block, included, invalid }
+        let changes = std::mem::take(&mut self.changes);
+        ExecutedTransactions { block, included, invalid, changes }
     }
 
     fn env_for(&self, tx: &PendingTransaction) -> EnvWithHandlerCfg {
@@ -302,6 +312,29 @@ impl<DB: Db + ?Sized, V: TransactionValidator> Iterator for &mut TransactionExec
             return Some(TransactionExecutionOutcome::Invalid(transaction, err))
         }
 
+        // drop transactions which invalidate a registered assertion, unless they already passed
+        // them on this very state when they were added to the pool
+        let validated = self.gas_used == 0 &&
+            self.assertions.is_validated(&transaction.hash(), self.parent_hash);
+        let validated_result = if validated {
+            None
+        } else {
+            match self.assertions.validate(&*self.db, &env) {
+                Ok(result_and_state) => result_and_state,
+                Err(err) => {
+                    warn!(target: "backend", "Skipping tx rejected by assertion [{:?}] {}", transaction.hash(), err);
+                    return Some(TransactionExecutionOutcome::Invalid(transaction, err))
+                }
+            }
+        };
+        // the assertion executor already executed the transaction on this very state, so its
+        // result is used instead of executing it again, unless the chain depends on custom
+        // precompiles or the optimism handler which the assertion executor doesn't have. The
+        // assertion executor doesn't record call traces, so the transaction has none.
+        let reuse_validated = self.precompile_factory.is_none() &&
+            !self.odyssey &&
+            !self.cfg_env.handler_cfg.is_optimism;
+
         let nonce = account.nonce;
 
         // records all call and step traces
@@ -313,16 +346,20 @@ impl<DB: Db + ?Sized, V: TransactionValidator> Iterator for &mut TransactionExec
             inspector = inspector.with_log_collector();
         }
 
-        let exec_result = {
+        let ResultAndState { result: exec_result, state } = if let Some(result_and_state) =
+            validated_result.filter(|_| reuse_validated)
+        {
+            trace!(target: "backend", "[{:?}] executed by the assertion executor", transaction.hash());
+            result_and_state
+        } else {
             let mut evm = new_evm_with_inspector(&mut *self.db, env, &mut inspector, self.odyssey);
             if let Some(factory) = &self.precompile_factory {
                 inject_precompiles(&mut evm, factory.precompiles());
             }
 
             trace!(target: "backend", "[{:?}] executing", transaction.hash());
-            // transact and commit the transaction
-            match evm.transact_commit() {
-                Ok(exec_result) => exec_result,
+            match evm.transact() {
+                Ok(result_and_state) => result_and_state,
                 Err(err) => {
                     warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
                     match err {
@@ -345,6 +382,9 @@ impl<DB: Db + ?Sized, V: TransactionValidator> Iterator for &mut TransactionExec
                 }
             }
         };
+        // record and commit the changes of the transaction
+        self.changes.commit(&state);
+        self.db.commit(state);
         inspector.print_logs();
 
         let (exit_reason, gas_used, out, logs) = match exec_result {
@@ -0,0 +1,1077 @@
+//! A database which persists the chain on disk
+
+use crate::{
+    eth::backend::db::{
+        Db, MaybeForkedDatabase, MaybeFullDatabase, SerializableAccountRecord, SerializableBlock,
+        SerializableHistoricalStates, SerializableState, SerializableTransaction, StateDb,
+    },
+    revm::primitives::AccountInfo,
+};
+use alloy_primitives::{
+    keccak256,
+    map::{AddressHashMap, HashMap, HashSet},
+    Address, B256, U256, U64,
+};
+use alloy_rpc_types::BlockId;
+use foundry_evm::{
+    backend::{
+        BlockchainDb, DatabaseError, DatabaseResult, RevertStateSnapshotAction, StateSnapshot,
+    },
+    revm::primitives::{Account, BlockEnv, Bytecode, KECCAK_EMPTY},
+    state_snapshot::StateSnapshots,
+};
+use revm::{
+    db::{AccountState, CacheDB, DbAccount},
+    Database, DatabaseCommit, DatabaseRef,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The key of the number and hash of the best block in the metadata tree.
+const BEST_BLOCK_KEY: &[u8] = b"best_block";
+/// The key of the block environment in the metadata tree.
+const BLOCK_ENV_KEY: &[u8] = b"block_env";
+/// The key of the number and hash of the block the chain was forked off in the metadata tree.
+const FORK_BLOCK_KEY: &[u8] = b"fork_block";
+
+/// The length of an encoded account, see [`encode_account`].
+const ACCOUNT_LEN: usize = 72;
+/// The record of a deleted account, which is not read from the fork.
+const DELETED_ACCOUNT: &[u8] = &[0];
+
+/// The tag of a changed account in the diff of a block.
+const ACCOUNT_TAG: u8 = 0;
+/// The tag of a changed storage slot in the diff of a block.
+const SLOT_TAG: u8 = 1;
+
+/// A database which keeps the state in an embedded key-value store on disk.
+///
+/// The state is read from the store lazily and cached in memory, like a forked database caches
+/// the state of its remote endpoint. Changes are kept in memory until a block is mined, when the
+/// accounts and storage slots changed since the previous block are written to the store, along
+/// with the block, its transactions and receipts.
+///
+/// For each persisted block, the values the block overwrote are kept as well, for the last
+/// `max_states` blocks. They provide the states at past blocks, and let persisted blocks be
+/// unwound on reverts and reorgs without rewriting the whole state.
+///
+/// In forking mode, the state which was not changed locally is read from the fork, and the
+/// values read are kept in the store as well, so they are not requested again after a restart.
+/// Deleted accounts and wiped storage are recorded explicitly, so the fork is not read through
+/// them.
+pub struct DiskDb {
+    /// The state changed since the last persisted block, and the persisted state read so far.
+    cache: CacheDB<StoreDb>,
+    /// The accounts changed since the last persisted block.
+    dirty: AddressHashMap<DirtyAccount>,
+    /// The number of the last persisted block.
+    best_number: Option<u64>,
+    state_snapshots: StateSnapshots<DiskDbStateSnapshot>,
+    store: StoreDb,
+    /// The path of the key-value store.
+    path: PathBuf,
+    /// The maximum number of past states kept on disk.
+    max_states: usize,
+}
+
+/// The changes of an account since the last persisted block.
+#[derive(Clone, Debug, Default)]
+struct DirtyAccount {
+    /// The changed storage slots.
+    slots: HashSet<U256>,
+    /// Whether the persisted storage of the account is wiped, i.e. it was created or destroyed.
+    wipe: bool,
+}
+
+/// A state snapshot of a [`DiskDb`].
+#[derive(Clone)]
+struct DiskDbStateSnapshot {
+    cache: CacheDB<StoreDb>,
+    dirty: AddressHashMap<DirtyAccount>,
+    /// The number of the last persisted block, the persisted blocks after it are unwound when
+    /// the snapshot is reverted.
+    best_number: Option<u64>,
+}
+
+/// The state of the block a [`DiskDb`] is forked off.
+#[derive(Clone)]
+pub struct DiskDbFork {
+    /// The remote state at the fork block.
+    pub db: Arc<dyn DatabaseRef<Error = DatabaseError> + Send + Sync>,
+    /// The number of the fork block.
+    pub block_number: u64,
+    /// The hash of the fork block.
+    pub block_hash: B256,
+}
+
+/// The chain persisted in a [`DiskDb`].
+pub struct PersistedChain {
+    /// The environment of the best block.
+    pub block_env: BlockEnv,
+    /// The blocks from the genesis block to the best block.
+    pub blocks: Vec<SerializableBlock>,
+    /// The transactions of the blocks, and their receipts.
+    pub transactions: Vec<SerializableTransaction>,
+    /// The states at the blocks whose past state is still kept, by block hash.
+    pub states: Vec<(B256, StateDb)>,
+}
+
+impl DiskDb {
+    /// Opens the key-value store at `path`, creating it if it does not exist, to persist the
+    /// chain and the states of the last `max_states` blocks, on top of the state of the `fork`,
+    /// if any.
+    ///
+    /// Blocks which were only partially persisted, e.g. because the node crashed, are unwound.
+    /// A store can only be opened with the fork it was created with.
+    pub fn open(
+        path: impl AsRef<Path>,
+        max_states: usize,
+        fork: Option<DiskDbFork>,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let store = StoreDb::open(&path, fork)?;
+        let best_number = store.best_block()?.map(|(number, _)| number);
+        store.unwind(best_number)?;
+        store.check_fork(best_number.is_some())?;
+        Ok(Self {
+            cache: CacheDB::new(store.clone()),
+            dirty: Default::default(),
+            best_number,
+            state_snapshots: Default::default(),
+            store,
+            path,
+            max_states,
+        })
+    }
+
+    /// Returns the persisted chain, or `None` if nothing was persisted yet.
+    ///
+    /// The state is not part of it, it is read from the store when accessed.
+    pub fn persisted_chain(&self) -> io::Result<Option<PersistedChain>> {
+        let Some(best_number) = self.best_number else { return Ok(None) };
+        let store = &self.store.0;
+
+        let mut blocks = Vec::new();
+        for entry in store.hashes.iter() {
+            let (_, hash) = entry?;
+            if let Some(block) = store.blocks.get(&hash)? {
+                blocks.push(decode::<SerializableBlock>(&block)?);
+            }
+        }
+        let transactions = store
+            .transactions
+            .iter()
+            .map(|entry| decode(&entry?.1))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let oldest = self.store.oldest_state()?.unwrap_or(best_number);
+        let states = blocks
+            .iter()
+            .filter(|block| block.header.number >= oldest)
+            .map(|block| (block.header.hash_slow(), self.state_at(block.header.number)))
+            .collect();
+
+        let block_env = match store.meta.get(BLOCK_ENV_KEY)? {
+            Some(env) => decode(&env)?,
+            None => Default::default(),
+        };
+        Ok(Some(PersistedChain { block_env, blocks, transactions, states }))
+    }
+
+    /// Returns the persisted state at the block `number`.
+    fn state_at(&self, number: u64) -> StateDb {
+        StateDb::new(CacheDB::new(self.store.at(Some(number))))
+    }
+
+    /// Persists the state changed since the chain was forked as the state of the fork block,
+    /// which is the first block of a forked chain. The block itself is not persisted, it is
+    /// fetched from the fork.
+    pub fn persist_fork_block(&mut self, at: BlockEnv) -> io::Result<()> {
+        let Some(fork) = self.store.0.fork.clone() else {
+            return Err(io::Error::other("the database is not forked"))
+        };
+        if self.best_number.is_some() {
+            return Err(io::Error::other("the fork block is already persisted"))
+        }
+        self.write_diff(fork.block_number)?;
+        let fork_block = [&fork.block_number.to_be_bytes()[..], fork.block_hash.as_slice()];
+        self.store.0.meta.insert(FORK_BLOCK_KEY, fork_block.concat())?;
+        self.set_best_block(at, fork.block_number, fork.block_hash)
+    }
+
+    /// Marks the block `number`, whose state was written, as the last persisted block.
+    fn set_best_block(&mut self, at: BlockEnv, number: u64, hash: B256) -> io::Result<()> {
+        let store = &self.store.0;
+        store.hashes.insert(number.to_be_bytes(), hash.as_slice())?;
+        store.meta.insert(BLOCK_ENV_KEY, encode(&at)?)?;
+        store.meta.insert(BEST_BLOCK_KEY, [&number.to_be_bytes()[..], hash.as_slice()].concat())?;
+        self.best_number = Some(number);
+
+        // drop the overwritten values of the oldest blocks
+        self.store.prune((number + 1).saturating_sub(self.max_states as u64))?;
+        store.db.flush()?;
+        Ok(())
+    }
+
+    fn mark_dirty(&mut self, address: Address) -> &mut DirtyAccount {
+        self.dirty.entry(address).or_default()
+    }
+
+    /// Writes the accounts and storage slots changed since the last persisted block to the
+    /// store, as changed by the block `number`, and keeps the values they overwrote.
+    fn write_diff(&mut self, number: u64) -> io::Result<()> {
+        let store = &self.store.0;
+        let mut diff = Vec::new();
+        let (mut accounts, mut account_history) = (sled::Batch::default(), sled::Batch::default());
+        let (mut storage, mut storage_history) = (sled::Batch::default(), sled::Batch::default());
+
+        for (address, dirty) in std::mem::take(&mut self.dirty) {
+            let prior = store.accounts.get(address)?.unwrap_or_default();
+            let info = self.cache.basic_ref(address).map_err(io::Error::other)?;
+            // the storage of created or destroyed accounts is no longer read from the fork
+            let wiped = dirty.wipe || info.is_none() || is_storage_wiped(&prior);
+            account_history.insert(history_key(address.as_slice(), number), prior);
+            diff.push(ACCOUNT_TAG);
+            diff.extend_from_slice(address.as_slice());
+            match info {
+                Some(info) => {
+                    if info.code_hash != KECCAK_EMPTY && !store.code.contains_key(info.code_hash)? {
+                        let code = match info.code {
+                            Some(ref code) => code.clone(),
+                            None => self
+                                .cache
+                                .code_by_hash_ref(info.code_hash)
+                                .map_err(io::Error::other)?,
+                        };
+                        store.code.insert(info.code_hash, &code.original_bytes()[..])?;
+                    }
+                    accounts.insert(address.as_slice(), encode_account(&info, wiped));
+                }
+                None => accounts.insert(address.as_slice(), DELETED_ACCOUNT),
+            }
+
+            let mut recorded = HashSet::default();
+            let mut slots = dirty.slots;
+            if dirty.wipe {
+                for entry in store.storage.scan_prefix(address) {
+                    let (key, value) = entry?;
+                    storage_history.insert(history_key(&key, number), value);
+                    storage.remove(key.clone());
+                    diff.push(SLOT_TAG);
+                    diff.extend_from_slice(&key);
+                    recorded.insert(key);
+                }
+                if let Some(account) = self.cache.accounts.get(&address) {
+                    slots.extend(account.storage.keys().copied());
+                }
+            }
+            for slot in slots {
+                let key = storage_key(address, slot);
+                if !recorded.contains(key.as_slice()) {
+                    let prior = store.storage.get(&key)?.unwrap_or_default();
+                    storage_history.insert(history_key(&key, number), prior);
+                    diff.push(SLOT_TAG);
+                    diff.extend_from_slice(&key);
+                }
+                let value = self.cache.storage_ref(address, slot).map_err(io::Error::other)?;
+                // cleared slots of storage which is read from the fork are kept as zero
+                if value.is_zero() && wiped {
+                    storage.remove(key);
+                } else {
+                    storage.insert(key, &value.to_be_bytes::<32>()[..]);
+                }
+            }
+        }
+
+        // the diff and the overwritten values go first, so that the block can be unwound if the
+        // node stops before it is fully persisted
+        store.diffs.insert(number.to_be_bytes(), diff)?;
+        store.account_history.apply_batch(account_history)?;
+        store.storage_history.apply_batch(storage_history)?;
+        store.accounts.apply_batch(accounts)?;
+        store.storage.apply_batch(storage)?;
+        Ok(())
+    }
+
+    /// Logs the error of a write to the store, which does not fail the operation on the state.
+    fn log_error(&self, result: io::Result<()>) {
+        if let Err(err) = result {
+            error!(target: "backend::diskdb", %err, path=?self.path, "failed to persist state");
+        }
+    }
+}
+
+impl fmt::Debug for DiskDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskDb")
+            .field("path", &self.path)
+            .field("best_number", &self.best_number)
+            .field("max_states", &self.max_states)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Db for DiskDb {
+    fn insert_account(&mut self, address: Address, account: AccountInfo) {
+        self.cache.insert_account_info(address, account);
+        self.mark_dirty(address);
+    }
+
+    fn set_storage_at(&mut self, address: Address, slot: B256, val: B256) -> DatabaseResult<()> {
+        self.cache.insert_account_storage(address, slot.into(), val.into())?;
+        self.mark_dirty(address).slots.insert(slot.into());
+        Ok(())
+    }
+
+    fn insert_block_hash(&mut self, number: U256, hash: B256) {
+        self.cache.block_hashes.insert(number, hash);
+    }
+
+    /// Dumps the whole state, including the persisted state which was not read yet.
+    fn dump_state(
+        &self,
+        at: BlockEnv,
+        best_number: U64,
+        blocks: Vec<SerializableBlock>,
+        transactions: Vec<SerializableTransaction>,
+        historical_states: Option<SerializableHistoricalStates>,
+    ) -> DatabaseResult<Option<SerializableState>> {
+        let store = &self.store.0;
+        let mut addresses = self.cache.accounts.keys().copied().collect::<HashSet<_>>();
+        for entry in store.accounts.iter() {
+            let (key, _) = entry.map_err(store_error)?;
+            addresses.insert(Address::from_slice(&key));
+        }
+
+        let mut accounts = BTreeMap::new();
+        for address in addresses {
+            let Some(info) = self.basic_ref(address)? else { continue };
+            let cached = self.cache.accounts.get(&address);
+            let mut slots = cached.map(|account| account.storage.clone()).unwrap_or_default();
+            let cleared = cached.is_some_and(|account| {
+                matches!(account.account_state, AccountState::StorageCleared)
+            });
+            if !cleared {
+                for entry in store.storage.scan_prefix(address) {
+                    let (key, value) = entry.map_err(store_error)?;
+                    slots
+                        .entry(U256::from_be_slice(&key[20..]))
+                        .or_insert_with(|| U256::from_be_slice(&value));
+                }
+            }
+            let code = match info.code {
+                Some(code) => code,
+                None => self.code_by_hash_ref(info.code_hash)?,
+            };
+            accounts.insert(
+                address,
+                SerializableAccountRecord {
+                    nonce: info.nonce,
+                    balance: info.balance,
+                    code: code.original_bytes(),
+                    storage: slots
+                        .into_iter()
+                        .filter(|(_, value)| !value.is_zero())
+                        .map(|(slot, value)| (slot.into(), value.into()))
+                        .collect(),
+                },
+            );
+        }
+
+        Ok(Some(SerializableState {
+            block: Some(at),
+            accounts,
+            best_block_number: Some(best_number),
+            blocks,
+            transactions,
+            historical_states,
+        }))
+    }
+
+    fn snapshot_state(&mut self) -> U256 {
+        self.state_snapshots.insert(DiskDbStateSnapshot {
+            cache: self.cache.clone(),
+            dirty: self.dirty.clone(),
+            best_number: self.best_number,
+        })
+    }
+
+    fn revert_state(&mut self, id: U256, action: RevertStateSnapshotAction) -> bool {
+        let Some(state_snapshot) = self.state_snapshots.remove(id) else {
+            warn!(target: "backend::diskdb", "No state snapshot to revert for {}", id);
+            return false
+        };
+        if action.is_keep() {
+            self.state_snapshots.insert_at(state_snapshot.clone(), id);
+        }
+        let DiskDbStateSnapshot { cache, dirty, best_number } = state_snapshot;
+        self.log_error(self.store.unwind(best_number));
+        self.cache = cache;
+        self.dirty = dirty;
+        self.best_number = best_number;
+        true
+    }
+
+    fn current_state(&self) -> StateDb {
+        let mut state = self.cache.clone();
+        state.db = self.store.at(self.best_number);
+        StateDb::new(state)
+    }
+
+    fn persist_block(
+        &mut self,
+        at: BlockEnv,
+        block: SerializableBlock,
+        transactions: Vec<SerializableTransaction>,
+    ) -> io::Result<()> {
+        let hash = block.header.hash_slow();
+        let number = block.header.number;
+        if self.best_number.is_some_and(|best_number| number <= best_number) {
+            return Err(io::Error::other(format!("block {number} is already persisted")))
+        }
+
+        self.write_diff(number)?;
+        let store = &self.store.0;
+        store.blocks.insert(hash, encode(&block)?)?;
+        for transaction in transactions {
+            let key = [&number.to_be_bytes()[..], transaction.info.transaction_hash.as_slice()];
+            store.transactions.insert(key.concat(), encode(&transaction)?)?;
+        }
+        self.set_best_block(at, number, hash)
+    }
+
+    fn unwind_persisted(&mut self, number: u64) -> io::Result<()> {
+        self.store.unwind(Some(number))?;
+        self.best_number = self.best_number.map(|best_number| best_number.min(number));
+        MaybeFullDatabase::clear(self);
+        Ok(())
+    }
+}
+
+impl MaybeFullDatabase for DiskDb {
+    fn as_dyn(&self) -> &dyn DatabaseRef<Error = DatabaseError> {
+        self
+    }
+
+    /// Returns the cached part of the state, like a forked database.
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        Some(&self.cache.accounts)
+    }
+
+    fn clear_into_state_snapshot(&mut self) -> StateSnapshot {
+        self.dirty.clear();
+        self.cache.clear_into_state_snapshot()
+    }
+
+    fn read_as_state_snapshot(&self) -> StateSnapshot {
+        self.cache.read_as_state_snapshot()
+    }
+
+    /// Discards the state changed since the last persisted block, and the cached state.
+    fn clear(&mut self) {
+        self.clear_into_state_snapshot();
+    }
+
+    /// Writes the state snapshot on top of the persisted state.
+    fn init_from_state_snapshot(&mut self, state_snapshot: StateSnapshot) {
+        for address in state_snapshot.accounts.keys() {
+            self.mark_dirty(*address);
+        }
+        for (address, slots) in &state_snapshot.storage {
+            self.mark_dirty(*address).slots.extend(slots.keys().copied());
+        }
+        self.cache.init_from_state_snapshot(state_snapshot);
+    }
+}
+
+impl MaybeForkedDatabase for DiskDb {
+    fn maybe_reset(&mut self, _url: Option<String>, _block_number: BlockId) -> Result<(), String> {
+        Err("resetting a fork persisted on disk is not supported".to_string())
+    }
+
+    /// Flushes the state read from the fork to disk.
+    fn maybe_flush_cache(&self) -> Result<(), String> {
+        self.store.0.db.flush().map(drop).map_err(|err| err.to_string())
+    }
+
+    fn maybe_inner(&self) -> Result<&BlockchainDb, String> {
+        Err("not supported".to_string())
+    }
+}
+
+impl DatabaseRef for DiskDb {
+    type Error = DatabaseError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.cache.basic_ref(address)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.cache.code_by_hash_ref(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.cache.storage_ref(address, index)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.cache.block_hash_ref(number)
+    }
+}
+
+impl Database for DiskDb {
+    type Error = DatabaseError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.cache.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.cache.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.cache.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.cache.block_hash(number)
+    }
+}
+
+impl DatabaseCommit for DiskDb {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        for (address, account) in &changes {
+            if !account.is_touched() {
+                continue
+            }
+            let dirty = self.mark_dirty(*address);
+            dirty.wipe |= account.is_selfdestructed() || account.is_created();
+            dirty.slots.extend(
+                account.storage.iter().filter(|(_, slot)| slot.is_changed()).map(|(key, _)| *key),
+            );
+        }
+        self.cache.commit(changes)
+    }
+}
+
+/// The trees of the key-value store.
+struct Store {
+    db: sled::Db,
+    /// Account records by address.
+    accounts: sled::Tree,
+    /// Storage values by address and slot.
+    storage: sled::Tree,
+    /// Bytecodes by code hash.
+    code: sled::Tree,
+    /// The account records overwritten by each block, by address and block number.
+    account_history: sled::Tree,
+    /// The storage values overwritten by each block, by address, slot and block number.
+    storage_history: sled::Tree,
+    /// The keys of the accounts and storage slots changed by each block, by block number.
+    diffs: sled::Tree,
+    /// Block hashes by block number.
+    hashes: sled::Tree,
+    /// Blocks by hash.
+    blocks: sled::Tree,
+    /// Mined transactions and their receipts by block number and transaction hash.
+    transactions: sled::Tree,
+    /// The best block, the block environment and the fork block.
+    meta: sled::Tree,
+    /// Account records read from the fork by address, empty for missing accounts.
+    fork_accounts: sled::Tree,
+    /// Storage values read from the fork by address and slot.
+    fork_storage: sled::Tree,
+    /// The state the chain is forked off, if any.
+    fork: Option<DiskDbFork>,
+}
+
+/// The state persisted in the key-value store, at the last persisted block or at a past block.
+#[derive(Clone)]
+pub struct StoreDb(Arc<Store>, Option<u64>);
+
+impl StoreDb {
+    fn open(path: &Path, fork: Option<DiskDbFork>) -> io::Result<Self> {
+        let db = sled::open(path)?;
+        let store = Store {
+            accounts: db.open_tree("accounts")?,
+            storage: db.open_tree("storage")?,
+            code: db.open_tree("code")?,
+            account_history: db.open_tree("account_history")?,
+            storage_history: db.open_tree("storage_history")?,
+            diffs: db.open_tree("diffs")?,
+            hashes: db.open_tree("hashes")?,
+            blocks: db.open_tree("blocks")?,
+            transactions: db.open_tree("transactions")?,
+            meta: db.open_tree("meta")?,
+            fork_accounts: db.open_tree("fork_accounts")?,
+            fork_storage: db.open_tree("fork_storage")?,
+            fork,
+            db,
+        };
+        Ok(Self(Arc::new(store), None))
+    }
+
+    /// Returns a view of the state at the block `number`, or at the last persisted block.
+    fn at(&self, number: Option<u64>) -> Self {
+        Self(self.0.clone(), number)
+    }
+
+    /// Returns the number and hash of the last persisted block.
+    fn best_block(&self) -> io::Result<Option<(u64, B256)>> {
+        Ok(self.0.meta.get(BEST_BLOCK_KEY)?.map(|value| {
+            (
+                u64::from_be_bytes(value[..8].try_into().expect("8 bytes")),
+                B256::from_slice(&value[8..]),
+            )
+        }))
+    }
+
+    /// Ensures the store is opened with the fork it was created with, if it holds a chain.
+    fn check_fork(&self, has_chain: bool) -> io::Result<()> {
+        let store = &self.0;
+        if !has_chain {
+            store.meta.remove(FORK_BLOCK_KEY)?;
+            return Ok(())
+        }
+        let persisted = store.meta.get(FORK_BLOCK_KEY)?.map(|value| {
+            (
+                u64::from_be_bytes(value[..8].try_into().expect("8 bytes")),
+                B256::from_slice(&value[8..]),
+            )
+        });
+        let fork = store.fork.as_ref().map(|fork| (fork.block_number, fork.block_hash));
+        match (persisted, fork) {
+            (Some(persisted), Some(fork)) if persisted != fork => Err(io::Error::other(format!(
+                "the database was forked off block {} ({}), not block {} ({})",
+                persisted.0, persisted.1, fork.0, fork.1
+            ))),
+            (Some((number, _)), None) => Err(io::Error::other(format!(
+                "the database was forked off block {number}, it can only be opened when forking"
+            ))),
+            (None, Some(_)) => Err(io::Error::other(
+                "the database was not forked, it can't be opened when forking",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the number of the oldest block whose state can still be read.
+    fn oldest_state(&self) -> io::Result<Option<u64>> {
+        Ok(self.0.diffs.first()?.map(|(key, _)| decode_number(&key).saturating_sub(1)))
+    }
+
+    /// Returns the value of `key` in `tree`, or the value it had at the block this view is at, or
+    /// `None` if it was not set by then.
+    fn read(
+        &self,
+        tree: &sled::Tree,
+        history: &sled::Tree,
+        key: &[u8],
+    ) -> io::Result<Option<Vec<u8>>> {
+        if let Some(number) = self.1 {
+            // the value overwritten by the first block after it
+            let next = number.saturating_add(1);
+            if !self.0.diffs.contains_key(next.to_be_bytes())? &&
+                self.best_block()?.is_some_and(|(best, _)| number < best)
+            {
+                return Err(io::Error::other(format!("the state at block {number} was pruned")))
+            }
+            let start = history_key(key, next);
+            let end = history_key(key, u64::MAX);
+            if let Some(entry) = history.range(start..=end).next() {
+                let prior = entry?.1;
+                return Ok((!prior.is_empty()).then(|| prior.to_vec()))
+            }
+        }
+        Ok(tree.get(key)?.map(|value| value.to_vec()))
+    }
+
+    /// Returns the account read from the fork, fetching it if it was not read yet.
+    fn fork_basic(&self, address: Address) -> Result<Option<AccountInfo>, DatabaseError> {
+        let store = &self.0;
+        let Some(fork) = &store.fork else { return Ok(None) };
+        if let Some(record) = store.fork_accounts.get(address).map_err(store_error)? {
+            return Ok(decode_account(&record))
+        }
+        let info = fork.db.basic_ref(address)?;
+        let record = match &info {
+            Some(info) => {
+                if let Some(code) = &info.code {
+                    store
+                        .code
+                        .insert(info.code_hash, &code.original_bytes()[..])
+                        .map_err(store_error)?;
+                }
+                encode_account(info, false)
+            }
+            None => Vec::new(),
+        };
+        store.fork_accounts.insert(address, record).map_err(store_error)?;
+        Ok(info)
+    }
+
+    /// Returns the storage value read from the fork, fetching it if it was not read yet.
+    fn fork_storage(&self, address: Address, index: U256) -> Result<U256, DatabaseError> {
+        let store = &self.0;
+        let Some(fork) = &store.fork else { return Ok(U256::ZERO) };
+        let key = storage_key(address, index);
+        if let Some(value) = store.fork_storage.get(&key).map_err(store_error)? {
+            return Ok(U256::from_be_slice(&value))
+        }
+        let value = fork.db.storage_ref(address, index)?;
+        store.fork_storage.insert(key, &value.to_be_bytes::<32>()[..]).map_err(store_error)?;
+        Ok(value)
+    }
+
+    /// Unwinds the persisted blocks after the block `number`, or all blocks if `None`.
+    fn unwind(&self, number: Option<u64>) -> io::Result<()> {
+        let store = &self.0;
+        let start = number.map_or(0, |number| number + 1).to_be_bytes();
+        let diffs = store.diffs.range(start..).collect::<Result<Vec<_>, _>>()?;
+        let best = store.hashes.last()?.map(|(key, _)| decode_number(&key));
+        if best.is_some_and(|best| best >= u64::from_be_bytes(start)) &&
+            diffs.first().is_none_or(|(key, _)| key[..] != start[..])
+        {
+            return Err(io::Error::other("the persisted blocks to unwind were pruned"))
+        }
+        for (key, diff) in diffs.into_iter().rev() {
+            let number = decode_number(&key);
+            for (tag, key) in diff_entries(&diff) {
+                let (tree, history) = match tag {
+                    ACCOUNT_TAG => (&store.accounts, &store.account_history),
+                    _ => (&store.storage, &store.storage_history),
+                };
+                let history_key = history_key(key, number);
+                match history.get(&history_key)? {
+                    Some(prior) if !prior.is_empty() => tree.insert(key, prior)?,
+                    _ => tree.remove(key)?,
+                };
+                history.remove(history_key)?;
+            }
+            store.diffs.remove(key)?;
+        }
+
+        let hashes = store.hashes.range(start..).collect::<Result<Vec<_>, _>>()?;
+        for (key, hash) in hashes {
+            store.blocks.remove(&hash)?;
+            store.hashes.remove(key)?;
+        }
+        let transactions = store.transactions.range(start..).collect::<Result<Vec<_>, _>>()?;
+        for (key, _) in transactions {
+            store.transactions.remove(key)?;
+        }
+
+        match number.map(|number| store.hashes.get(number.to_be_bytes())).transpose()?.flatten() {
+            Some(hash) => {
+                let number = number.expect("block number").to_be_bytes();
+                store.meta.insert(BEST_BLOCK_KEY, [&number[..], &hash[..]].concat())?
+            }
+            None => store.meta.remove(BEST_BLOCK_KEY)?,
+        };
+        store.db.flush()?;
+        Ok(())
+    }
+
+    /// Drops the values overwritten by the blocks before the block `oldest`.
+    fn prune(&self, oldest: u64) -> io::Result<()> {
+        let store = &self.0;
+        let diffs = store.diffs.range(..oldest.to_be_bytes()).collect::<Result<Vec<_>, _>>()?;
+        for (key, diff) in diffs {
+            let number = decode_number(&key);
+            for (tag, key) in diff_entries(&diff) {
+                let history = match tag {
+                    ACCOUNT_TAG => &store.account_history,
+                    _ => &store.storage_history,
+                };
+                history.remove(history_key(key, number))?;
+            }
+            store.diffs.remove(key)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for StoreDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreDb").field("at", &self.1).finish_non_exhaustive()
+    }
+}
+
+impl DatabaseRef for StoreDb {
+    type Error = DatabaseError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let record = self
+            .read(&self.0.accounts, &self.0.account_history, address.as_slice())
+            .map_err(store_error)?;
+        let info = match record {
+            Some(record) => decode_account(&record),
+            None => self.fork_basic(address)?,
+        };
+        let Some(mut info) = info else { return Ok(None) };
+        if info.code_hash != KECCAK_EMPTY {
+            info.code = Some(self.code_by_hash_ref(info.code_hash)?);
+        }
+        Ok(Some(info))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if let Some(code) = self.0.code.get(code_hash).map_err(store_error)? {
+            return Ok(Bytecode::new_raw(code.to_vec().into()))
+        }
+        let Some(fork) = &self.0.fork else { return Err(DatabaseError::MissingCode(code_hash)) };
+        let code = fork.db.code_by_hash_ref(code_hash)?;
+        self.0.code.insert(code_hash, &code.original_bytes()[..]).map_err(store_error)?;
+        Ok(code)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self
+            .read(&self.0.storage, &self.0.storage_history, &storage_key(address, index))
+            .map_err(store_error)?;
+        if let Some(value) = value {
+            return Ok(U256::from_be_slice(&value))
+        }
+        if self.0.fork.is_none() {
+            return Ok(U256::ZERO)
+        }
+        let record = self
+            .read(&self.0.accounts, &self.0.account_history, address.as_slice())
+            .map_err(store_error)?;
+        if record.is_some_and(|record| is_storage_wiped(&record)) {
+            return Ok(U256::ZERO)
+        }
+        self.fork_storage(address, index)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        match self.0.hashes.get(number.to_be_bytes()).map_err(store_error)? {
+            Some(hash) => Ok(B256::from_slice(&hash)),
+            None => match &self.0.fork {
+                Some(fork) => fork.db.block_hash_ref(number),
+                // like the empty database of the in-memory state
+                None => Ok(keccak256(number.to_string().as_bytes())),
+            },
+        }
+    }
+}
+
+/// Returns the key of the storage `slot` of `address`.
+fn storage_key(address: Address, slot: U256) -> Vec<u8> {
+    [address.as_slice(), &slot.to_be_bytes::<32>()].concat()
+}
+
+/// Returns the key of the value of `key` overwritten by the block `number`.
+fn history_key(key: &[u8], number: u64) -> Vec<u8> {
+    [key, &number.to_be_bytes()].concat()
+}
+
+fn decode_number(key: &[u8]) -> u64 {
+    u64::from_be_bytes(key[key.len() - 8..].try_into().expect("8 bytes"))
+}
+
+/// Returns the tagged keys of the accounts and storage slots changed by a block.
+fn diff_entries(diff: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut rest = diff;
+    std::iter::from_fn(move || {
+        let (&tag, tail) = rest.split_first()?;
+        let len = if tag == ACCOUNT_TAG { 20 } else { 52 };
+        let (key, tail) = tail.split_at(len);
+        rest = tail;
+        Some((tag, key))
+    })
+}
+
+/// Encodes an account as its nonce, balance and code hash, the code is stored by its hash,
+/// followed by a marker if its storage was wiped.
+fn encode_account(info: &AccountInfo, wiped: bool) -> Vec<u8> {
+    let wiped: &[u8] = if wiped { &[1] } else { &[] };
+    [
+        &info.nonce.to_be_bytes()[..],
+        &info.balance.to_be_bytes::<32>(),
+        info.code_hash.as_slice(),
+        wiped,
+    ]
+    .concat()
+}
+
+/// Decodes an account encoded with [`encode_account`], or `None` for a missing or deleted
+/// account.
+fn decode_account(record: &[u8]) -> Option<AccountInfo> {
+    if record.len() < ACCOUNT_LEN {
+        return None
+    }
+    Some(AccountInfo {
+        nonce: u64::from_be_bytes(record[..8].try_into().expect("8 bytes")),
+        balance: U256::from_be_slice(&record[8..40]),
+        code_hash: B256::from_slice(&record[40..ACCOUNT_LEN]),
+        code: None,
+    })
+}
+
+/// Returns whether the account of the `record` was deleted or its storage was wiped, in which
+/// case its storage is not read from the fork.
+fn is_storage_wiped(record: &[u8]) -> bool {
+    record == DELETED_ACCOUNT || record.len() > ACCOUNT_LEN
+}
+
+fn store_error(err: impl Into<io::Error>) -> DatabaseError {
+    let err: io::Error = err.into();
+    DatabaseError::AnyRequest(Arc::new(eyre::Report::new(err)))
+}
+
+fn encode<T: Serialize>(value: &T) -> io::Result<Vec<u8>> {
+    Ok(serde_json::to_vec(value)?)
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> io::Result<T> {
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::backend::mem::in_memory_db::MemDb;
+    use alloy_consensus::Header;
+    use alloy_primitives::Bytes;
+
+    fn block(number: u64, parent_hash: B256) -> SerializableBlock {
+        SerializableBlock {
+            header: Header { number, parent_hash, ..Default::default() },
+            transactions: vec![],
+            ommers: vec![],
+        }
+    }
+
+    #[test]
+    fn test_persists_diffs_and_reads_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = Address::repeat_byte(1);
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let genesis = block(0, B256::ZERO);
+        let block1 = block(1, genesis.header.hash_slow());
+
+        {
+            let mut db = DiskDb::open(dir.path(), 2, None).unwrap();
+            assert!(db.persisted_chain().unwrap().is_none());
+
+            db.insert_account(
+                addr,
+                AccountInfo {
+                    balance: U256::from(100),
+                    nonce: 2,
+                    code_hash: code.hash_slow(),
+                    code: Some(code.clone()),
+                },
+            );
+            db.set_storage_at(addr, B256::with_last_byte(1), B256::with_last_byte(7)).unwrap();
+            db.persist_block(Default::default(), genesis.clone(), vec![]).unwrap();
+            db.set_storage_at(addr, B256::with_last_byte(2), B256::with_last_byte(8)).unwrap();
+            db.set_storage_at(addr, B256::with_last_byte(1), B256::ZERO).unwrap();
+            db.persist_block(Default::default(), block1.clone(), vec![]).unwrap();
+        }
+
+        let db = DiskDb::open(dir.path(), 2, None).unwrap();
+        // nothing is loaded into memory until it is read
+        assert!(db.cache.accounts.is_empty());
+        let account = db.basic_ref(addr).unwrap().unwrap();
+        assert_eq!(account.balance, U256::from(100));
+        assert_eq!(account.nonce, 2);
+        assert_eq!(db.code_by_hash_ref(account.code_hash).unwrap(), code);
+        assert_eq!(db.storage_ref(addr, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(db.storage_ref(addr, U256::from(2)).unwrap(), U256::from(8));
+
+        let chain = db.persisted_chain().unwrap().unwrap();
+        assert_eq!(chain.blocks.len(), 2);
+        assert_eq!(chain.states.len(), 2);
+        // the state at the genesis block is read from the overwritten values
+        let (hash, genesis_state) = &chain.states[0];
+        assert_eq!(*hash, genesis.header.hash_slow());
+        assert_eq!(genesis_state.storage_ref(addr, U256::from(1)).unwrap(), U256::from(7));
+        assert_eq!(genesis_state.storage_ref(addr, U256::from(2)).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn test_unwinds_persisted_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = Address::repeat_byte(1);
+        let genesis = block(0, B256::ZERO);
+        let block1 = block(1, genesis.header.hash_slow());
+
+        let mut db = DiskDb::open(dir.path(), 8, None).unwrap();
+        db.set_storage_at(addr, B256::with_last_byte(1), B256::with_last_byte(7)).unwrap();
+        db.persist_block(Default::default(), genesis, vec![]).unwrap();
+        let id = db.snapshot_state();
+        db.set_storage_at(addr, B256::with_last_byte(1), B256::with_last_byte(9)).unwrap();
+        db.persist_block(Default::default(), block1, vec![]).unwrap();
+
+        assert!(db.revert_state(id, RevertStateSnapshotAction::RevertRemove));
+        assert_eq!(db.best_number, Some(0));
+        assert_eq!(db.store.storage_ref(addr, U256::from(1)).unwrap(), U256::from(7));
+        assert_eq!(db.persisted_chain().unwrap().unwrap().blocks.len(), 1);
+
+        db.unwind_persisted(0).unwrap();
+        assert_eq!(db.storage_ref(addr, U256::from(1)).unwrap(), U256::from(7));
+    }
+
+    #[test]
+    fn test_persists_state_read_from_fork() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = Address::repeat_byte(1);
+        let destroyed = Address::repeat_byte(2);
+        let info = AccountInfo { balance: U256::from(100), ..Default::default() };
+
+        let mut remote = MemDb::default();
+        remote.insert_account(addr, info.clone());
+        remote.set_storage_at(addr, B256::with_last_byte(1), B256::with_last_byte(7)).unwrap();
+        remote.set_storage_at(addr, B256::with_last_byte(2), B256::with_last_byte(8)).unwrap();
+        remote.insert_account(destroyed, info.clone());
+        remote.set_storage_at(destroyed, B256::with_last_byte(1), B256::with_last_byte(9)).unwrap();
+        let fork = DiskDbFork {
+            db: Arc::new(remote),
+            block_number: 10,
+            block_hash: B256::repeat_byte(10),
+        };
+
+        {
+            let mut db = DiskDb::open(dir.path(), 8, Some(fork.clone())).unwrap();
+            assert_eq!(db.storage_ref(addr, U256::from(1)).unwrap(), U256::from(7));
+            db.set_storage_at(addr, B256::with_last_byte(1), B256::ZERO).unwrap();
+            db.persist_fork_block(Default::default()).unwrap();
+
+            let mut account = Account::from(info);
+            account.mark_touch();
+            account.mark_selfdestruct();
+            db.commit([(destroyed, account)].into_iter().collect());
+            db.persist_block(Default::default(), block(11, fork.block_hash), vec![]).unwrap();
+        }
+
+        // the store can only be opened with the fork it was created with
+        let other_fork = DiskDbFork { block_number: 11, ..fork.clone() };
+        assert!(DiskDb::open(dir.path(), 8, Some(other_fork)).is_err());
+        assert!(DiskDb::open(dir.path(), 8, None).is_err());
+
+        let db = DiskDb::open(dir.path(), 8, Some(fork)).unwrap();
+        // the values read from the fork were persisted
+        assert!(db.store.0.fork_accounts.contains_key(addr).unwrap());
+        assert!(db.store.0.fork_storage.contains_key(storage_key(addr, U256::from(1))).unwrap());
+        // local changes shadow the fork
+        assert_eq!(db.basic_ref(addr).unwrap().unwrap().balance, U256::from(100));
+        assert_eq!(db.storage_ref(addr, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(db.storage_ref(addr, U256::from(2)).unwrap(), U256::from(8));
+        assert!(db.basic_ref(destroyed).unwrap().is_none());
+        assert_eq!(db.storage_ref(destroyed, U256::from(1)).unwrap(), U256::ZERO);
+
+        // the fork block is not persisted, but its state is
+        assert_eq!(db.persisted_chain().unwrap().unwrap().blocks.len(), 1);
+        let fork_state = db.store.at(Some(10));
+        assert_eq!(fork_state.storage_ref(addr, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(fork_state.storage_ref(destroyed, U256::from(1)).unwrap(), U256::from(9));
+    }
+}
@@ -6,12 +6,15 @@ use crate::{
     config::PruneStateHistoryConfig,
     eth::{
         backend::{
+            assertions::{AssertionsManager, RegisteredAssertion},
             cheats::CheatsManager,
             db::{Db, MaybeFullDatabase, SerializableState},
+            dump::{self, AccountDelta, DumpChunk, StateChanges, StateDelta, StateFormat},
             executor::{ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
             mem::{
+                disk_db::PersistedChain,
                 state::{storage_root, trie_accounts},
                 storage::MinedTransactionReceipt,
             },
@@ -103,7 +106,7 @@ use revm::{
 };
 use std::{
     collections::BTreeMap,
-    io::{Read, Write},
+    io::{self, Read, Write},
     path::PathBuf,
     sync::Arc,
     time::Duration,
@@ -111,6 +114,7 @@ use std::{
 use storage::{Blockchain, MinedTransaction, DEFAULT_HISTORY_LIMIT};
 use tokio::sync::RwLock as AsyncRwLock;
 pub mod cache;
+pub mod disk_db;
 pub mod fork_db;
 pub mod in_memory_db;
 pub mod inspector;
@@ -185,6 +189,8 @@ pub struct Backend {
     time: TimeManager,
     /// Contains state of custom overrides.
     cheats: CheatsManager,
+    /// Contains the credible-layer assertions transactions are validated against.
+    assertions: AssertionsManager,
     /// Contains fee data.
     fees: FeeManager,
     /// Initialised genesis.
@@ -207,6 +213,8 @@ pub struct Backend {
     precompile_factory: Option<Arc<dyn PrecompileFactory>>,
     /// Prevent race conditions during mining
     mining: Arc<tokio::sync::Mutex<()>>,
+    /// The changes since the last incremental state dump, if the state is dumped incrementally.
+    state_changes: Option<Arc<Mutex<StateChanges>>>,
     // === wallet === //
     capabilities: Arc<RwLock<WalletCapabilities>>,
     executor_wallet: Arc<RwLock<Option<EthereumWallet>>>,
@@ -270,9 +278,17 @@ impl Backend {
             states = states.disk_path(cache_path);
         }
 
-        let (slots_in_an_epoch, precompile_factory) = {
+        let (slots_in_an_epoch, precompile_factory, assertions) = {
             let cfg = node_config.read().await;
-            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone())
+            let (spec_id, chain_id) = {
+                let env = env.read();
+                (env.handler_cfg.spec_id, env.cfg.chain_id)
+            };
+            let assertions = AssertionsManager::new(spec_id, chain_id, cfg.assertion_gas_limit);
+            for assertion in &cfg.assertions {
+                assertions.register(assertion.clone()).map_err(|err| eyre::eyre!(err))?;
+            }
+            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone(), assertions)
         };
 
         let (capabilities, executor_wallet) = if odyssey {
@@ -312,6 +328,9 @@ impl Backend {
             (WalletCapabilities::default(), None)
         };
 
+        let state_changes = (node_config.read().await.state_format == StateFormat::Incremental)
+            .then(|| Arc::new(Mutex::new(StateChanges::new())));
+
         let backend = Self {
             db,
             blockchain,
@@ -320,6 +339,7 @@ impl Backend {
             fork,
             time: TimeManager::new(start_timestamp),
             cheats: Default::default(),
+            assertions,
             new_block_listeners: Default::default(),
             fees,
             genesis,
@@ -333,6 +353,7 @@ impl Backend {
             slots_in_an_epoch,
             precompile_factory,
             mining: Arc::new(tokio::sync::Mutex::new(())),
+            state_changes,
             capabilities: Arc::new(RwLock::new(capabilities)),
             executor_wallet: Arc::new(RwLock::new(executor_wallet)),
         };
@@ -585,6 +606,7 @@ impl Backend {
             );
             self.states.write().clear();
             self.db.write().await.clear();
+            self.record_state_changes(StateChanges::replace);
 
             self.apply_genesis().await?;
 
@@ -604,6 +626,18 @@ impl Backend {
         &self.cheats
     }
 
+    /// Returns the `AssertionsManager` that holds the registered credible-layer assertions
+    pub fn assertions(&self) -> &AssertionsManager {
+        &self.assertions
+    }
+
+    /// Registers a credible-layer assertion contract for an adopter address
+    ///
+    /// Returns an error if the assertion contract can't be deployed
+    pub fn register_assertion(&self, assertion: RegisteredAssertion) -> Result<(), String> {
+        self.assertions.register(assertion).map(drop)
+    }
+
     /// Returns the `FeeManager` that manages fee/pricings
     pub fn fees(&self) -> &FeeManager {
         &self.fees
@@ -661,17 +695,31 @@ impl Backend {
 
     /// Sets the nonce of the given address
     pub async fn set_nonce(&self, address: Address, nonce: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_nonce(address, nonce.try_into().unwrap_or(u64::MAX))
+        self.db.write().await.set_nonce(address, nonce.try_into().unwrap_or(u64::MAX))?;
+        self.record_state_changes(|state_changes| {
+            state_changes.account(address);
+        });
+        Ok(())
     }
 
     /// Sets the balance of the given address
     pub async fn set_balance(&self, address: Address, balance: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_balance(address, balance)
+        self.db.write().await.set_balance(address, balance)?;
+        self.record_state_changes(|state_changes| {
+            state_changes.account(address);
+        });
+        Ok(())
     }
 
     /// Sets the code of the given address
     pub async fn set_code(&self, address: Address, code: Bytes) -> DatabaseResult<()> {
-        self.db.write().await.set_code(address, code.0.into())
+        self.db.write().await.set_code(address, code.0.into())?;
+        self.record_state_changes(|state_changes| {
+            if let Some(account) = state_changes.account(address) {
+                account.code = true;
+            }
+        });
+        Ok(())
     }
 
     /// Sets the value for the given slot of the given address
@@ -681,7 +729,21 @@ impl Backend {
         slot: U256,
         val: B256,
     ) -> DatabaseResult<()> {
-        self.db.write().await.set_storage_at(address, slot.into(), val)
+        self.db.write().await.set_storage_at(address, slot.into(), val)?;
+        self.record_state_changes(|state_changes| {
+            if let Some(account) = state_changes.account(address) {
+                account.slots.insert(slot);
+            }
+        });
+        Ok(())
+    }
+
+    /// Records changes to include in the next incremental state dump, if the state is dumped
+    /// incrementally.
+    fn record_state_changes(&self, f: impl FnOnce(&mut StateChanges)) {
+        if let Some(state_changes) = &self.state_changes {
+            f(&mut state_changes.lock());
+        }
     }
 
     /// Returns the configured specid
@@ -860,6 +922,7 @@ impl Backend {
                 ..Default::default()
             };
         }
+        self.record_state_changes(StateChanges::replace);
         Ok(self.db.write().await.revert_state(id, RevertStateSnapshotAction::RevertRemove))
     }
 
@@ -896,11 +959,17 @@ impl Backend {
     }
 
     /// Write all chain data to serialized bytes buffer
+    ///
+    /// The buffer is gzip compressed JSON, or the binary encoding if the node dumps its state in
+    /// a binary format.
     pub async fn dump_state(
         &self,
         preserve_historical_states: bool,
     ) -> Result<Bytes, BlockchainError> {
         let state = self.serialized_state(preserve_historical_states).await?;
+        if self.node_config.read().await.state_format != StateFormat::Json {
+            return dump::encode(state).map(Into::into).map_err(|_| BlockchainError::DataUnavailable)
+        }
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
         encoder
             .write_all(&serde_json::to_vec(&state).unwrap_or_default())
@@ -908,6 +977,116 @@ impl Backend {
         Ok(encoder.finish().unwrap_or_default().into())
     }
 
+    /// Encodes the changes since the previous call as a frame to append to an incremental state
+    /// dump.
+    ///
+    /// Only the accounts, blocks and states which changed are read. The complete state is encoded
+    /// on the first call, and after the state was replaced, e.g. by reverting to a state snapshot.
+    pub async fn incremental_dump(
+        &self,
+        preserve_historical_states: bool,
+    ) -> Result<DumpChunk, BlockchainError> {
+        let Some(state_changes) = &self.state_changes else {
+            return Err(RpcError::invalid_params("The state is not dumped incrementally").into())
+        };
+        // no block is mined while the changes are read
+        let _mining_guard = self.mining.lock().await;
+        let changes = std::mem::take(&mut *state_changes.lock());
+        let result = self.encode_state_changes(changes, preserve_historical_states).await;
+        if result.is_err() {
+            // the next dump starts over with the complete state
+            state_changes.lock().replace();
+        }
+        result
+    }
+
+    /// Makes the next incremental state dump a complete one, e.g. because the previous one could
+    /// not be written.
+    pub fn reset_incremental_dump(&self) {
+        self.record_state_changes(StateChanges::replace);
+    }
+
+    async fn encode_state_changes(
+        &self,
+        changes: StateChanges,
+        preserve_historical_states: bool,
+    ) -> Result<DumpChunk, BlockchainError> {
+        if changes.replaced {
+            let state = self.serialized_state(preserve_historical_states).await?;
+            return dump::encode(state)
+                .map(DumpChunk::Full)
+                .map_err(|_| BlockchainError::DataUnavailable)
+        }
+        if changes.is_empty() {
+            return Ok(DumpChunk::Delta(Vec::new()))
+        }
+
+        let mut delta = StateDelta {
+            block: Some(self.env.read().block.clone()),
+            best_block_number: Some(self.blockchain.storage.read().best_number),
+            pruned: changes.pruned,
+            ..Default::default()
+        };
+
+        let db = self.db.read().await;
+        for (address, changed) in changes.accounts {
+            let Some(info) = db.basic_ref(address)? else {
+                delta.accounts.push((address, None));
+                continue
+            };
+            let code = if changed.code {
+                let code = match info.code {
+                    Some(code) => code,
+                    None => db.code_by_hash_ref(info.code_hash)?,
+                };
+                Some(code.original_bytes())
+            } else {
+                None
+            };
+            let mut storage = BTreeMap::new();
+            for slot in changed.slots {
+                storage.insert(B256::from(slot), B256::from(db.storage_ref(address, slot)?));
+            }
+            let account = AccountDelta {
+                nonce: info.nonce,
+                balance: info.balance,
+                code,
+                wipe: changed.wipe,
+                storage,
+            };
+            delta.accounts.push((address, Some(account)));
+        }
+        drop(db);
+
+        {
+            let storage = self.blockchain.storage.read();
+            for hash in changes.blocks {
+                let Some(block) = storage.blocks.get(&hash) else { continue };
+                delta.transactions.extend(
+                    block
+                        .transactions
+                        .iter()
+                        .filter_map(|tx| storage.transactions.get(&tx.hash()))
+                        .map(|tx| tx.clone().into()),
+                );
+                delta.blocks.push(block.clone().into());
+            }
+        }
+
+        if preserve_historical_states {
+            let mut states = self.states.write();
+            delta.historical_states = changes
+                .states
+                .iter()
+                .filter_map(|hash| Some((*hash, states.serialized_state(hash)?)))
+                .collect();
+        }
+
+        dump::encode_delta(&delta)
+            .map(DumpChunk::Delta)
+            .map_err(|_| BlockchainError::DataUnavailable)
+    }
+
     /// Apply [SerializableState] data to the backend storage.
     pub async fn load_state(&self, state: SerializableState) -> Result<bool, BlockchainError> {
         // load the blocks and transactions into the storage
@@ -960,6 +1139,7 @@ impl Backend {
             }
         }
 
+        self.record_state_changes(StateChanges::replace);
         if !self.db.write().await.load_state(state.clone())? {
             return Err(RpcError::invalid_params(
                 "Loading state not supported with the current configuration",
@@ -967,6 +1147,14 @@ impl Backend {
             .into());
         }
 
+        // make the hashes of the loaded blocks available to the evm
+        {
+            let mut db = self.db.write().await;
+            for block in &state.blocks {
+                db.insert_block_hash(U256::from(block.header.number), block.header.hash_slow());
+            }
+        }
+
         if let Some(historical_states) = state.historical_states {
             self.states.write().load_states(historical_states);
         }
@@ -974,8 +1162,53 @@ impl Backend {
         Ok(true)
     }
 
+    /// Restores the chain persisted on disk, replacing the fresh genesis block.
+    ///
+    /// The database must already be the one the chain was persisted in, the state is read from it
+    /// when accessed. A forked chain continues the fork block, which is not persisted.
+    pub async fn restore_persisted_chain(&self, chain: PersistedChain) {
+        let PersistedChain { block_env, blocks, transactions, states } = chain;
+        let is_fork = self.is_fork();
+        {
+            let mut storage = self.blockchain.storage.write();
+            if !is_fork {
+                *storage = BlockchainStorage::empty();
+            }
+            storage.load_blocks(blocks);
+            storage.load_transactions(transactions);
+            if let Some((&number, &hash)) = storage.hashes.iter().max_by_key(|(number, _)| **number)
+            {
+                storage.best_number = number;
+                storage.best_hash = hash;
+            }
+            if !is_fork {
+                storage.genesis_hash = storage.hashes.get(&U64::ZERO).copied().unwrap_or_default();
+            }
+        }
+        self.env.write().block = block_env;
+
+        // make the hashes of the persisted blocks available to the evm
+        {
+            let hashes = self.blockchain.storage.read().hashes.clone();
+            let mut db = self.db.write().await;
+            for (number, hash) in hashes {
+                db.insert_block_hash(U256::from(number), hash);
+            }
+        }
+
+        let mut block_states = self.states.write();
+        for (hash, state) in states {
+            block_states.insert(hash, state);
+        }
+    }
+
     /// Deserialize and add all chain data to the backend storage
     pub async fn load_state_bytes(&self, buf: Bytes) -> Result<bool, BlockchainError> {
+        if dump::is_binary(&buf) {
+            let state = dump::decode(&buf).map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
+            return self.load_state(state).await
+        }
+
         let orig_buf = &buf.0[..];
         let mut decoder = GzDecoder::new(orig_buf);
         let mut decoded_data = Vec::new();
@@ -1099,6 +1332,8 @@ impl Backend {
             print_logs: self.print_logs,
             precompile_factory: self.precompile_factory.clone(),
             odyssey: self.odyssey,
+            assertions: &self.assertions,
+            changes: Default::default(),
         };
 
         // create a new pending block
@@ -1159,6 +1394,7 @@ impl Backend {
                 let db = self.db.read().await.current_state();
                 // store current state before executing all transactions
                 self.states.write().insert(best_hash, db);
+                self.record_state_changes(|state_changes| state_changes.states.push(best_hash));
             }
 
             let (executed_tx, block_hash) = {
@@ -1182,9 +1418,14 @@ impl Backend {
                     print_logs: self.print_logs,
                     odyssey: self.odyssey,
                     precompile_factory: self.precompile_factory.clone(),
+                    assertions: &self.assertions,
+                    changes: Default::default(),
                 };
                 let executed_tx = executor.execute();
 
+                // transactions still in the pool need to be validated on top of the new block
+                self.assertions.clear_validated();
+
                 // we also need to update the new blockhash in the db itself
                 let block_hash = executed_tx.block.block.header.hash_slow();
                 db.insert_block_hash(U256::from(executed_tx.block.block.header.number), block_hash);
@@ -1193,7 +1434,7 @@ impl Backend {
             };
 
             // create the new block with the current timestamp
-            let ExecutedTransactions { block, included, invalid } = executed_tx;
+            let ExecutedTransactions { block, included, invalid, changes } = executed_tx;
             let BlockInfo { block, transactions, receipts } = block;
 
             let header = block.header.clone();
@@ -1247,15 +1488,23 @@ impl Backend {
             }
 
             // remove old transactions that exceed the transaction block keeper
+            let mut pruned = None;
             if let Some(transaction_block_keeper) = self.transaction_block_keeper {
                 if storage.blocks.len() > transaction_block_keeper {
                     let to_clear = block_number
                         .to::<u64>()
                         .saturating_sub(transaction_block_keeper.try_into().unwrap_or(u64::MAX));
+                    pruned = storage.hashes.get(&U64::from(to_clear)).copied();
                     storage.remove_block_transactions_by_number(to_clear)
                 }
             }
 
+            self.record_state_changes(|state_changes| {
+                state_changes.extend(changes);
+                state_changes.blocks.push(block_hash);
+                state_changes.pruned.extend(pruned);
+            });
+
             // we intentionally set the difficulty to `0` for newer blocks
             env.block.difficulty = U256::from(0);
 
@@ -1292,12 +1541,35 @@ impl Backend {
         self.fees
             .set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(next_block_excess_blob_gas));
 
+        if let Err(err) = self.persist_block(block_hash).await {
+            error!(target: "backend", %err, "failed to persist block {block_hash}");
+        }
+
         // notify all listeners
         self.notify_on_new_block(header, block_hash);
 
         outcome
     }
 
+    /// Persists the block with the given hash and its transactions, along with the state changed
+    /// by the block, if the database stores the chain on disk.
+    pub async fn persist_block(&self, hash: B256) -> io::Result<()> {
+        let Some(block) = self.blockchain.storage.read().blocks.get(&hash).cloned() else {
+            return Ok(())
+        };
+        let transactions = {
+            let storage = self.blockchain.storage.read();
+            block
+                .transactions
+                .iter()
+                .filter_map(|tx| storage.transactions.get(&tx.hash()))
+                .map(|tx| tx.clone().into())
+                .collect()
+        };
+        let at = self.env.read().block.clone();
+        self.db.write().await.persist_block(at, block.into(), transactions)
+    }
+
     /// Executes the [TransactionRequest] without writing to the DB
     ///
     /// # Errors
@@ -2642,6 +2914,10 @@ impl Backend {
 
         {
             // Set state to common state
+            self.record_state_changes(StateChanges::replace);
+            if let Err(err) = self.db.write().await.unwind_persisted(common_block.header.number) {
+                error!(target: "backend", %err, "failed to unwind persisted blocks");
+            }
             self.db.write().await.clear();
             for (address, acc) in common_state {
                 for (key, value) in acc.storage {
@@ -2714,8 +2990,21 @@ impl TransactionValidator for Backend {
     ) -> Result<(), BlockchainError> {
         let address = *tx.sender();
         let account = self.get_account(address).await?;
-        let env = self.next_env();
-        Ok(self.validate_pool_transaction_for(tx, &account, &env)?)
+        let mut env = self.next_env();
+        self.validate_pool_transaction_for(tx, &account, &env)?;
+
+        // Reject transactions which would invalidate a registered assertion right away, so the
+        // sender learns which assertion rejected it.
+        if !self.assertions.is_empty() {
+            // read the best hash before the state, so that the transaction is only considered
+            // validated on top of a block if it was validated on its state
+            let best_hash = self.best_hash();
+            env.tx = tx.to_revm_tx_env();
+            let db = self.db.read().await;
+            self.assertions.validate(&**db, &env)?;
+            self.assertions.mark_validated(*tx.hash(), best_hash);
+        }
+        Ok(())
     }
 
     fn validate_pool_transaction_for(
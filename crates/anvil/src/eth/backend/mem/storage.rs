@@ -33,7 +33,7 @@ use anvil_core::eth::{
 };
 use anvil_rpc::error::RpcError;
 use foundry_evm::{
-    backend::MemDb,
+    backend::{MemDb, StateSnapshot},
     revm::primitives::Env,
     traces::{
         CallKind, FourByteInspector, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig,
@@ -202,6 +202,18 @@ impl InMemoryBlockStates {
     }
 
     /// Serialize all states to a list of serializable historical states
+    /// Serializes the state at the block with the given hash, if it is still kept.
+    pub fn serialized_state(&mut self, hash: &B256) -> Option<StateSnapshot> {
+        match self.states.get_mut(hash) {
+            Some(state) => Some(state.serialize_state()),
+            None => self
+                .on_disk_states
+                .contains_key(hash)
+                .then(|| self.disk_cache.read(*hash))
+                .flatten(),
+        }
+    }
+
     pub fn serialized_states(&mut self) -> SerializableHistoricalStates {
         // Get in-memory states
         let mut states = self
@@ -344,7 +356,6 @@ impl BlockchainStorage {
         self.best_number = U64::from(block_number);
     }
 
-    #[allow(unused)]
     pub fn empty() -> Self {
         Self {
             blocks: Default::default(),
@@ -2,9 +2,12 @@
 
 /// [revm](foundry_evm::revm) related types
 pub mod db;
+/// Binary and incremental state dumps
+pub mod dump;
 /// In-memory Backend
 pub mod mem;
 
+pub mod assertions;
 pub mod cheats;
 pub mod time;
 
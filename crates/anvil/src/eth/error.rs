@@ -263,6 +263,13 @@ pub enum InvalidTransactionError {
     /// Thrown when an access list is used before the berlin hard fork.
     #[error("EIP-7702 authorization lists are not supported before the Prague hardfork")]
     AuthorizationListNotSupported,
+    /// Thrown when the transaction invalidates a registered credible-layer assertion.
+    #[error("transaction rejected by assertion: {0}")]
+    AssertionFailed(String),
+    /// Thrown when the transaction could not be validated against the registered credible-layer
+    /// assertions.
+    #[error("transaction rejected, assertions could not be validated: {0}")]
+    AssertionValidation(String),
     /// Forwards error from the revm
     #[error(transparent)]
     Revm(revm::primitives::InvalidTransaction),
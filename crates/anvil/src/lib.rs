@@ -60,6 +60,8 @@ pub mod filter;
 pub mod logging;
 /// types for subscriptions
 pub mod pubsub;
+/// hosting L2 chains next to the node and relaying messages between them
+pub mod relay;
 /// axum RPC server implementations
 pub mod server;
 /// Futures for shutdown signal
@@ -0,0 +1,272 @@
+//! Support for hosting OP-stack L2 chains next to an L1 chain, with a relayer of the messages
+//! between them
+
+use crate::{eth::error::BlockchainError, EthApi, NodeConfig, NodeHandle};
+use alloy_primitives::{address, keccak256, Address, Bytes, TxKind, B256, U256};
+use alloy_rpc_types::{Filter, Log, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use alloy_sol_types::{sol, SolEvent};
+use anvil_core::eth::transaction::optimism::DepositTransaction;
+use futures::StreamExt;
+use std::{path::PathBuf, str::FromStr};
+
+/// The address of the `L2ToL1MessagePasser` predeploy, which emits the withdrawals of an L2.
+pub const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+sol! {
+    /// Emitted by the `OptimismPortal` on L1 for each deposit.
+    event TransactionDeposited(
+        address indexed from,
+        address indexed to,
+        uint256 indexed version,
+        bytes opaqueData
+    );
+
+    /// Emitted by the `L2ToL1MessagePasser` on L2 for each withdrawal.
+    event MessagePassed(
+        uint256 indexed nonce,
+        address indexed sender,
+        address indexed target,
+        uint256 value,
+        uint256 gasLimit,
+        bytes data,
+        bytes32 withdrawalHash
+    );
+}
+
+/// An L2 chain hosted next to the L1 chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct L2Chain {
+    /// The chain id of the L2.
+    pub chain_id: u64,
+    /// The port the L2 node listens on.
+    pub port: u16,
+    /// The address of the `OptimismPortal` of the L2 on L1.
+    pub portal: Address,
+}
+
+impl L2Chain {
+    /// Returns the configuration of the L2 node.
+    ///
+    /// The L2 starts from a fresh default chain: only the host, server and logging settings of
+    /// the L1 node carry over, not its fork, genesis or state. If the L1 chain is persisted on
+    /// disk, the L2 chain is persisted next to it, at `<L1_DB_PATH>-l2-<CHAIN_ID>`.
+    pub fn node_config(&self, l1: &NodeConfig) -> NodeConfig {
+        let db_path = l1.db_path.as_ref().map(|path| {
+            let mut path = path.clone().into_os_string();
+            path.push(format!("-l2-{}", self.chain_id));
+            PathBuf::from(path)
+        });
+        NodeConfig::default()
+            .with_host(l1.host.clone())
+            .with_server_config(l1.server_config.clone())
+            .set_silent(l1.silent)
+            .with_db_path(db_path)
+            .with_chain_id(Some(self.chain_id))
+            .with_port(self.port)
+            .with_optimism(true)
+    }
+}
+
+impl FromStr for L2Chain {
+    type Err = String;
+
+    /// Parses an L2 chain in the form `<CHAIN_ID>:<PORT>:<PORTAL>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let (Some(chain_id), Some(port), Some(portal)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!("expected `<CHAIN_ID>:<PORT>:<PORTAL>`, got `{s}`"));
+        };
+        Ok(Self {
+            chain_id: chain_id
+                .parse()
+                .map_err(|err| format!("invalid chain id `{chain_id}`: {err}"))?,
+            port: port.parse().map_err(|err| format!("invalid port `{port}`: {err}"))?,
+            portal: portal
+                .parse()
+                .map_err(|err| format!("invalid portal address `{portal}`: {err}"))?,
+        })
+    }
+}
+
+/// Spawns a node for each of the L2 `chains` of the L1 node `l1`, which was launched with
+/// `config`, and the relayers between them.
+pub async fn try_spawn_l2s(
+    l1: &EthApi,
+    config: &NodeConfig,
+    chains: &[L2Chain],
+) -> eyre::Result<Vec<(EthApi, NodeHandle)>> {
+    let mut nodes = Vec::with_capacity(chains.len());
+    for chain in chains {
+        let (api, handle) = crate::try_spawn(chain.node_config(config)).await?;
+        handle.task_manager().spawn(Relayer::new(l1.clone(), api.clone(), chain.portal).run());
+        nodes.push((api, handle));
+    }
+    Ok(nodes)
+}
+
+/// Relays the messages between an L1 node and an L2 node.
+///
+/// Each `TransactionDeposited` event of the portal on L1 is injected as a deposit transaction on
+/// L2. Each `MessagePassed` event of the `L2ToL1MessagePasser` on L2 is executed on L1 as a call
+/// of its target by the portal with the gas limit of the withdrawal, the way the portal finalizes
+/// withdrawals, without proving them. Reverting withdrawals are mined like any reverting call.
+///
+/// Withdrawals are sent as unsigned transactions of the portal, without impersonating it, so
+/// other callers can't send transactions as the portal unless they impersonate it themselves.
+pub struct Relayer {
+    l1: EthApi,
+    l2: EthApi,
+    portal: Address,
+}
+
+impl Relayer {
+    pub fn new(l1: EthApi, l2: EthApi, portal: Address) -> Self {
+        Self { l1, l2, portal }
+    }
+
+    /// Relays the messages of each new block of both chains, until either node shuts down.
+    pub async fn run(self) {
+        let mut l1_blocks = self.l1.new_block_notifications();
+        let mut l2_blocks = self.l2.new_block_notifications();
+        loop {
+            let result = tokio::select! {
+                Some(block) = l1_blocks.next() => self.relay_deposits(block.hash).await,
+                Some(block) = l2_blocks.next() => self.relay_withdrawals(block.hash).await,
+                else => break,
+            };
+            if let Err(err) = result {
+                error!(target: "relay", %err, "failed to relay messages");
+            }
+        }
+    }
+
+    async fn relay_deposits(&self, block_hash: B256) -> Result<(), BlockchainError> {
+        let filter = Filter::new()
+            .at_block_hash(block_hash)
+            .address(self.portal)
+            .event_signature(TransactionDeposited::SIGNATURE_HASH);
+        for log in self.l1.logs(filter).await? {
+            let Some(deposit) = deposit_transaction(&log) else {
+                warn!(target: "relay", tx=?log.transaction_hash, "skipping malformed deposit");
+                continue
+            };
+            let mut encoded = Vec::new();
+            deposit.encode_2718(&mut encoded);
+            let hash = self.l2.send_raw_transaction(encoded.into()).await?;
+            trace!(target: "relay", l1_tx=?log.transaction_hash, l2_tx=?hash, "relayed deposit");
+        }
+        Ok(())
+    }
+
+    async fn relay_withdrawals(&self, block_hash: B256) -> Result<(), BlockchainError> {
+        let filter = Filter::new()
+            .at_block_hash(block_hash)
+            .address(L2_TO_L1_MESSAGE_PASSER)
+            .event_signature(MessagePassed::SIGNATURE_HASH);
+        for log in self.l2.logs(filter).await? {
+            let Ok(message) = MessagePassed::decode_log(&log.inner, true).map(|log| log.data)
+            else {
+                warn!(target: "relay", tx=?log.transaction_hash, "skipping malformed withdrawal");
+                continue
+            };
+            let request = TransactionRequest::default()
+                .from(self.portal)
+                .to(message.target)
+                .value(message.value)
+                .gas_limit(withdrawal_gas_limit(message.gasLimit, &message.data))
+                .input(message.data.into());
+            let hash = self.l1.eth_send_unsigned_transaction(WithOtherFields::new(request)).await?;
+            trace!(target: "relay", l2_tx=?log.transaction_hash, l1_tx=?hash, "relayed withdrawal");
+        }
+        Ok(())
+    }
+}
+
+/// Returns the deposit transaction of a `TransactionDeposited` event, or `None` if the event is
+/// malformed or of an unknown version.
+fn deposit_transaction(log: &Log) -> Option<DepositTransaction> {
+    let event = TransactionDeposited::decode_log(&log.inner, true).ok()?.data;
+    if !event.version.is_zero() {
+        return None
+    }
+    // The opaque data of version 0 is the packed encoding of
+    // `(uint256 mint, uint256 value, uint64 gasLimit, bool isCreation, bytes data)`.
+    let data = &event.opaqueData;
+    if data.len() < 73 {
+        return None
+    }
+    let gas_limit = u64::from_be_bytes(data[64..72].try_into().ok()?);
+    let source_hash = deposit_source_hash(log.block_hash?, log.log_index?);
+    Some(DepositTransaction {
+        nonce: 0,
+        source_hash,
+        from: event.from,
+        kind: if data[72] != 0 { TxKind::Create } else { TxKind::Call(event.to) },
+        mint: U256::from_be_slice(&data[..32]),
+        value: U256::from_be_slice(&data[32..64]),
+        gas_limit,
+        is_system_tx: false,
+        input: Bytes::copy_from_slice(&data[73..]),
+    })
+}
+
+/// Returns the gas limit of the transaction finalizing a withdrawal, which leaves the call of its
+/// target with the gas limit of the withdrawal after paying the intrinsic gas of the transaction.
+fn withdrawal_gas_limit(gas_limit: U256, data: &[u8]) -> u64 {
+    let calldata_gas: u64 = data.iter().map(|byte| if *byte == 0 { 4 } else { 16 }).sum();
+    gas_limit.saturating_to::<u64>().saturating_add(21_000 + calldata_gas)
+}
+
+/// Returns the source hash of a user deposit, which identifies the event it was emitted by.
+fn deposit_source_hash(block_hash: B256, log_index: u64) -> B256 {
+    let deposit_id = keccak256([block_hash.0, U256::from(log_index).to_be_bytes()].concat());
+    keccak256([B256::ZERO.0, deposit_id.0].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn can_parse_l2_chain() {
+        let chain: L2Chain = "901:9546:0x5FbDB2315678afecb367f032d93F642f64180aa3".parse().unwrap();
+        assert_eq!(chain.chain_id, 901);
+        assert_eq!(chain.port, 9546);
+        assert_eq!(chain.portal, address!("5FbDB2315678afecb367f032d93F642f64180aa3"));
+        assert!("901:9546".parse::<L2Chain>().is_err());
+    }
+
+    #[test]
+    fn can_decode_deposit() {
+        let (from, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let opaque_data = [
+            U256::from(7).to_be_bytes::<32>().as_slice(),
+            U256::from(5).to_be_bytes::<32>().as_slice(),
+            100_000u64.to_be_bytes().as_slice(),
+            &[0],
+            &[0xab, 0xcd],
+        ]
+        .concat();
+        let event =
+            TransactionDeposited { from, to, version: U256::ZERO, opaqueData: opaque_data.into() };
+        let block_hash = b256!("0101010101010101010101010101010101010101010101010101010101010101");
+        let log = Log {
+            inner: alloy_primitives::Log { address: Address::ZERO, data: event.encode_log_data() },
+            block_hash: Some(block_hash),
+            log_index: Some(3),
+            ..Default::default()
+        };
+
+        let deposit = deposit_transaction(&log).unwrap();
+        assert_eq!(deposit.from, from);
+        assert_eq!(deposit.kind, TxKind::Call(to));
+        assert_eq!(deposit.mint, U256::from(7));
+        assert_eq!(deposit.value, U256::from(5));
+        assert_eq!(deposit.gas_limit, 100_000);
+        assert_eq!(deposit.input, Bytes::from_static(&[0xab, 0xcd]));
+        assert_eq!(deposit.source_hash, deposit_source_hash(block_hash, 3));
+    }
+}
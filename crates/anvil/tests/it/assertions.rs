@@ -0,0 +1,71 @@
+//! credible-layer assertion tests
+
+use alloy_network::{ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{address, bytes, Address, Bytes, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{spawn, NodeConfig};
+
+/// The address of the first contract deployed by the first dev account.
+const COUNTER: Address = address!("5FbDB2315678afecb367f032d93F642f64180aa3");
+
+/// A counter that increments its number when called without calldata and returns it otherwise.
+const COUNTER_CODE: Bytes =
+    bytes!("601a80600b6000396000f336600e57600054600101600055005b60005460005260206000f3");
+
+/// An assertion contract for [`COUNTER`] with a single `assertAtMostOne()` assertion function,
+/// which reverts with `number above one` once the number of the counter is above one.
+const ASSERTION_CODE: Bytes = bytes!("60be80600b6000396000f360003560e01c806395eff8b514601d5763bb08a38014605157600080fd5b602060005260016020527fbb08a3800000000000000000000000000000000000000000000000000000000060405260606000f35b6020600060016000735fbdb2315678afecb367f032d93f642f64180aa35afa15607f57600160005111607f57005b6308c379a060e01b600052602060045260106024527f6e756d6265722061626f7665206f6e650000000000000000000000000000000060445260646000fd");
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_transactions_invalidating_assertions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let sender = handle.dev_accounts().next().unwrap();
+
+    let tx = TransactionRequest::default().with_from(sender).with_deploy_code(COUNTER_CODE);
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert_eq!(receipt.contract_address(), Some(COUNTER));
+
+    api.anvil_register_assertion(
+        COUNTER,
+        ASSERTION_CODE,
+        Some("CounterAssertion".to_string()),
+        Some(vec!["assertAtMostOne()".to_string()]),
+    )
+    .unwrap();
+
+    let increment =
+        WithOtherFields::new(TransactionRequest::default().with_from(sender).with_to(COUNTER));
+
+    // the first increment keeps the number at one and passes the assertion
+    let receipt =
+        provider.send_transaction(increment.clone()).await.unwrap().get_receipt().await.unwrap();
+    assert!(receipt.status());
+    let block_number = provider.get_block_number().await.unwrap();
+
+    // the second increment invalidates the assertion and is rejected
+    let err = provider.send_transaction(increment.clone()).await.unwrap_err().to_string();
+    assert!(err.contains("transaction rejected by assertion"), "{err}");
+    assert!(err.contains("CounterAssertion::assertAtMostOne() reverted"), "{err}");
+    assert!(err.contains("number above one"), "{err}");
+
+    assert_eq!(provider.get_block_number().await.unwrap(), block_number);
+    let get_number = WithOtherFields::new(
+        TransactionRequest::default().with_to(COUNTER).with_input(bytes!("00")),
+    );
+    let number = provider.call(&get_number).await.unwrap();
+    assert_eq!(U256::from_be_slice(&number), U256::from(1));
+
+    // without the assertion, the increment is mined
+    assert!(api.anvil_remove_assertion(COUNTER).unwrap());
+    let receipt = provider.send_transaction(increment).await.unwrap().get_receipt().await.unwrap();
+    assert!(receipt.status());
+}
@@ -15,7 +15,7 @@ use alloy_rpc_types::{
 };
 use alloy_serde::WithOtherFields;
 use alloy_signer_local::PrivateKeySigner;
-use anvil::{eth::EthApi, spawn, NodeConfig, NodeHandle};
+use anvil::{eth::EthApi, spawn, try_spawn, NodeConfig, NodeHandle};
 use foundry_common::provider::get_http_provider;
 use foundry_config::Config;
 use foundry_test_utils::rpc::{self, next_http_rpc_endpoint, next_rpc_endpoint};
@@ -99,6 +99,53 @@ async fn test_spawn_fork() {
     assert_eq!(head, U256::from(BLOCK_NUMBER))
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_with_fallback_urls() {
+    // nothing listens on port 1, requests to it fail on connect
+    let dead_url = "http://127.0.0.1:1".to_string();
+
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    origin_api.anvil_set_balance(Address::random(), U256::from(1337)).await.unwrap();
+    origin_api.mine_one().await;
+    let origin_block = origin_handle
+        .http_provider()
+        .get_block(BlockId::number(1), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // same height, different state root and thus a different block hash
+    let (other_api, other_handle) = spawn(NodeConfig::test()).await;
+    other_api.mine_one().await;
+
+    let config = || {
+        NodeConfig::test()
+            .with_eth_rpc_url(Some(origin_handle.http_endpoint()))
+            .with_fork_block_number(Some(1u64))
+    };
+
+    // a dead fallback endpoint doesn't prevent forking
+    let (api, handle) = spawn(
+        config().with_fork_fallback_urls(vec![dead_url.clone(), origin_handle.http_endpoint()]),
+    )
+    .await;
+    assert!(api.is_fork());
+    let block = handle
+        .http_provider()
+        .get_block(BlockId::number(1), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.header.hash, origin_block.header.hash);
+
+    // but an endpoint on another chain does
+    let err =
+        try_spawn(config().with_fork_fallback_urls(vec![dead_url, other_handle.http_endpoint()]))
+            .await
+            .unwrap_err();
+    assert!(format!("{err:?}").contains("fork endpoints disagree"), "{err:?}");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_eth_get_balance() {
     let (api, handle) = spawn(fork_config()).await;
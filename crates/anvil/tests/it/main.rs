@@ -4,6 +4,7 @@ mod abi;
 mod anvil;
 mod anvil_api;
 mod api;
+mod assertions;
 mod eip4844;
 mod eip7702;
 mod fork;
@@ -13,6 +14,7 @@ mod ipc;
 mod logs;
 mod optimism;
 mod otterscan;
+mod persistence;
 mod proof;
 mod pubsub;
 mod revert;
@@ -3,11 +3,16 @@
 use crate::utils::{http_provider, http_provider_with_signer};
 use alloy_eips::eip2718::Encodable2718;
 use alloy_network::{EthereumWallet, TransactionBuilder};
-use alloy_primitives::{b256, Address, TxHash, TxKind, U256};
+use alloy_primitives::{b256, hex, Address, Bytes, LogData, TxHash, TxKind, B256, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::TransactionRequest;
+use alloy_rpc_types::{BlockTransactionsKind, TransactionRequest};
 use alloy_serde::WithOtherFields;
-use anvil::{spawn, EthereumHardfork, NodeConfig};
+use alloy_sol_types::SolEvent;
+use anvil::{
+    eth::EthApi,
+    relay::{try_spawn_l2s, L2Chain, MessagePassed, TransactionDeposited, L2_TO_L1_MESSAGE_PASSER},
+    spawn, EthereumHardfork, NodeConfig,
+};
 use anvil_core::eth::transaction::optimism::DepositTransaction;
 use op_alloy_rpc_types::OpTransactionFields;
 
@@ -233,3 +238,121 @@ async fn test_deposit_tx_checks_sufficient_funds_after_applying_deposited_value(
     // recipient should've received the entire deposited value
     assert_eq!(recipient_new_balance, U256::from(send_value));
 }
+
+/// Sends a transaction from `from` to `emitter`, which has the code of a contract emitting its
+/// calldata as an event with four topics, so that it emits `log`.
+async fn emit_log(api: &EthApi, from: Address, emitter: Address, log: LogData) {
+    // CALLDATACOPY everything after the topics to memory and LOG4 it with the topics.
+    let code = hex!("608036038060805f376060356040356020355f35845fa400");
+    api.anvil_set_code(emitter, Bytes::from_static(&code)).await.unwrap();
+    let mut input = log.topics().iter().flat_map(|topic| topic.0).collect::<Vec<_>>();
+    input.extend_from_slice(&log.data);
+    let tx = TransactionRequest::default().from(from).to(emitter).input(Bytes::from(input).into());
+    api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+}
+
+async fn wait_for_balance(api: &EthApi, address: Address, balance: U256) {
+    for _ in 0..50 {
+        if api.balance(address, None).await.unwrap() == balance {
+            return
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("balance of {address} did not reach {balance}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_relays_messages_between_l1_and_l2() {
+    let config = NodeConfig::test();
+    let (l1, l1_handle) = spawn(config.clone()).await;
+    let portal = Address::repeat_byte(0x99);
+    let mut l2s =
+        try_spawn_l2s(&l1, &config, &[L2Chain { chain_id: 901, port: 0, portal }]).await.unwrap();
+    let (l2, l2_handle) = l2s.remove(0);
+    assert_eq!(l2.chain_id(), 901);
+
+    // deposit from L1 to L2
+    let recipient = Address::random();
+    let opaque_data = [
+        U256::from(1000).to_be_bytes::<32>().as_slice(),
+        U256::from(1000).to_be_bytes::<32>().as_slice(),
+        21_000u64.to_be_bytes().as_slice(),
+        &[0],
+    ]
+    .concat();
+    let deposit = TransactionDeposited {
+        from: Address::random(),
+        to: recipient,
+        version: U256::ZERO,
+        opaqueData: opaque_data.into(),
+    };
+    let from = l1_handle.dev_accounts().next().unwrap();
+    emit_log(&l1, from, portal, deposit.encode_log_data()).await;
+    wait_for_balance(&l2, recipient, U256::from(1000)).await;
+
+    // withdrawal from L2 to L1
+    let target = Address::random();
+    l1.anvil_set_balance(portal, U256::from(10u128.pow(18))).await.unwrap();
+    let withdrawal = MessagePassed {
+        nonce: U256::ZERO,
+        sender: from,
+        target,
+        value: U256::from(500),
+        gasLimit: U256::from(21_000),
+        data: Bytes::new(),
+        withdrawalHash: B256::ZERO,
+    };
+    let from = l2_handle.dev_accounts().next().unwrap();
+    emit_log(&l2, from, L2_TO_L1_MESSAGE_PASSER, withdrawal.encode_log_data()).await;
+    wait_for_balance(&l1, target, U256::from(500)).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_relays_reverting_withdrawal() {
+    let config = NodeConfig::test();
+    let (l1, l1_handle) = spawn(config.clone()).await;
+    let provider = http_provider(&l1_handle.http_endpoint());
+    let portal = Address::repeat_byte(0x99);
+    let mut l2s =
+        try_spawn_l2s(&l1, &config, &[L2Chain { chain_id: 901, port: 0, portal }]).await.unwrap();
+    let (l2, l2_handle) = l2s.remove(0);
+
+    // a target looping until it runs out of gas
+    let target = Address::random();
+    l1.anvil_set_code(target, Bytes::from_static(&hex!("5b5f56"))).await.unwrap();
+    l1.anvil_set_balance(portal, U256::from(10u128.pow(18))).await.unwrap();
+    let withdrawal = MessagePassed {
+        nonce: U256::ZERO,
+        sender: Address::random(),
+        target,
+        value: U256::from(500),
+        gasLimit: U256::from(50_000),
+        data: Bytes::new(),
+        withdrawalHash: B256::ZERO,
+    };
+    let number = provider.get_block_number().await.unwrap();
+    let from = l2_handle.dev_accounts().next().unwrap();
+    emit_log(&l2, from, L2_TO_L1_MESSAGE_PASSER, withdrawal.encode_log_data()).await;
+
+    let mut block = None;
+    for _ in 0..50 {
+        block = provider
+            .get_block_by_number((number + 1).into(), BlockTransactionsKind::Hashes)
+            .await
+            .unwrap();
+        if block.is_some() {
+            break
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    let hash = block.expect("withdrawal was not relayed").transactions.hashes().next().unwrap();
+    let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+    assert!(!receipt.status());
+    assert_eq!(receipt.from, portal);
+    // the call of the target was given the gas limit of the withdrawal
+    assert_eq!(receipt.gas_used, 21_000 + 50_000);
+    assert_eq!(l1.balance(target, None).await.unwrap(), U256::ZERO);
+
+    // the withdrawal was relayed without impersonating the portal
+    assert!(!l1.is_impersonated(portal));
+}
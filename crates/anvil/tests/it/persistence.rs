@@ -0,0 +1,172 @@
+//! tests for persisting the chain on disk
+
+use crate::abi::Greeter;
+use alloy_network::{ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, BlockTransactionsKind, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use anvil::{eth::EthApi, spawn, try_spawn, NodeConfig, NodeHandle};
+use std::time::Duration;
+
+/// Spawns a node with the `config` persisting its chain on disk, waiting for a previous node
+/// using the same database to shut down.
+async fn spawn_with_db(config: NodeConfig) -> (EthApi, NodeHandle) {
+    for _ in 0..50 {
+        if let Ok(node) = try_spawn(config.clone()).await {
+            return node
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    spawn(config).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_restore_persisted_chain() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("db");
+
+    let (api, handle) = spawn_with_db(NodeConfig::test().with_db_path(Some(db_path.clone()))).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = Address::random();
+
+    api.mine_one().await;
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1337));
+    let transfer = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    let greeter = Greeter::deploy(&provider, "Hello".to_string()).await.unwrap();
+    let address = *greeter.address();
+    let deploy_number = provider.get_block_number().await.unwrap();
+    greeter.setGreeting("World!".to_string()).send().await.unwrap().get_receipt().await.unwrap();
+    api.mine_one().await;
+
+    let best = provider
+        .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    let code = provider.get_code_at(address).await.unwrap();
+    drop(provider);
+    drop(api);
+    drop(handle);
+
+    let (api, handle) = spawn_with_db(NodeConfig::test().with_db_path(Some(db_path.clone()))).await;
+    let provider = handle.http_provider();
+
+    // the blocks and receipts are restored
+    assert_eq!(provider.get_block_number().await.unwrap(), best.header.number);
+    let restored = provider
+        .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.header.hash, best.header.hash);
+    let receipt =
+        provider.get_transaction_receipt(transfer.transaction_hash).await.unwrap().unwrap();
+    assert!(receipt.status());
+    assert_eq!(receipt.block_hash, transfer.block_hash);
+
+    // the state is restored
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1337));
+    assert_eq!(provider.get_code_at(address).await.unwrap(), code);
+    let greeter = Greeter::new(address, &provider);
+    assert_eq!(greeter.greet().call().await.unwrap()._0, "World!");
+
+    // and so are the states at past blocks
+    let past = BlockId::number(deploy_number - 1);
+    assert_eq!(provider.get_code_at(address).block_id(past).await.unwrap(), Bytes::new());
+    let deployed = BlockId::number(deploy_number);
+    assert_eq!(greeter.greet().block(deployed).call().await.unwrap()._0, "Hello");
+
+    // the chain goes on from the restored block
+    api.mine_one().await;
+    let next = provider
+        .get_block(BlockNumberOrTag::Latest.into(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.header.number, best.header.number + 1);
+    assert_eq!(next.header.parent_hash, best.header.hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1337));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_restore_persisted_forked_chain() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("db");
+
+    // state of the origin chain, which the fork only reads
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let origin = origin_handle.http_provider();
+    let greeter = Greeter::deploy(&origin, "Hello".to_string()).await.unwrap();
+    let address = *greeter.address();
+    let remote = Address::random();
+    origin_api.anvil_set_balance(remote, U256::from(1000)).await.unwrap();
+    origin_api.mine_one().await;
+    let fork_number = origin.get_block_number().await.unwrap();
+
+    let config = || {
+        NodeConfig::test()
+            .with_eth_rpc_url(Some(origin_handle.http_endpoint()))
+            .with_fork_block_number(Some(fork_number))
+            .with_db_path(Some(db_path.clone()))
+    };
+
+    let (api, handle) = spawn_with_db(config()).await;
+    assert!(api.is_fork());
+    let provider = handle.http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+    let to = Address::random();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(1337));
+    provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+    let greeter = Greeter::new(address, &provider);
+    greeter.setGreeting("World!".to_string()).send().await.unwrap().get_receipt().await.unwrap();
+
+    let best = provider
+        .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(best.header.number, fork_number + 2);
+    drop(greeter);
+    drop(provider);
+    drop(api);
+    drop(handle);
+
+    let (api, handle) = spawn_with_db(config()).await;
+    let provider = handle.http_provider();
+
+    // the blocks mined on top of the fork are restored
+    assert_eq!(provider.get_block_number().await.unwrap(), best.header.number);
+    let restored = provider
+        .get_block(BlockId::latest(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.header.hash, best.header.hash);
+
+    // the local changes shadow the forked state, the rest is still read from the fork
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1337));
+    let greeter = Greeter::new(address, &provider);
+    assert_eq!(greeter.greet().call().await.unwrap()._0, "World!");
+    assert_eq!(provider.get_balance(remote).await.unwrap(), U256::from(1000));
+    assert_eq!(origin.get_balance(to).await.unwrap(), U256::ZERO);
+
+    // the chain goes on from the restored block
+    api.mine_one().await;
+    let next = provider
+        .get_block(BlockNumberOrTag::Latest.into(), BlockTransactionsKind::Hashes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(next.header.parent_hash, best.header.hash);
+    assert_eq!(greeter.greet().call().await.unwrap()._0, "World!");
+}
@@ -2,11 +2,14 @@
 
 use crate::abi::Greeter;
 use alloy_network::{ReceiptResponse, TransactionBuilder};
-use alloy_primitives::{address, utils::Unit, Bytes, Uint, U256, U64};
+use alloy_primitives::{address, utils::Unit, Address, Bytes, Uint, U256, U64};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, TransactionRequest};
 use alloy_serde::WithOtherFields;
-use anvil::{spawn, NodeConfig};
+use anvil::{
+    eth::backend::dump::{self, DumpChunk, StateFormat},
+    spawn, NodeConfig,
+};
 use foundry_test_utils::rpc::next_http_rpc_endpoint;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -276,3 +279,53 @@ async fn test_fork_load_state_with_greater_state_block() {
 
     assert_eq!(new_block_number, block_number);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_dump_state_incrementally() {
+    let (api, handle) = spawn(NodeConfig::test().with_state_format(StateFormat::Incremental)).await;
+    let provider = handle.http_provider();
+    let alice = Address::random();
+
+    let DumpChunk::Full(mut buf) = api.backend.incremental_dump(false).await.unwrap() else {
+        panic!("first dump is not complete")
+    };
+    let DumpChunk::Delta(delta) = api.backend.incremental_dump(false).await.unwrap() else {
+        panic!("unchanged state is dumped completely")
+    };
+    assert!(delta.is_empty());
+
+    let tx = TransactionRequest::default()
+        .with_from(handle.dev_accounts().next().unwrap())
+        .with_to(alice)
+        .with_value(U256::from(1));
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    api.anvil_set_balance(alice, U256::from(7)).await.unwrap();
+    let DumpChunk::Delta(delta) = api.backend.incremental_dump(false).await.unwrap() else {
+        panic!("changes are dumped completely")
+    };
+    buf.extend(delta);
+
+    let state = dump::decode(&buf).unwrap();
+    assert_eq!(state.best_block_number, Some(U64::from(1)));
+    assert_eq!(state.blocks.len(), 2);
+    assert_eq!(state.transactions.len(), 1);
+    assert_eq!(state.transactions[0].info.transaction_hash, receipt.transaction_hash);
+    assert_eq!(state.accounts[&alice].balance, U256::from(7));
+
+    // reverted blocks are dropped by a complete dump
+    let id = api.evm_snapshot().await.unwrap();
+    api.mine_one().await;
+    api.evm_revert(id).await.unwrap();
+    let DumpChunk::Full(buf) = api.backend.incremental_dump(false).await.unwrap() else {
+        panic!("reverted state is not dumped completely")
+    };
+    let state = dump::decode(&buf).unwrap();
+    assert_eq!(state.best_block_number, Some(U64::from(1)));
+    assert_eq!(state.blocks.len(), 2);
+}
@@ -31,10 +31,58 @@ interface Vm {
         /// The abi encoded calldata of the transaction
         bytes data;
     }
-    /// Gets the address for a given private key.
+    /// An assertion contract registered against an adopter for assertion execution testing
+    struct AssertionExContract {
+        /// The address of the contract adopting the assertion
+        address adopter;
+        /// The creation code of the assertion contract, including its constructor arguments
+        bytes assertionContract;
+        /// The label of the assertion contract used in reports
+        string label;
+    }
+
+    /// The outcome of an assertion function executed by `assertionEx`. Returned by `getAssertionExResults`.
+    struct AssertionExResult {
+        /// The index of the transaction in the executed block which triggered the assertion function
+        uint256 txIndex;
+        /// The address of the adopter the assertion contract is registered for
+        address adopter;
+        /// The label of the assertion contract
+        string label;
+        /// The selector of the assertion function
+        bytes4 selector;
+        /// The name of the assertion function, decoded from the assertion contract's ABI if available
+        string functionName;
+        /// The gas used by the assertion function
+        uint64 gasUsed;
+        /// Whether the assertion function succeeded
+        bool success;
+        /// The raw revert data of the assertion function, empty if it succeeded
+        bytes revertData;
+        /// The decoded revert reason of the assertion function, empty if it succeeded
+        string revertReason;
+    }
+
+    /// Executes the abi encoded `AssertionExTransaction` and validates it against the assertions of
+    /// `assertionContract`, registered for `assertionAdopter`.
     #[cheatcode(group = Credible, safety = Safe)]
     function assertionEx(bytes calldata tx, address assertionAdopter, bytes calldata assertionContract, string calldata assertionContractLabel) external;
 
+    /// Executes the abi encoded `AssertionExTransaction`s in order as a single block and validates each
+    /// of them against the assertions of `assertionContract`, registered for `assertionAdopter`.
+    #[cheatcode(group = Credible, safety = Safe)]
+    function assertionEx(bytes[] calldata txs, address assertionAdopter, bytes calldata assertionContract, string calldata assertionContractLabel) external;
+
+    /// Executes the abi encoded `AssertionExTransaction`s in order as a single block and validates each
+    /// of them against all of the given assertion contracts.
+    #[cheatcode(group = Credible, safety = Safe)]
+    function assertionExBatch(bytes[] calldata txs, AssertionExContract[] calldata assertions) external;
+
+    /// Returns the results of all assertion functions executed by the last `assertionEx` call, including
+    /// the ones executed before it reverted.
+    #[cheatcode(group = Credible, safety = Safe)]
+    function getAssertionExResults() external view returns (AssertionExResult[] memory results);
+
 
     //  ======== Types ========
 
@@ -685,6 +733,15 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Unsafe)]
     function snapshotGasLastCall(string calldata group, string calldata name) external returns (uint256 gasUsed);
 
+    /// Snapshot capture the total gas usage of the assertion functions executed by the last `assertionEx` call by name.
+    /// The group name is derived from the contract name.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function snapshotGasLastAssertionEx(string calldata name) external returns (uint256 gasUsed);
+
+    /// Snapshot capture the total gas usage of the assertion functions executed by the last `assertionEx` call by name in a group.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function snapshotGasLastAssertionEx(string calldata group, string calldata name) external returns (uint256 gasUsed);
+
     /// Start a snapshot capture of the current gas usage by name.
     /// The group name is derived from the contract name.
     #[cheatcode(group = Evm, safety = Unsafe)]
@@ -4,8 +4,8 @@ use alloy_primitives::{map::AddressHashMap, U256};
 use foundry_common::{fs::normalize_path, ContractsByArtifact};
 use foundry_compilers::{utils::canonicalize, ProjectPathsConfig};
 use foundry_config::{
-    cache::StorageCachingConfig, fs_permissions::FsAccessKind, Config, FsPermissions,
-    ResolvedRpcEndpoint, ResolvedRpcEndpoints, RpcEndpoint, RpcEndpointUrl,
+    cache::StorageCachingConfig, fs_permissions::FsAccessKind, Config, CredibleConfig,
+    FsPermissions, ResolvedRpcEndpoint, ResolvedRpcEndpoints, RpcEndpoint, RpcEndpointUrl,
 };
 use foundry_evm_core::opts::EvmOpts;
 use semver::Version;
@@ -57,6 +57,10 @@ pub struct CheatsConfig {
     pub assertions_revert: bool,
     /// Optional seed for the RNG algorithm.
     pub seed: Option<U256>,
+    /// Whether to avoid network requests, e.g. when identifying function signatures.
+    pub offline: bool,
+    /// Credible layer assertion execution settings.
+    pub credible: CredibleConfig,
 }
 
 impl CheatsConfig {
@@ -98,6 +102,8 @@ impl CheatsConfig {
             running_version,
             assertions_revert: config.assertions_revert,
             seed: config.fuzz.seed,
+            offline: config.offline,
+            credible: config.credible.clone(),
         }
     }
 
@@ -234,6 +240,8 @@ impl Default for CheatsConfig {
             running_version: Default::default(),
             assertions_revert: true,
             seed: None,
+            offline: false,
+            credible: Default::default(),
         }
     }
 }
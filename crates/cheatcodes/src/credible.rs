@@ -1,25 +1,106 @@
-use crate::{Cheatcode, CheatcodesExecutor, CheatsCtxt, Result, Vm::*};
-use alloy_primitives::{Bytes, TxKind};
+use crate::{Cheatcode, Cheatcodes, CheatcodesExecutor, CheatsCtxt, Result, Vm::*};
+use alloy_json_abi::{Function, JsonAbi};
+use alloy_primitives::{keccak256, Bytes, Selector, TxKind};
 use alloy_sol_types::{Revert, SolError, SolValue};
 use assertion_executor::{
     db::fork_db::ForkDb,
     store::{AssertionState, AssertionStore},
     ExecutorConfig, ExecutorError,
 };
+use foundry_config::Config;
 use foundry_evm_core::backend::{DatabaseError, DatabaseExt};
+use foundry_evm_traces::{
+    identifier::SignaturesIdentifier, CallKind, CallTrace, CallTraceArena, CallTraceNode,
+    TracingInspector, TracingInspectorConfig,
+};
 use revm::{
-    primitives::{AccountInfo, Address, Bytecode, ExecutionResult, TxEnv, B256, U256},
-    DatabaseCommit, DatabaseRef,
+    db::CacheDB,
+    interpreter::InstructionResult,
+    primitives::{
+        AccountInfo, Address, Bytecode, Env, ExecutionResult, SpecId, TxEnv, B256, KECCAK_EMPTY,
+        U256,
+    },
+    DatabaseCommit, DatabaseRef, Evm,
 };
+use revm_inspectors::tracing::types::TraceMemberOrder;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
 };
 
+mod replay;
+use replay::StateRecorder;
+pub use replay::{AssertionReplay, ReplayAccount, ReplayAssertion, ReplayedTransaction};
+
+/// The outcome of an assertion function executed by an `assertionEx` cheatcode call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssertionResult {
+    /// Index of the transaction in the executed block which triggered the assertion function.
+    pub tx_index: usize,
+    /// Address of the adopter the assertion contract is registered for.
+    pub adopter: Address,
+    /// Label of the assertion contract.
+    pub label: String,
+    /// Selector of the assertion function.
+    pub selector: Selector,
+    /// Name of the assertion function, if it could be decoded from the assertion contract's ABI.
+    pub function_name: Option<String>,
+    /// Gas used by the assertion function.
+    pub gas_used: u64,
+    /// Whether the assertion function succeeded.
+    pub success: bool,
+    /// Raw revert data of the assertion function, empty if it succeeded.
+    pub revert_data: Bytes,
+    /// Decoded revert reason of the assertion function, if it reverted.
+    pub revert_reason: Option<String>,
+}
+
+impl AssertionResult {
+    /// Converts the result into its Solidity representation.
+    fn to_sol(&self) -> AssertionExResult {
+        AssertionExResult {
+            txIndex: U256::from(self.tx_index),
+            adopter: self.adopter,
+            label: self.label.clone(),
+            selector: self.selector,
+            functionName: self.function_name.clone().unwrap_or_default(),
+            gasUsed: self.gas_used,
+            success: self.success,
+            revertData: self.revert_data.clone(),
+            revertReason: self.revert_reason.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Report metadata of an assertion contract registered for an adopter.
+struct AssertionContractInfo<'a> {
+    /// Label of the assertion contract.
+    label: &'a str,
+    /// Address the assertion contract is attributed to in call traces.
+    address: Address,
+    /// Assertion functions by selector, decoded from the assertion contract's ABI or identified
+    /// by their signature.
+    functions: HashMap<Selector, Function>,
+}
+
+impl AssertionContractInfo<'_> {
+    /// Returns the signature of the assertion function with the given selector, or the raw
+    /// selector and its index if the function is unknown.
+    fn fn_display(&self, selector: Selector, index: usize) -> String {
+        match self.functions.get(&selector) {
+            Some(function) => function.signature(),
+            None => format!("[selector {selector}:index {index}]"),
+        }
+    }
+}
+
 /// Wrapper around DatabaseExt to make it thread-safe
 #[derive(Clone)]
 struct ThreadSafeDb<'a> {
     db: Arc<Mutex<&'a mut dyn DatabaseExt>>,
+    /// Records the state read from the database, if a replay file is written on failure.
+    recorder: Option<Arc<Mutex<StateRecorder>>>,
 }
 
 impl std::fmt::Debug for ThreadSafeDb<'_> {
@@ -31,8 +112,8 @@ impl std::fmt::Debug for ThreadSafeDb<'_> {
 /// Separate implementation block for constructor and helper methods
 impl<'a> ThreadSafeDb<'a> {
     /// Creates a new thread-safe database wrapper
-    pub fn new(db: &'a mut dyn DatabaseExt) -> Self {
-        Self { db: Arc::new(Mutex::new(db)) }
+    pub fn new(db: &'a mut dyn DatabaseExt, recorder: Option<Arc<Mutex<StateRecorder>>>) -> Self {
+        Self { db: Arc::new(Mutex::new(db)), recorder }
     }
 }
 
@@ -41,7 +122,19 @@ impl DatabaseRef for ThreadSafeDb<'_> {
     type Error = DatabaseError;
 
     fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
-        self.db.lock().unwrap().basic(address)
+        let mut db = self.db.lock().unwrap();
+        let info = db.basic(address)?;
+        if let (Some(recorder), Some(info)) = (&self.recorder, &info) {
+            let code = match &info.code {
+                Some(code) => code.original_bytes(),
+                None if info.code_hash != KECCAK_EMPTY => {
+                    db.code_by_hash(info.code_hash)?.original_bytes()
+                }
+                None => Bytes::new(),
+            };
+            recorder.lock().unwrap().record_account(address, info, code);
+        }
+        Ok(info)
     }
 
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
@@ -49,15 +142,23 @@ impl DatabaseRef for ThreadSafeDb<'_> {
     }
 
     fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
-        self.db.lock().unwrap().storage(address, index)
+        let value = self.db.lock().unwrap().storage(address, index)?;
+        if let Some(recorder) = &self.recorder {
+            recorder.lock().unwrap().record_storage(address, index, value);
+        }
+        Ok(value)
     }
 
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
-        self.db.lock().unwrap().block_hash(number)
+        let hash = self.db.lock().unwrap().block_hash(number)?;
+        if let Some(recorder) = &self.recorder {
+            recorder.lock().unwrap().record_block_hash(number, hash);
+        }
+        Ok(hash)
     }
 }
 
-impl Cheatcode for assertionExCall {
+impl Cheatcode for assertionEx_0Call {
     fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor) -> Result {
         let Self {
             tx,
@@ -65,84 +166,274 @@ impl Cheatcode for assertionExCall {
             assertionContract,
             assertionContractLabel,
         } = self;
+        let assertion = AssertionExContract {
+            adopter: *assertion_adopter,
+            assertionContract: assertionContract.clone(),
+            label: assertionContractLabel.clone(),
+        };
+        execute_assertions(ccx, executor, std::slice::from_ref(tx), &[assertion])
+    }
+}
 
-        let spec_id = ccx.ecx.spec_id();
-        let block = ccx.ecx.env.block.clone();
-        let state = ccx.ecx.journaled_state.state.clone();
-        let chain_id = ccx.ecx.env.cfg.chain_id;
+impl Cheatcode for assertionEx_1Call {
+    fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor) -> Result {
+        let Self {
+            txs,
+            assertionAdopter: assertion_adopter,
+            assertionContract,
+            assertionContractLabel,
+        } = self;
+        let assertion = AssertionExContract {
+            adopter: *assertion_adopter,
+            assertionContract: assertionContract.clone(),
+            label: assertionContractLabel.clone(),
+        };
+        execute_assertions(ccx, executor, txs, &[assertion])
+    }
+}
 
-        // Setup assertion database
-        let db = ThreadSafeDb::new(ccx.ecx.db);
+impl Cheatcode for assertionExBatchCall {
+    fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor) -> Result {
+        let Self { txs, assertions } = self;
+        execute_assertions(ccx, executor, txs, assertions)
+    }
+}
 
-        // Prepare assertion store
-        let assertion_contract_bytecode = Bytes::from(assertionContract.to_vec());
+impl Cheatcode for getAssertionExResultsCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self {} = self;
+        let results = state
+            .assertion_results
+            .last()
+            .map(|results| results.iter().map(AssertionResult::to_sol).collect::<Vec<_>>())
+            .unwrap_or_default();
+        Ok(results.abi_encode())
+    }
+}
 
-        let config = ExecutorConfig { spec_id, chain_id, assertion_gas_limit: 3_000_000 };
+/// Executes the abi encoded `AssertionExTransaction`s in order as a single block and validates
+/// each of them against the given assertion contracts.
+///
+/// Execution stops at the first transaction which reverts or invalidates an assertion.
+fn execute_assertions(
+    ccx: &mut CheatsCtxt,
+    executor: &mut dyn CheatcodesExecutor,
+    txs: &[Bytes],
+    assertions: &[AssertionExContract],
+) -> Result {
+    // Record the results of this call before anything can fail, so that they are available even if
+    // it reverts and never refer to a previous call.
+    ccx.state.assertion_results.push(Vec::new());
 
-        let store = match AssertionStore::new_ephemeral() {
-            Ok(store) => store,
-            Err(e) => {
-                executor.console_log(ccx, format!("Error: {e}"));
-                executor.console_log(
-                    ccx,
-                    "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
-                );
-                bail!("Assertion store creation failed");
-            }
+    ensure!(!txs.is_empty(), "no transactions provided");
+    ensure!(!assertions.is_empty(), "no assertion contracts provided");
+
+    let spec_id = ccx.ecx.spec_id();
+    let cfg = ccx.ecx.env.cfg.clone();
+    let block = ccx.ecx.env.block.clone();
+    let state = ccx.ecx.journaled_state.state.clone();
+    let chain_id = ccx.ecx.env.cfg.chain_id;
+    let credible = ccx.state.config.credible.clone();
+    let gas_limit = credible
+        .tx_gas_limit
+        .unwrap_or_else(|| ccx.ecx.env.block.gas_limit.try_into().unwrap_or(u64::MAX));
+
+    let config =
+        ExecutorConfig { spec_id, chain_id, assertion_gas_limit: credible.assertion_gas_limit };
+
+    let store = match AssertionStore::new_ephemeral() {
+        Ok(store) => store,
+        Err(e) => {
+            executor.console_log(ccx, format!("Error: {e}"));
+            executor.console_log(
+                ccx,
+                "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
+            );
+            bail!("Assertion store creation failed");
+        }
+    };
+
+    // Insert assertion contracts into store
+    let mut contracts = HashMap::with_capacity(assertions.len());
+    for AssertionExContract { adopter, assertionContract, label } in assertions {
+        let info = AssertionContractInfo {
+            label,
+            address: assertion_address(assertionContract),
+            functions: assertion_fns(ccx.state, assertionContract),
         };
+        if contracts.insert(*adopter, info).is_some() {
+            bail!("multiple assertion contracts provided for adopter {adopter}");
+        }
 
-        // Insert assertion contract into store
+        let assertion_contract_bytecode = Bytes::from(assertionContract.to_vec());
         let assertion_state = AssertionState::new_active(assertion_contract_bytecode, &config)
             .map_err(|e| format!("Assertion Executor Error: {e:#?}"))?;
 
-        match store.insert(*assertion_adopter, assertion_state) {
-            Ok(_) => (), // Successfully inserted (either first time or replacing existing)
-            Err(e) => {
-                executor.console_log(ccx, format!("Error: {e}"));
-                executor.console_log(
-                    ccx,
-                    "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
-                );
-                bail!("Assertion insertion failed");
-            }
+        if let Err(e) = store.insert(*adopter, assertion_state) {
+            executor.console_log(ccx, format!("Error: {e}"));
+            executor.console_log(
+                ccx,
+                "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
+            );
+            bail!("Assertion insertion failed");
         }
-        let decoded_tx = AssertionExTransaction::abi_decode(tx, true)
-            .map_err(|e| format!("Failed to decode transaction: {e}"))?;
-
-        let tx_env = TxEnv {
-            caller: decoded_tx.from,
-            gas_limit: ccx.ecx.env.block.gas_limit.try_into().unwrap_or(u64::MAX),
-            transact_to: TxKind::Call(decoded_tx.to),
-            value: decoded_tx.value,
-            data: decoded_tx.data,
-            chain_id: Some(chain_id),
-            ..Default::default()
-        };
+    }
 
+    let tx_envs = txs
+        .iter()
+        .map(|tx| {
+            let decoded_tx = AssertionExTransaction::abi_decode(tx, true)
+                .map_err(|e| format!("Failed to decode transaction: {e}"))?;
+            Ok(TxEnv {
+                caller: decoded_tx.from,
+                gas_limit,
+                transact_to: TxKind::Call(decoded_tx.to),
+                value: decoded_tx.value,
+                data: decoded_tx.data,
+                chain_id: Some(chain_id),
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The triggering transactions are traced separately, as the assertion executor runs them in
+    // its own EVM.
+    let trace_config =
+        executor.tracing_inspector().and_then(|tracer| tracer.as_ref()).and_then(|tracer| {
+            // The last recorded call is the call to this cheatcode.
+            let parent = tracer.traces().nodes().len().checked_sub(1)?;
+            Some((*tracer.config(), parent))
+        });
+
+    // Record the inputs of the call, so that a replay file can be written if it fails.
+    let recorder =
+        credible.replay_dir.as_ref().map(|_| Arc::new(Mutex::new(StateRecorder::default())));
+    let replay = recorder.as_ref().map(|_| AssertionReplay {
+        spec_id,
+        cfg: cfg.clone(),
+        assertion_gas_limit: credible.assertion_gas_limit,
+        block: block.clone(),
+        pre_state: Default::default(),
+        block_hashes: Default::default(),
+        transactions: tx_envs.clone(),
+        assertions: assertions
+            .iter()
+            .map(|assertion| ReplayAssertion {
+                adopter: assertion.adopter,
+                label: assertion.label.clone(),
+                code: assertion.assertionContract.clone(),
+                abi: assertion_abi(ccx.state, &assertion.assertionContract),
+            })
+            .collect(),
+    });
+    let committed_state = recorder.as_ref().map(|_| state.clone());
+
+    // Execute the transactions in order on top of each other, stopping at the first one that
+    // reverts or invalidates an assertion, unless assertion failures are only reported as warnings.
+    let (results, mut tx_traces) = {
+        // Setup assertion database
+        let db = ThreadSafeDb::new(ccx.ecx.db, recorder.clone());
         let mut assertion_executor = config.build(db, store);
 
         // Commit current journal state so that it is available for assertions and
-        // triggering tx
+        // triggering txs
         let mut fork_db = ForkDb::new(assertion_executor.db.clone());
         fork_db.commit(state);
 
-        // Validate transaction and handle errors
-        let validate_result = match assertion_executor.validate_transaction(
-            block,
-            tx_env,
-            &mut fork_db,
-        ) {
+        let mut results = Vec::with_capacity(tx_envs.len());
+        let mut tx_traces = Vec::with_capacity(tx_envs.len());
+        for tx_env in tx_envs {
+            // Trace the transaction on the same state it is validated on, before validating it.
+            let trace = trace_config.and_then(|(trace_config, _)| {
+                let env = Env { cfg: cfg.clone(), block: block.clone(), tx: tx_env.clone() };
+                trace_transaction(&fork_db, env, spec_id, trace_config)
+            });
+
+            let result =
+                assertion_executor.validate_transaction(block.clone(), tx_env, &mut fork_db);
+
+            // Only attach the trace if it matches the validated execution.
+            tx_traces.push(trace.and_then(|(traces, traced)| {
+                let validated = &result.as_ref().ok()?.result_and_state.result;
+                if traced.is_success() != validated.is_success() ||
+                    traced.gas_used() != validated.gas_used() ||
+                    traced.output() != validated.output()
+                {
+                    debug!("traced assertion triggering transaction diverged from its validation");
+                    return None;
+                }
+                Some(traces)
+            }));
+            let proceed = match &result {
+                Ok(result)
+                    if result.result_and_state.result.is_success() &&
+                        (result.is_valid() || !credible.fail_on_revert) =>
+                {
+                    // Make the state changes available to the next transaction in the block.
+                    fork_db.commit(result.result_and_state.state.clone());
+                    true
+                }
+                _ => false,
+            };
+            results.push(result);
+            if !proceed {
+                break;
+            }
+        }
+        (results, tx_traces)
+    };
+
+    // Write a replay file if the call fails.
+    let failed = results.iter().any(|result| match result {
+        Ok(result) => {
+            !result.result_and_state.result.is_success() ||
+                (!result.is_valid() && credible.fail_on_revert)
+        }
+        Err(_) => true,
+    });
+    if let (true, Some(mut replay), Some(recorder), Some(committed_state), Some(replay_dir)) =
+        (failed, replay, recorder, committed_state, &credible.replay_dir)
+    {
+        let recorder = std::mem::take(&mut *recorder.lock().unwrap());
+        (replay.pre_state, replay.block_hashes) = recorder.into_pre_state(&committed_state);
+        match replay.write(&ccx.state.config.root.join(replay_dir)) {
+            Ok(path) => {
+                executor.console_log(ccx, format!("Assertion replay written to {}", path.display()))
+            }
+            Err(err) => {
+                executor.console_log(ccx, format!("Failed to write assertion replay: {err}"))
+            }
+        }
+    }
+
+    let is_bundle = txs.len() > 1;
+    let mut total_executions = 0;
+    for (tx_index, result) in results.into_iter().enumerate() {
+        let tx_label =
+            if is_bundle { format!("Transaction #{tx_index}") } else { "Transaction".to_string() };
+
+        // Attach the trace of the triggering transaction to the cheatcode call
+        if let (Some((_, parent)), Some(Some(tx_trace))) =
+            (trace_config, tx_traces.get_mut(tx_index).map(Option::take))
+        {
+            if let Some(tracer) = executor.tracing_inspector().and_then(|t| t.as_mut()) {
+                attach_trace(tracer.traces_mut(), parent, tx_trace.into_nodes());
+            }
+        }
+
+        // Handle validation errors
+        let validate_result = match result {
             Ok(result) => result,
             Err(ExecutorError::TxError(evm_err)) => {
-                executor.console_log(ccx, format!("EVM execution failed: {evm_err}"));
+                executor.console_log(ccx, format!("{tx_label} EVM execution failed: {evm_err}"));
                 bail!("EVM execution failed");
             }
             Err(ExecutorError::AssertionReadError(store_err)) => {
                 executor.console_log(ccx, format!("Error: {store_err}"));
                 executor.console_log(
-                        ccx,
-                        "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
-                    );
+                    ccx,
+                    "This is a bug, please open an issue at https://github.com/phoundry-labs/phoundry/issues".to_string(),
+                );
                 bail!("Assertion store error");
             }
         };
@@ -150,73 +441,348 @@ impl Cheatcode for assertionExCall {
         // Handle transaction revert
         if !validate_result.result_and_state.result.is_success() {
             let decoded_error = decode_revert_error(&validate_result.result_and_state.result);
-            executor.console_log(ccx, format!("Transaction reverted: {}", decoded_error.reason()));
+            executor.console_log(ccx, format!("{tx_label} reverted: {}", decoded_error.reason()));
             bail!("Transaction Reverted");
         }
 
-        // Get assertion execution info
-        let Some(assertion_contract) = validate_result.assertions_executions.first() else {
-            bail!("No assertion executed");
-        };
+        total_executions += validate_result.assertions_executions.len();
+
+        // Identify assertion functions of contracts without a known ABI by their signature
+        let unknown_fns = validate_result
+            .assertions_executions
+            .iter()
+            .flat_map(|assertion_contract| {
+                assertion_contract
+                    .assertion_fns_results
+                    .iter()
+                    .map(|assertion_fn| (assertion_contract.adopter, assertion_fn.id.fn_selector))
+            })
+            .filter(|(adopter, selector)| {
+                contracts.get(adopter).is_some_and(|info| !info.functions.contains_key(selector))
+            })
+            .collect::<Vec<_>>();
+        if !unknown_fns.is_empty() {
+            let selectors = unknown_fns.iter().map(|(_, selector)| *selector).collect::<Vec<_>>();
+            let identified = identify_fns(ccx.state, &selectors);
+            for ((adopter, selector), function) in unknown_fns.into_iter().zip(identified) {
+                if let (Some(info), Some(function)) = (contracts.get_mut(&adopter), function) {
+                    info.functions.insert(selector, function);
+                }
+            }
+        }
+
+        // Attach the executed assertion functions to the cheatcode call
+        if let Some((_, parent)) = trace_config {
+            for assertion_contract in &validate_result.assertions_executions {
+                let Some(info) = contracts.get(&assertion_contract.adopter) else { continue };
+                ccx.state.labels.insert(info.address, info.label.to_string());
+                for assertion_fn in &assertion_contract.assertion_fns_results {
+                    let result = assertion_fn.as_result();
+                    let trace = assertion_fn_trace(
+                        assertion_contract.adopter,
+                        info.address,
+                        assertion_fn.id.fn_selector,
+                        result.clone().into_output().unwrap_or_default(),
+                        result.gas_used(),
+                        credible.assertion_gas_limit,
+                        assertion_fn.is_success(),
+                    );
+                    if let Some(tracer) = executor.tracing_inspector().and_then(|t| t.as_mut()) {
+                        let node = CallTraceNode { trace, ..Default::default() };
+                        attach_trace(tracer.traces_mut(), parent, vec![node]);
+                    }
+                }
+            }
+        }
+
+        // Record structured assertion function results
+        let call_results = ccx.state.assertion_results.last_mut().unwrap();
+        for assertion_contract in &validate_result.assertions_executions {
+            let info = contracts.get(&assertion_contract.adopter);
+            for assertion_fn in &assertion_contract.assertion_fns_results {
+                let result = assertion_fn.as_result();
+                let success = assertion_fn.is_success();
+                let (revert_data, revert_reason) = if success {
+                    (Bytes::new(), None)
+                } else {
+                    let revert_data = result.clone().into_output().unwrap_or_default();
+                    (revert_data, Some(decode_revert_error(result).reason().to_string()))
+                };
+                call_results.push(AssertionResult {
+                    tx_index,
+                    adopter: assertion_contract.adopter,
+                    label: info.map(|info| info.label.to_string()).unwrap_or_default(),
+                    selector: assertion_fn.id.fn_selector,
+                    function_name: info
+                        .and_then(|info| info.functions.get(&assertion_fn.id.fn_selector))
+                        .map(|function| function.name.clone()),
+                    gas_used: result.gas_used(),
+                    success,
+                    revert_data,
+                    revert_reason,
+                });
+            }
+        }
 
         // Log gas usage information
-        let total_assertion_gas = validate_result.total_assertions_gas();
-        let total_assertions_ran = validate_result.total_assertion_funcs_ran();
-        let tx_gas_used = validate_result.result_and_state.result.gas_used();
-        let mut assertion_gas_message = format!(
-            "Transaction gas cost: {tx_gas_used}\n  Total Assertion gas cost: {total_assertion_gas}\n  Total assertions ran: {total_assertions_ran}\n  Assertion Functions gas cost\n  ",
-        );
+        if credible.gas_report {
+            let total_assertion_gas = validate_result.total_assertions_gas();
+            let total_assertions_ran = validate_result.total_assertion_funcs_ran();
+            let tx_gas_used = validate_result.result_and_state.result.gas_used();
+            let mut assertion_gas_message = format!(
+                "{tx_label} gas cost: {tx_gas_used}\n  Total Assertion gas cost: {total_assertion_gas}\n  Total assertions ran: {total_assertions_ran}\n  Assertion Functions gas cost\n  ",
+            );
 
-        // Format individual assertion function results
-        for (fn_selector_index, assertion_fn) in
-            assertion_contract.assertion_fns_results.iter().enumerate()
-        {
-            assertion_gas_message.push_str(&format!(
-                "   └─ [selector {}:index {}] gas cost: {}\n",
-                assertion_fn.id.fn_selector,
-                fn_selector_index,
-                assertion_fn.as_result().gas_used()
-            ));
+            // Format individual assertion function results, for every triggered assertion contract
+            for assertion_contract in &validate_result.assertions_executions {
+                let Some(info) = contracts.get(&assertion_contract.adopter) else { continue };
+                for (fn_selector_index, assertion_fn) in
+                    assertion_contract.assertion_fns_results.iter().enumerate()
+                {
+                    assertion_gas_message.push_str(&format!(
+                        "   └─ {}::{} gas cost: {}\n",
+                        info.label,
+                        info.fn_display(assertion_fn.id.fn_selector, fn_selector_index),
+                        assertion_fn.as_result().gas_used()
+                    ));
+                }
+            }
+            executor.console_log(ccx, assertion_gas_message);
         }
-        executor.console_log(ccx, assertion_gas_message);
 
         // Handle failed assertions
         if !validate_result.is_valid() {
-            let mut error_msg = format!("\n  {assertionContractLabel} Assertions Failed:\n");
-            // Collect failed assertions
-            let reverted_assertions: HashMap<_, _> = assertion_contract
-                .assertion_fns_results
-                .iter()
-                .enumerate()
-                .filter(|(_, assertion_fn)| !assertion_fn.is_success())
-                .map(|(fn_selector_index, assertion_fn)| {
-                    let key = format!(
-                        "[selector {}:index {}]",
-                        assertion_fn.id.fn_selector, fn_selector_index
-                    );
-                    let revert = decode_revert_error(assertion_fn.as_result());
-                    (key, revert)
-                })
-                .collect();
-
-            // Format error messages
-            for (key, revert) in reverted_assertions {
-                error_msg.push_str(&format!(
-                    "   └─ {} - Revert Reason: {} \n",
-                    key,
-                    revert.reason()
-                ));
+            let mut error_msg = String::new();
+            let mut failed_fns = Vec::new();
+            for assertion_contract in &validate_result.assertions_executions {
+                let Some(info) = contracts.get(&assertion_contract.adopter) else { continue };
+
+                // Collect failed assertions
+                let reverted_assertions: Vec<_> = assertion_contract
+                    .assertion_fns_results
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, assertion_fn)| !assertion_fn.is_success())
+                    .map(|(fn_selector_index, assertion_fn)| {
+                        let key = info.fn_display(assertion_fn.id.fn_selector, fn_selector_index);
+                        let revert = decode_revert_error(assertion_fn.as_result());
+                        (key, revert)
+                    })
+                    .collect();
+                if reverted_assertions.is_empty() {
+                    continue;
+                }
+
+                let label = info.label;
+                if is_bundle {
+                    error_msg.push_str(&format!("\n  {label} Assertions Failed in {tx_label}:\n"));
+                } else {
+                    error_msg.push_str(&format!("\n  {label} Assertions Failed:\n"));
+                }
+
+                // Format error messages
+                for (key, revert) in reverted_assertions {
+                    failed_fns.push(format!("{label}::{key}"));
+                    error_msg.push_str(&format!(
+                        "   └─ {} - Revert Reason: {} \n",
+                        key,
+                        revert.reason()
+                    ));
+                }
+            }
+
+            if !credible.fail_on_revert {
+                executor.console_log(ccx, format!("Warning: {}", error_msg.trim_start()));
+                continue;
             }
 
             executor.console_log(ccx, error_msg);
-            bail!("Assertions Reverted");
+            bail!("Assertions Reverted in {tx_label}: {}", failed_fns.join(", "));
+        }
+    }
+
+    if total_executions == 0 {
+        bail!("No assertion executed");
+    }
+
+    Ok(Default::default())
+}
+
+/// Returns the ABI of the given assertion contract creation code, if it matches one of the
+/// available artifacts.
+fn assertion_abi(state: &Cheatcodes, creation_code: &[u8]) -> Option<JsonAbi> {
+    let artifacts = state.config.available_artifacts.as_ref()?;
+    let (_, contract) = artifacts.find_by_creation_code(creation_code)?;
+    Some(contract.abi.clone())
+}
+
+/// Returns the assertion functions by selector of the given assertion contract creation code, if it
+/// matches one of the available artifacts.
+fn assertion_fns(state: &Cheatcodes, creation_code: &[u8]) -> HashMap<Selector, Function> {
+    let Some(abi) = assertion_abi(state, creation_code) else {
+        return HashMap::new();
+    };
+    abi.functions().map(|func| (func.selector(), func.clone())).collect()
+}
+
+/// Returns the address an assertion contract is attributed to in call traces.
+fn assertion_address(creation_code: &[u8]) -> Address {
+    Address::from_word(keccak256(creation_code))
+}
+
+/// Returns the call trace of an assertion function, as a static call from the adopter to the
+/// assertion contract.
+fn assertion_fn_trace(
+    adopter: Address,
+    address: Address,
+    selector: Selector,
+    output: Bytes,
+    gas_used: u64,
+    gas_limit: u64,
+    success: bool,
+) -> CallTrace {
+    CallTrace {
+        caller: adopter,
+        address,
+        kind: CallKind::StaticCall,
+        data: Bytes::copy_from_slice(selector.as_slice()),
+        output,
+        gas_used,
+        gas_limit,
+        success,
+        status: if success { InstructionResult::Return } else { InstructionResult::Revert },
+        ..Default::default()
+    }
+}
+
+/// Executes the transaction on top of `db` with a tracing inspector, without committing it, and
+/// returns its call traces and execution result.
+fn trace_transaction<DB: DatabaseRef>(
+    db: DB,
+    env: Env,
+    spec_id: SpecId,
+    config: TracingInspectorConfig,
+) -> Option<(CallTraceArena, ExecutionResult)> {
+    let mut evm = Evm::builder()
+        .with_db(CacheDB::new(db))
+        .with_external_context(TracingInspector::new(config))
+        .with_env(Box::new(env))
+        .with_spec_id(spec_id)
+        .append_handler_register(revm::inspector_handle_register)
+        .build();
+    let result = match evm.transact() {
+        Ok(result) => result.result,
+        Err(err) => {
+            debug!(?err, "failed to trace assertion triggering transaction");
+            return None;
         }
+    };
+    Some((evm.into_context().external.into_traces(), result))
+}
 
-        Ok(Default::default())
+/// Appends the call trace nodes, rooted at the first node, as a child call of the node at
+/// `parent`.
+fn attach_trace(arena: &mut CallTraceArena, parent: usize, nodes: Vec<CallTraceNode>) {
+    let offset = arena.nodes().len();
+    let Some(parent_node) = arena.nodes().get(parent) else { return };
+    let depth = parent_node.trace.depth + 1;
+    let arena_nodes = arena.nodes_mut();
+    for mut node in nodes {
+        node.idx += offset;
+        node.parent = Some(node.parent.map_or(parent, |idx| idx + offset));
+        node.children.iter_mut().for_each(|idx| *idx += offset);
+        node.trace.depth += depth;
+        arena_nodes.push(node);
     }
+
+    let parent = &mut arena_nodes[parent];
+    parent.children.push(offset);
+    parent.ordering.push(TraceMemberOrder::Call(parent.children.len() - 1));
+}
+
+/// Identifies the assertion functions with the given selectors using the ABIs of the available
+/// artifacts.
+///
+/// Selectors which aren't part of any local ABI are only looked up with the signature identifier
+/// if `credible.identify_selectors` is enabled.
+fn identify_fns(state: &Cheatcodes, selectors: &[Selector]) -> Vec<Option<Function>> {
+    let abis = state
+        .config
+        .available_artifacts
+        .iter()
+        .flat_map(|artifacts| artifacts.iter().map(|(_, contract)| &contract.abi))
+        .collect::<Vec<_>>();
+    let mut functions = selectors
+        .iter()
+        .map(|selector| find_fn(abis.iter().copied(), *selector))
+        .collect::<Vec<_>>();
+
+    if !state.config.credible.identify_selectors || state.config.offline {
+        return functions;
+    }
+    let unknown = selectors
+        .iter()
+        .zip(&functions)
+        .filter(|(_, function)| function.is_none())
+        .map(|(selector, _)| *selector)
+        .collect::<Vec<_>>();
+    if unknown.is_empty() {
+        return functions;
+    }
+
+    let identifier = match SignaturesIdentifier::new(Config::foundry_cache_dir(), false) {
+        Ok(identifier) => identifier,
+        Err(err) => {
+            debug!(%err, "failed to create signatures identifier");
+            return functions;
+        }
+    };
+    let mut identified = foundry_common::block_on(async {
+        identifier.write().await.identify_functions(unknown.iter()).await
+    })
+    .into_iter();
+    for function in functions.iter_mut().filter(|function| function.is_none()) {
+        *function = identified.next().flatten();
+    }
+    functions
+}
+
+/// Returns the function with the given selector from the first ABI which contains it.
+fn find_fn<'a>(
+    abis: impl IntoIterator<Item = &'a JsonAbi>,
+    selector: Selector,
+) -> Option<Function> {
+    abis.into_iter()
+        .flat_map(|abi| abi.functions())
+        .find(|function| function.selector() == selector)
+        .cloned()
 }
 
 fn decode_revert_error(revert: &ExecutionResult) -> Revert {
     Revert::abi_decode(&revert.clone().into_output().unwrap_or_default(), false)
         .unwrap_or(Revert::new(("Unknown Revert Reason".to_string(),)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CheatsConfig;
+
+    #[test]
+    fn identifies_fns_from_local_abis() {
+        let abis = [
+            JsonAbi::parse(["function increment()"]).unwrap(),
+            JsonAbi::parse(["function assertIsOne() returns (bool)"]).unwrap(),
+        ];
+        let selector = abis[1].function("assertIsOne").unwrap()[0].selector();
+        assert_eq!(
+            find_fn(&abis, selector).map(|f| f.signature()).as_deref(),
+            Some("assertIsOne()")
+        );
+        assert!(find_fn(&abis, Selector::ZERO).is_none());
+
+        // Remote lookups are opt-in, unknown selectors stay unresolved by default.
+        let state = Cheatcodes::new(Arc::new(CheatsConfig::default()));
+        assert_eq!(identify_fns(&state, &[selector, Selector::ZERO]), vec![None, None]);
+    }
+}
@@ -0,0 +1,354 @@
+//! Replay files of failed `assertionEx` calls.
+//!
+//! A replay file contains everything needed to re-run an `assertionEx` call against the assertion
+//! executor outside of the test it failed in: the block environment, the spec id, the pre-state
+//! the call read, the triggering transactions and the assertion contracts.
+
+use super::{assertion_address, assertion_fn_trace, trace_transaction, AssertionResult};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{hex, keccak256, map::HashMap, Address, Bytes, Selector, B256, U256};
+use assertion_executor::{
+    db::fork_db::ForkDb,
+    store::{AssertionState, AssertionStore},
+    ExecutorConfig, ExecutorError,
+};
+use eyre::{eyre, Result};
+use foundry_evm_core::{backend::DatabaseError, decode::RevertDecoder};
+use foundry_evm_traces::{CallTraceArena, TracingInspectorConfig};
+use revm::{
+    db::{CacheDB, EmptyDBTyped},
+    primitives::{AccountInfo, BlockEnv, Bytecode, CfgEnv, Env, EvmState, SpecId, TxEnv},
+    DatabaseCommit,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The inputs of an `assertionEx` call, sufficient to re-run it against the assertion executor
+/// offline.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionReplay {
+    /// The spec id the call was executed with.
+    pub spec_id: SpecId,
+    /// The configuration environment the call was executed with, including its chain id.
+    pub cfg: CfgEnv,
+    /// The gas limit for executing a single assertion function.
+    pub assertion_gas_limit: u64,
+    /// The block environment the transactions were executed in.
+    pub block: BlockEnv,
+    /// The accounts read by the call, including the state committed in the test before the call.
+    pub pre_state: BTreeMap<Address, ReplayAccount>,
+    /// The block hashes read by the call.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub block_hashes: BTreeMap<u64, B256>,
+    /// The triggering transactions, executed in order.
+    pub transactions: Vec<TxEnv>,
+    /// The assertion contracts the transactions are validated against.
+    pub assertions: Vec<ReplayAssertion>,
+}
+
+/// An account of the pre-state of a replay.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayAccount {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The runtime code of the account.
+    #[serde(default, skip_serializing_if = "Bytes::is_empty")]
+    pub code: Bytes,
+    /// The storage slots of the account that were read or written.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// An assertion contract of a replay.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayAssertion {
+    /// The address of the contract the assertions protect.
+    pub adopter: Address,
+    /// The label of the assertion contract.
+    pub label: String,
+    /// The creation code of the assertion contract, including constructor arguments.
+    pub code: Bytes,
+    /// The ABI of the assertion contract, if it is a project contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi: Option<JsonAbi>,
+}
+
+impl ReplayAssertion {
+    /// Returns the address the assertion contract is attributed to in call traces.
+    pub fn address(&self) -> Address {
+        assertion_address(&self.code)
+    }
+}
+
+/// The outcome of a replayed triggering transaction.
+#[derive(Clone, Debug)]
+pub struct ReplayedTransaction {
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Call traces of the transaction, if tracing is enabled.
+    pub traces: Option<CallTraceArena>,
+    /// The results of the assertion functions triggered by the transaction.
+    pub assertions: Vec<AssertionResult>,
+    /// Call traces of the assertion functions triggered by the transaction, if tracing is
+    /// enabled.
+    pub assertion_traces: Vec<CallTraceArena>,
+}
+
+impl AssertionReplay {
+    /// Reads a replay file.
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(foundry_common::fs::read_json_file(path)?)
+    }
+
+    /// Writes the replay to a file in `dir`, named after the hash of its contents, and returns
+    /// its path.
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        let contents = serde_json::to_vec_pretty(self)?;
+        let path = dir.join(format!("{}.json", &hex::encode(keccak256(&contents))[..16]));
+        foundry_common::fs::create_dir_all(dir)?;
+        foundry_common::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    /// Re-runs the transactions in order against the assertion contracts on top of the pre-state.
+    ///
+    /// Stops at the first transaction which reverts or invalidates an assertion.
+    pub fn run(&self, tracing: Option<TracingInspectorConfig>) -> Result<Vec<ReplayedTransaction>> {
+        let config = ExecutorConfig {
+            spec_id: self.spec_id,
+            chain_id: self.cfg.chain_id,
+            assertion_gas_limit: self.assertion_gas_limit,
+        };
+
+        let store = AssertionStore::new_ephemeral()
+            .map_err(|e| eyre!("Assertion store creation failed: {e}"))?;
+        for assertion in &self.assertions {
+            let assertion_state = AssertionState::new_active(assertion.code.clone(), &config)
+                .map_err(|e| eyre!("Assertion Executor Error: {e:#?}"))?;
+            store
+                .insert(assertion.adopter, assertion_state)
+                .map_err(|e| eyre!("Assertion insertion failed: {e}"))?;
+        }
+
+        let db = self.pre_state_db();
+        let mut assertion_executor = config.build(db, store);
+        let mut fork_db = ForkDb::new(assertion_executor.db.clone());
+
+        let mut replayed = Vec::with_capacity(self.transactions.len());
+        for tx_env in &self.transactions {
+            // Trace the transaction on the same state it is validated on, before validating it.
+            let trace = tracing.and_then(|tracing| {
+                let env =
+                    Env { cfg: self.cfg.clone(), block: self.block.clone(), tx: tx_env.clone() };
+                trace_transaction(&fork_db, env, self.spec_id, tracing)
+            });
+
+            let result = match assertion_executor.validate_transaction(
+                self.block.clone(),
+                tx_env.clone(),
+                &mut fork_db,
+            ) {
+                Ok(result) => result,
+                Err(ExecutorError::TxError(err)) => {
+                    return Err(eyre!("transaction #{} execution failed: {err:?}", replayed.len()))
+                }
+                Err(err) => return Err(eyre!("{err:?}")),
+            };
+
+            // Only keep the trace if it matches the validated execution.
+            let validated = &result.result_and_state.result;
+            let traces = trace.and_then(|(traces, traced)| {
+                (traced.is_success() == validated.is_success() &&
+                    traced.gas_used() == validated.gas_used() &&
+                    traced.output() == validated.output())
+                .then_some(traces)
+            });
+
+            let tx_index = replayed.len();
+            let mut assertions = Vec::new();
+            let mut assertion_traces = Vec::new();
+            for assertion_contract in &result.assertions_executions {
+                let assertion =
+                    self.assertions.iter().find(|a| a.adopter == assertion_contract.adopter);
+                let functions: HashMap<Selector, String> = assertion
+                    .and_then(|a| a.abi.as_ref())
+                    .map(|abi| abi.functions().map(|f| (f.selector(), f.name.clone())).collect())
+                    .unwrap_or_default();
+                let revert_decoder = assertion
+                    .and_then(|a| a.abi.as_ref())
+                    .map_or_else(RevertDecoder::new, |abi| RevertDecoder::new().with_abi(abi));
+                for assertion_fn in &assertion_contract.assertion_fns_results {
+                    let fn_result = assertion_fn.as_result();
+                    let success = assertion_fn.is_success();
+                    let output = fn_result.clone().into_output().unwrap_or_default();
+                    if tracing.is_some() {
+                        if let Some(assertion) = assertion {
+                            let mut arena = CallTraceArena::default();
+                            arena.nodes_mut()[0].trace = assertion_fn_trace(
+                                assertion_contract.adopter,
+                                assertion.address(),
+                                assertion_fn.id.fn_selector,
+                                output.clone(),
+                                fn_result.gas_used(),
+                                self.assertion_gas_limit,
+                                success,
+                            );
+                            assertion_traces.push(arena);
+                        }
+                    }
+                    let (revert_data, revert_reason) = if success {
+                        (Bytes::new(), None)
+                    } else {
+                        let reason = revert_decoder.decode(&output, None);
+                        (output, Some(reason))
+                    };
+                    assertions.push(AssertionResult {
+                        tx_index,
+                        adopter: assertion_contract.adopter,
+                        label: assertion.map(|a| a.label.clone()).unwrap_or_default(),
+                        selector: assertion_fn.id.fn_selector,
+                        function_name: functions.get(&assertion_fn.id.fn_selector).cloned(),
+                        gas_used: fn_result.gas_used(),
+                        success,
+                        revert_data,
+                        revert_reason,
+                    });
+                }
+            }
+
+            let success = result.result_and_state.result.is_success();
+            let proceed = success && result.is_valid();
+            if proceed {
+                fork_db.commit(result.result_and_state.state.clone());
+            }
+            replayed.push(ReplayedTransaction {
+                success,
+                gas_used: result.result_and_state.result.gas_used(),
+                traces,
+                assertions,
+                assertion_traces,
+            });
+            if !proceed {
+                break;
+            }
+        }
+        Ok(replayed)
+    }
+
+    /// Returns a database containing only the pre-state of the replay.
+    fn pre_state_db(&self) -> CacheDB<EmptyDBTyped<DatabaseError>> {
+        let mut db = CacheDB::new(EmptyDBTyped::default());
+        for (address, account) in &self.pre_state {
+            let code = Bytecode::new_raw(account.code.clone());
+            let info = AccountInfo::new(account.balance, account.nonce, code.hash_slow(), code);
+            db.insert_account_info(*address, info);
+            for (slot, value) in &account.storage {
+                let _ = db.insert_account_storage(*address, *slot, *value);
+            }
+        }
+        for (number, hash) in &self.block_hashes {
+            db.block_hashes.insert(U256::from(*number), *hash);
+        }
+        db
+    }
+}
+
+/// Records the state that an `assertionEx` call reads from the test database.
+#[derive(Debug, Default)]
+pub(super) struct StateRecorder {
+    accounts: BTreeMap<Address, ReplayAccount>,
+    block_hashes: BTreeMap<u64, B256>,
+}
+
+impl StateRecorder {
+    /// Records an account read, with its runtime code.
+    pub(super) fn record_account(&mut self, address: Address, info: &AccountInfo, code: Bytes) {
+        let account = self.accounts.entry(address).or_default();
+        account.balance = info.balance;
+        account.nonce = info.nonce;
+        account.code = code;
+    }
+
+    /// Records a storage slot read.
+    pub(super) fn record_storage(&mut self, address: Address, slot: U256, value: U256) {
+        self.accounts.entry(address).or_default().storage.insert(slot, value);
+    }
+
+    /// Records a block hash read.
+    pub(super) fn record_block_hash(&mut self, number: u64, hash: B256) {
+        self.block_hashes.insert(number, hash);
+    }
+
+    /// Returns the recorded pre-state, overridden by the state committed in the test.
+    pub(super) fn into_pre_state(
+        self,
+        committed: &EvmState,
+    ) -> (BTreeMap<Address, ReplayAccount>, BTreeMap<u64, B256>) {
+        let Self { mut accounts, block_hashes } = self;
+        for (address, committed) in committed {
+            let account = accounts.entry(*address).or_default();
+            account.balance = committed.info.balance;
+            account.nonce = committed.info.nonce;
+            if let Some(code) = committed.info.code.as_ref().filter(|code| !code.is_empty()) {
+                account.code = code.original_bytes();
+            }
+            account
+                .storage
+                .extend(committed.storage.iter().map(|(slot, value)| (*slot, value.present_value)));
+        }
+        (accounts, block_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+    use revm::primitives::{Account, AccountStatus, EvmStorageSlot};
+
+    #[test]
+    fn committed_state_overrides_recorded_reads() {
+        let adopter = address!("5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f");
+
+        let mut recorder = StateRecorder::default();
+        let info = AccountInfo { balance: U256::from(1), nonce: 1, ..Default::default() };
+        recorder.record_account(adopter, &info, Bytes::from_static(&[0x60, 0x80]));
+        recorder.record_storage(adopter, U256::ZERO, U256::from(1));
+        recorder.record_storage(adopter, U256::from(1), U256::from(2));
+        recorder.record_block_hash(1, B256::with_last_byte(1));
+
+        let committed = EvmState::from_iter([(
+            adopter,
+            Account {
+                info: AccountInfo { balance: U256::from(2), nonce: 2, ..Default::default() },
+                storage: [(U256::ZERO, EvmStorageSlot::new_changed(U256::from(1), U256::from(3)))]
+                    .into_iter()
+                    .collect(),
+                status: AccountStatus::Touched,
+            },
+        )]);
+
+        let (pre_state, block_hashes) = recorder.into_pre_state(&committed);
+        assert_eq!(
+            pre_state[&adopter],
+            ReplayAccount {
+                balance: U256::from(2),
+                nonce: 2,
+                code: Bytes::from_static(&[0x60, 0x80]),
+                storage: BTreeMap::from([
+                    (U256::ZERO, U256::from(3)),
+                    (U256::from(1), U256::from(2))
+                ]),
+            }
+        );
+        assert_eq!(block_hashes, BTreeMap::from([(1, B256::with_last_byte(1))]));
+    }
+}
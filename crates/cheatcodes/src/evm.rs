@@ -627,6 +627,26 @@ impl Cheatcode for snapshotGasLastCall_1Call {
     }
 }
 
+impl Cheatcode for snapshotGasLastAssertionEx_0Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { name } = self;
+        let Some(assertion_gas) = last_assertion_gas(ccx.state) else {
+            bail!("no assertionEx call was made yet");
+        };
+        inner_last_gas_snapshot(ccx, None, Some(name.clone()), assertion_gas)
+    }
+}
+
+impl Cheatcode for snapshotGasLastAssertionEx_1Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { group, name } = self;
+        let Some(assertion_gas) = last_assertion_gas(ccx.state) else {
+            bail!("no assertionEx call was made yet");
+        };
+        inner_last_gas_snapshot(ccx, Some(group.clone()), Some(name.clone()), assertion_gas)
+    }
+}
+
 impl Cheatcode for startSnapshotGas_0Call {
     fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self { name } = self;
@@ -951,6 +971,11 @@ fn inner_last_gas_snapshot(
     Ok(value.abi_encode())
 }
 
+/// Returns the total gas used by the assertion functions executed by the last `assertionEx` call.
+fn last_assertion_gas(state: &Cheatcodes) -> Option<u64> {
+    state.assertion_results.last().map(|results| results.iter().map(|r| r.gas_used).sum())
+}
+
 fn inner_start_gas_snapshot(
     ccx: &mut CheatsCtxt,
     group: Option<String>,
@@ -17,7 +17,7 @@ use crate::{
         },
     },
     utils::IgnoredTraces,
-    CheatsConfig, CheatsCtxt, DynCheatcode, Error, Result,
+    AssertionResult, CheatsConfig, CheatsCtxt, DynCheatcode, Error, Result,
     Vm::{self, AccountAccess},
 };
 use alloy_primitives::{
@@ -463,6 +463,10 @@ pub struct Cheatcodes {
     // **Note**: both must a BTreeMap to ensure the order of the keys is deterministic.
     pub gas_snapshots: BTreeMap<String, BTreeMap<String, String>>,
 
+    /// Results of the assertion functions executed by `assertionEx` calls over the course of a
+    /// test, one entry per call.
+    pub assertion_results: Vec<Vec<AssertionResult>>,
+
     /// Mapping slots.
     pub mapping_slots: Option<AddressHashMap<MappingSlots>>,
 
@@ -527,6 +531,7 @@ impl Cheatcodes {
             eth_deals: Default::default(),
             gas_metering: Default::default(),
             gas_snapshots: Default::default(),
+            assertion_results: Default::default(),
             mapping_slots: Default::default(),
             pc: Default::default(),
             breakpoints: Default::default(),
@@ -61,6 +61,9 @@ mod toml;
 mod utils;
 
 mod credible;
+pub use credible::{
+    AssertionReplay, AssertionResult, ReplayAccount, ReplayAssertion, ReplayedTransaction,
+};
 
 /// Cheatcode implementation.
 pub(crate) trait Cheatcode: CheatcodeDef + DynCheatcode {
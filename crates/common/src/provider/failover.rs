@@ -0,0 +1,416 @@
+//! Transport that load-balances requests across several upstream endpoints and fails over
+//! between them, temporarily taking endpoints out of rotation when they keep failing.
+
+use super::runtime_transport::RuntimeTransport;
+use alloy_json_rpc::{ErrorPayload, RequestPacket, ResponsePacket, ResponsePayload, RpcError};
+use alloy_transport::{TransportError, TransportErrorKind, TransportFut};
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// Number of consecutive failed requests after which an endpoint is marked unhealthy.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// How long an unhealthy endpoint is kept at the back of the rotation before it's tried first
+/// again.
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// JSON-RPC error codes of server-side failures that another endpoint might not run into:
+/// resource unavailable and limit exceeded (EIP-1474), and internal error (JSON-RPC 2.0).
+///
+/// Any other error, e.g. invalid params, a reverted call or a rejected transaction, is the
+/// answer to the request itself and every endpoint would return it the same way.
+const RETRIABLE_ERROR_CODES: [i64; 3] = [-32002, -32005, -32603];
+
+/// Methods that are never sent to more than one endpoint, since a transaction that seems to
+/// have failed might still have been broadcast.
+const NON_RETRIABLE_METHODS: [&str; 2] = ["eth_sendRawTransaction", "eth_sendTransaction"];
+
+/// Returns whether the request may be sent to another endpoint if the first one fails.
+fn is_retriable_request(req: &RequestPacket) -> bool {
+    let is_retriable = |method: &str| !NON_RETRIABLE_METHODS.contains(&method);
+    match req {
+        RequestPacket::Single(req) => is_retriable(req.method()),
+        RequestPacket::Batch(reqs) => reqs.iter().all(|req| is_retriable(req.method())),
+    }
+}
+
+/// Returns whether the request failed because of the endpoint rather than the request: the
+/// endpoint couldn't be reached, answered with HTTP 5xx or 429, or with a retriable JSON-RPC error.
+fn is_retriable_transport_error(err: &TransportError) -> bool {
+    match err {
+        RpcError::Transport(TransportErrorKind::HttpError(err)) => {
+            err.status == 429 || err.status >= 500
+        }
+        RpcError::Transport(_) => true,
+        RpcError::ErrorResp(err) => RETRIABLE_ERROR_CODES.contains(&err.code),
+        _ => false,
+    }
+}
+
+/// Returns the first JSON-RPC error of the response with one of the [RETRIABLE_ERROR_CODES].
+fn retriable_error(res: &ResponsePacket) -> Option<&ErrorPayload> {
+    let err = match res {
+        ResponsePacket::Single(res) => match &res.payload {
+            ResponsePayload::Failure(err) => err,
+            ResponsePayload::Success(_) => return None,
+        },
+        ResponsePacket::Batch(res) => res.iter().find_map(|res| match &res.payload {
+            ResponsePayload::Failure(err) => Some(err),
+            ResponsePayload::Success(_) => None,
+        })?,
+    };
+    RETRIABLE_ERROR_CODES.contains(&err.code).then_some(err)
+}
+
+/// An upstream endpoint and its health.
+#[derive(Debug)]
+struct Endpoint {
+    transport: RuntimeTransport,
+    /// Number of consecutive failed requests.
+    failures: AtomicU32,
+    /// Set while the endpoint is considered unhealthy.
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    fn new(transport: RuntimeTransport) -> Self {
+        Self { transport, failures: AtomicU32::new(0), unhealthy_until: Mutex::new(None) }
+    }
+
+    fn is_healthy(&self) -> bool {
+        !matches!(*self.unhealthy_until.lock(), Some(until) if Instant::now() < until)
+    }
+
+    fn on_success(&self) {
+        if self.failures.swap(0, Ordering::Relaxed) >= MAX_CONSECUTIVE_FAILURES {
+            debug!(target: "provider", url=%self.transport.url(), "upstream endpoint recovered");
+        }
+        *self.unhealthy_until.lock() = None;
+    }
+
+    fn on_failure(&self, err: &dyn fmt::Display) {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= MAX_CONSECUTIVE_FAILURES {
+            let mut unhealthy_until = self.unhealthy_until.lock();
+            if unhealthy_until.is_none() {
+                warn!(
+                    target: "provider",
+                    url=%self.transport.url(),
+                    %err,
+                    failures,
+                    "marking upstream endpoint unhealthy"
+                );
+            }
+            *unhealthy_until = Some(Instant::now() + UNHEALTHY_COOLDOWN);
+        }
+    }
+}
+
+/// A transport over one or more [RuntimeTransport]s.
+///
+/// Requests are distributed round-robin across all healthy endpoints. If an endpoint can't be
+/// reached, answers with HTTP 5xx or 429, or with a server-side JSON-RPC error such as an internal
+/// error or an exceeded limit, the request is sent to the next endpoint until one succeeds or all
+/// of them have been tried. Any other error, e.g. invalid params or a revert, is returned as is.
+/// Transactions are only ever sent to a single endpoint.
+///
+/// An endpoint that fails three requests in a row is marked unhealthy and
+/// only tried after all healthy endpoints, until it either answers a request or the cooldown
+/// expires.
+#[derive(Clone, Debug)]
+pub struct FailoverTransport {
+    endpoints: Arc<[Endpoint]>,
+    /// Index of the endpoint the next request starts at.
+    next: Arc<AtomicUsize>,
+}
+
+impl FailoverTransport {
+    /// Creates a new transport over the given endpoints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transports` is empty.
+    pub fn new(transports: Vec<RuntimeTransport>) -> Self {
+        assert!(!transports.is_empty(), "at least one transport is required");
+        Self {
+            endpoints: transports.into_iter().map(Endpoint::new).collect(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the URLs of all endpoints.
+    pub fn urls(&self) -> impl Iterator<Item = &Url> + '_ {
+        self.endpoints.iter().map(|endpoint| endpoint.transport.url())
+    }
+
+    /// Returns the URLs of the endpoints that are currently marked unhealthy.
+    pub fn unhealthy_urls(&self) -> Vec<Url> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| !endpoint.is_healthy())
+            .map(|endpoint| endpoint.transport.url().clone())
+            .collect()
+    }
+
+    /// Returns the order in which endpoints are tried for the next request: round-robin over the
+    /// healthy endpoints, followed by the unhealthy ones.
+    fn next_order(&self) -> Vec<usize> {
+        let len = self.endpoints.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % len;
+        let (mut order, unhealthy): (Vec<_>, Vec<_>) =
+            (0..len).map(|i| (start + i) % len).partition(|&i| self.endpoints[i].is_healthy());
+        order.extend(unhealthy);
+        order
+    }
+
+    /// Sends a request to the endpoints, failing over until one of them succeeds.
+    pub fn request(&self, req: RequestPacket) -> TransportFut<'static> {
+        if self.endpoints.len() == 1 {
+            return self.endpoints[0].transport.request(req);
+        }
+
+        let this = self.clone();
+        Box::pin(async move {
+            let attempts = if is_retriable_request(&req) { this.endpoints.len() } else { 1 };
+            let mut last_err = None;
+            for idx in this.next_order().into_iter().take(attempts) {
+                let endpoint = &this.endpoints[idx];
+                match endpoint.transport.request(req.clone()).await {
+                    Ok(res) => {
+                        if let Some(err) = retriable_error(&res) {
+                            trace!(
+                                target: "provider",
+                                url=%endpoint.transport.url(),
+                                %err,
+                                "upstream endpoint returned an error response"
+                            );
+                            endpoint.on_failure(err);
+                            last_err = Some(Ok(res));
+                            continue;
+                        }
+                        endpoint.on_success();
+                        return Ok(res);
+                    }
+                    Err(err) if !is_retriable_transport_error(&err) => {
+                        // the endpoint answered, the request itself was rejected
+                        endpoint.on_success();
+                        return Err(err);
+                    }
+                    Err(err) => {
+                        trace!(
+                            target: "provider",
+                            url=%endpoint.transport.url(),
+                            %err,
+                            "request to upstream endpoint failed"
+                        );
+                        endpoint.on_failure(&err);
+                        last_err = Some(Err(err));
+                    }
+                }
+            }
+            // every endpoint that was tried failed, the last error response or transport error is
+            // returned
+            last_err.expect("at least one endpoint")
+        })
+    }
+}
+
+impl fmt::Display for FailoverTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FailoverTransport [")?;
+        for (i, url) in self.urls().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{url}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl tower::Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.request(req)
+    }
+}
+
+impl tower::Service<RequestPacket> for &FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.request(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::runtime_transport::RuntimeTransportBuilder;
+    use alloy_json_rpc::{Id, Request};
+
+    fn transport(url: &str) -> RuntimeTransport {
+        RuntimeTransportBuilder::new(url.parse().unwrap()).build()
+    }
+
+    fn request(method: &'static str) -> RequestPacket {
+        Request::new(method, Id::Number(1), ()).serialize().unwrap().into()
+    }
+
+    fn response(payload: &str) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":1,{payload}}}"#)
+    }
+
+    /// Spawns an HTTP server answering every request with the given status and body, returns its
+    /// transport and the number of requests it received.
+    async fn serve(status: u16, body: String) -> (RuntimeTransport, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let handler = {
+            let hits = hits.clone();
+            move || async move {
+                hits.fetch_add(1, Ordering::Relaxed);
+                let status = axum::http::StatusCode::from_u16(status).unwrap();
+                (status, [(axum::http::header::CONTENT_TYPE, "application/json")], body)
+            }
+        };
+        let router = axum::Router::new().route("/", axum::routing::post(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        (transport(&url), hits)
+    }
+
+    #[tokio::test]
+    async fn marks_failing_endpoints_unhealthy() {
+        // unsupported schemes fail on connect without touching the network
+        let failover = FailoverTransport::new(vec![transport("foo://a"), transport("foo://b")]);
+        assert_eq!(failover.next_order(), vec![0, 1]);
+        assert_eq!(failover.next_order(), vec![1, 0]);
+
+        for _ in 0..MAX_CONSECUTIVE_FAILURES - 1 {
+            assert!(failover.request(request("eth_chainId")).await.is_err());
+        }
+        assert!(failover.unhealthy_urls().is_empty());
+
+        assert!(failover.request(request("eth_chainId")).await.is_err());
+        assert_eq!(failover.unhealthy_urls().len(), 2);
+
+        // a recovered endpoint is tried before the unhealthy one
+        failover.endpoints[1].on_success();
+        assert_eq!(failover.next_order(), vec![1, 0]);
+        assert_eq!(failover.next_order(), vec![1, 0]);
+    }
+
+    #[test]
+    fn retries_only_server_errors() {
+        let packet =
+            |payload: &str| -> ResponsePacket { serde_json::from_str(&response(payload)).unwrap() };
+
+        assert!(retriable_error(&packet(r#""result":"0x1""#)).is_none());
+        let internal = packet(r#""error":{"code":-32603,"message":"internal error"}"#);
+        assert_eq!(retriable_error(&internal).unwrap().message, "internal error");
+        let limited = packet(r#""error":{"code":-32005,"message":"limit exceeded"}"#);
+        assert_eq!(retriable_error(&limited).unwrap().message, "limit exceeded");
+
+        for client_error in [
+            r#""error":{"code":-32602,"message":"invalid params"}"#,
+            r#""error":{"code":-32000,"message":"nonce too low"}"#,
+            r#""error":{"code":3,"message":"execution reverted","data":"0x"}"#,
+            r#""error":{"code":-32000,"message":"execution reverted"}"#,
+        ] {
+            assert!(retriable_error(&packet(client_error)).is_none(), "{client_error}");
+        }
+
+        assert!(is_retriable_transport_error(&TransportErrorKind::http_error(503, String::new())));
+        assert!(is_retriable_transport_error(&TransportErrorKind::http_error(429, String::new())));
+        assert!(!is_retriable_transport_error(&TransportErrorKind::http_error(400, String::new())));
+        assert!(!is_retriable_transport_error(&TransportErrorKind::http_error(401, String::new())));
+    }
+
+    #[tokio::test]
+    async fn returns_client_errors_as_is() {
+        let invalid_params = response(r#""error":{"code":-32602,"message":"invalid params"}"#);
+        let (a, a_hits) = serve(200, invalid_params.clone()).await;
+        let (b, b_hits) = serve(200, invalid_params).await;
+        let failover = FailoverTransport::new(vec![a, b]);
+
+        let res = failover.request(request("eth_getBalance")).await.unwrap();
+        let ResponsePacket::Single(res) = res else { panic!("expected a single response") };
+        let ResponsePayload::Failure(err) = res.payload else { panic!("expected an error") };
+        assert_eq!(err.code, -32602);
+        assert_eq!(a_hits.load(Ordering::Relaxed) + b_hits.load(Ordering::Relaxed), 1);
+
+        // HTTP client errors aren't failed over either
+        let (a, a_hits) = serve(400, "bad request".to_string()).await;
+        let (b, b_hits) = serve(400, "bad request".to_string()).await;
+        let failover = FailoverTransport::new(vec![a, b]);
+        let err = failover.request(request("eth_getBalance")).await.unwrap_err();
+        let RpcError::Transport(TransportErrorKind::HttpError(err)) = err else {
+            panic!("{err:?}")
+        };
+        assert_eq!(err.status, 400);
+        assert_eq!(a_hits.load(Ordering::Relaxed) + b_hits.load(Ordering::Relaxed), 1);
+        assert!(failover.unhealthy_urls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fails_over_server_errors() {
+        let (unavailable, unavailable_hits) = serve(503, "unavailable".to_string()).await;
+        let (internal, internal_hits) =
+            serve(200, response(r#""error":{"code":-32603,"message":"internal error"}"#)).await;
+        let (ok, ok_hits) = serve(200, response(r#""result":"0x1""#)).await;
+        let failover = FailoverTransport::new(vec![unavailable, internal, ok]);
+
+        let res = failover.request(request("eth_chainId")).await.unwrap();
+        let ResponsePacket::Single(res) = res else { panic!("expected a single response") };
+        assert!(matches!(res.payload, ResponsePayload::Success(_)));
+        assert_eq!(unavailable_hits.load(Ordering::Relaxed), 1);
+        assert_eq!(internal_hits.load(Ordering::Relaxed), 1);
+        assert_eq!(ok_hits.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn never_fails_over_transactions() {
+        let (a, a_hits) = serve(503, "unavailable".to_string()).await;
+        let (b, b_hits) = serve(503, "unavailable".to_string()).await;
+        let failover = FailoverTransport::new(vec![a, b]);
+
+        for method in NON_RETRIABLE_METHODS {
+            assert!(failover.request(request(method)).await.is_err());
+        }
+        // the requests went round-robin, one per endpoint
+        assert_eq!(a_hits.load(Ordering::Relaxed), 1);
+        assert_eq!(b_hits.load(Ordering::Relaxed), 1);
+    }
+}
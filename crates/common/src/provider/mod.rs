@@ -1,5 +1,6 @@
 //! Provider-related instantiation and usage utilities.
 
+pub mod failover;
 pub mod runtime_transport;
 
 use crate::{
@@ -16,9 +17,9 @@ use alloy_transport::{
     utils::guess_local_url,
 };
 use eyre::{Result, WrapErr};
+use failover::FailoverTransport;
 use foundry_config::NamedChain;
 use reqwest::Url;
-use runtime_transport::RuntimeTransport;
 use std::{
     net::SocketAddr,
     path::{Path, PathBuf},
@@ -35,7 +36,7 @@ const DEFAULT_UNKNOWN_CHAIN_BLOCK_TIME: Duration = Duration::from_secs(3);
 const POLL_INTERVAL_BLOCK_TIME_SCALE_FACTOR: f32 = 0.6;
 
 /// Helper type alias for a retry provider
-pub type RetryProvider<N = AnyNetwork> = RootProvider<RetryBackoffService<RuntimeTransport>, N>;
+pub type RetryProvider<N = AnyNetwork> = RootProvider<RetryBackoffService<FailoverTransport>, N>;
 
 /// Helper type alias for a retry provider with a signer
 pub type RetryProviderWithSigner<N = AnyNetwork> = FillProvider<
@@ -52,8 +53,8 @@ pub type RetryProviderWithSigner<N = AnyNetwork> = FillProvider<
         >,
         WalletFiller<EthereumWallet>,
     >,
-    RootProvider<RetryBackoffService<RuntimeTransport>, N>,
-    RetryBackoffService<RuntimeTransport>,
+    RootProvider<RetryBackoffService<FailoverTransport>, N>,
+    RetryBackoffService<FailoverTransport>,
     N,
 >;
 
@@ -91,6 +92,8 @@ pub fn try_get_http_provider(builder: impl AsRef<str>) -> Result<RetryProvider>
 pub struct ProviderBuilder {
     // Note: this is a result, so we can easily chain builder calls
     url: Result<Url>,
+    /// Additional endpoints requests are load-balanced across
+    fallback_urls: Result<Vec<Url>>,
     chain: NamedChain,
     max_retry: u32,
     initial_backoff: u64,
@@ -106,41 +109,14 @@ pub struct ProviderBuilder {
 impl ProviderBuilder {
     /// Creates a new builder instance
     pub fn new(url_str: &str) -> Self {
-        // a copy is needed for the next lines to work
-        let mut url_str = url_str;
-
-        // invalid url: non-prefixed URL scheme is not allowed, so we prepend the default http
-        // prefix
-        let storage;
-        if url_str.starts_with("localhost:") {
-            storage = format!("http://{url_str}");
-            url_str = storage.as_str();
-        }
-
-        let url = Url::parse(url_str)
-            .or_else(|err| match err {
-                ParseError::RelativeUrlWithoutBase => {
-                    if SocketAddr::from_str(url_str).is_ok() {
-                        Url::parse(&format!("http://{url_str}"))
-                    } else {
-                        let path = Path::new(url_str);
-
-                        if let Ok(path) = resolve_path(path) {
-                            Url::parse(&format!("file://{}", path.display()))
-                        } else {
-                            Err(err)
-                        }
-                    }
-                }
-                _ => Err(err),
-            })
-            .wrap_err_with(|| format!("invalid provider URL: {url_str:?}"));
+        let url = parse_url(url_str);
 
         // Use the final URL string to guess if it's a local URL.
         let is_local = url.as_ref().is_ok_and(|url| guess_local_url(url.as_str()));
 
         Self {
             url,
+            fallback_urls: Ok(vec![]),
             chain: NamedChain::Mainnet,
             max_retry: 8,
             initial_backoff: 800,
@@ -153,6 +129,15 @@ impl ProviderBuilder {
         }
     }
 
+    /// Sets additional upstream endpoints of the same chain.
+    ///
+    /// Requests are load-balanced across these and the main URL, and fail over to the next
+    /// endpoint if one of them is unreachable.
+    pub fn fallback_urls<S: AsRef<str>>(mut self, urls: impl IntoIterator<Item = S>) -> Self {
+        self.fallback_urls = urls.into_iter().map(|url| parse_url(url.as_ref())).collect();
+        self
+    }
+
     /// Enables a request timeout.
     ///
     /// The timeout is applied from when the request starts connecting until the
@@ -251,6 +236,7 @@ impl ProviderBuilder {
     pub fn build(self) -> Result<RetryProvider> {
         let Self {
             url,
+            fallback_urls,
             chain,
             max_retry,
             initial_backoff,
@@ -261,15 +247,12 @@ impl ProviderBuilder {
             is_local,
         } = self;
         let url = url?;
+        let fallback_urls = fallback_urls?;
 
         let retry_layer =
             RetryBackoffLayer::new(max_retry, initial_backoff, compute_units_per_second);
 
-        let transport = RuntimeTransportBuilder::new(url)
-            .with_timeout(timeout)
-            .with_headers(headers)
-            .with_jwt(jwt)
-            .build();
+        let transport = build_transport(url, fallback_urls, timeout, headers, jwt);
         let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
 
         if !is_local {
@@ -294,6 +277,7 @@ impl ProviderBuilder {
     pub fn build_with_wallet(self, wallet: EthereumWallet) -> Result<RetryProviderWithSigner> {
         let Self {
             url,
+            fallback_urls,
             chain,
             max_retry,
             initial_backoff,
@@ -304,15 +288,12 @@ impl ProviderBuilder {
             is_local,
         } = self;
         let url = url?;
+        let fallback_urls = fallback_urls?;
 
         let retry_layer =
             RetryBackoffLayer::new(max_retry, initial_backoff, compute_units_per_second);
 
-        let transport = RuntimeTransportBuilder::new(url)
-            .with_timeout(timeout)
-            .with_headers(headers)
-            .with_jwt(jwt)
-            .build();
+        let transport = build_transport(url, fallback_urls, timeout, headers, jwt);
 
         let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
 
@@ -334,6 +315,60 @@ impl ProviderBuilder {
     }
 }
 
+/// Parses a provider URL, accepting socket addresses and IPC paths without a scheme.
+fn parse_url(url_str: &str) -> Result<Url> {
+    // a copy is needed for the next lines to work
+    let mut url_str = url_str;
+
+    // invalid url: non-prefixed URL scheme is not allowed, so we prepend the default http
+    // prefix
+    let storage;
+    if url_str.starts_with("localhost:") {
+        storage = format!("http://{url_str}");
+        url_str = storage.as_str();
+    }
+
+    Url::parse(url_str)
+        .or_else(|err| match err {
+            ParseError::RelativeUrlWithoutBase => {
+                if SocketAddr::from_str(url_str).is_ok() {
+                    Url::parse(&format!("http://{url_str}"))
+                } else {
+                    let path = Path::new(url_str);
+
+                    if let Ok(path) = resolve_path(path) {
+                        Url::parse(&format!("file://{}", path.display()))
+                    } else {
+                        Err(err)
+                    }
+                }
+            }
+            _ => Err(err),
+        })
+        .wrap_err_with(|| format!("invalid provider URL: {url_str:?}"))
+}
+
+/// Builds a [FailoverTransport] over the main URL and all fallback URLs.
+fn build_transport(
+    url: Url,
+    fallback_urls: Vec<Url>,
+    timeout: Duration,
+    headers: Vec<String>,
+    jwt: Option<String>,
+) -> FailoverTransport {
+    let transports = std::iter::once(url)
+        .chain(fallback_urls)
+        .map(|url| {
+            RuntimeTransportBuilder::new(url)
+                .with_timeout(timeout)
+                .with_headers(headers.clone())
+                .with_jwt(jwt.clone())
+                .build()
+        })
+        .collect();
+    FailoverTransport::new(transports)
+}
+
 #[cfg(not(windows))]
 fn resolve_path(path: &Path) -> Result<PathBuf, ()> {
     if path.is_absolute() {
@@ -135,6 +135,11 @@ impl fmt::Display for RuntimeTransport {
 }
 
 impl RuntimeTransport {
+    /// Returns the URL the transport connects to.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
     /// Connects the underlying transport, depending on the URL scheme.
     pub async fn connect(&self) -> Result<InnerTransport, RuntimeTransportError> {
         match self.url.scheme() {
@@ -59,6 +59,11 @@ pub trait TestFunctionExt {
         self.test_function_kind().is_invariant_test()
     }
 
+    /// Returns `true` if this function is a symbolic test.
+    fn is_symbolic_test(&self) -> bool {
+        self.test_function_kind().is_symbolic_test()
+    }
+
     /// Returns `true` if this function is an `afterInvariant` function.
     fn is_after_invariant(&self) -> bool {
         self.test_function_kind().is_after_invariant()
@@ -116,6 +121,8 @@ pub enum TestFunctionKind {
     FuzzTest { should_fail: bool },
     /// `invariant*` or `statefulFuzz*`.
     InvariantTest,
+    /// `check_*`, explored symbolically if symbolic testing is enabled.
+    SymbolicTest,
     /// `afterInvariant`.
     AfterInvariant,
     /// `fixture*`.
@@ -140,6 +147,7 @@ impl TestFunctionKind {
             _ if name.starts_with("invariant") || name.starts_with("statefulFuzz") => {
                 Self::InvariantTest
             }
+            _ if name.starts_with("check_") => Self::SymbolicTest,
             _ if name.eq_ignore_ascii_case("setup") => Self::Setup,
             _ if name.eq_ignore_ascii_case("afterinvariant") => Self::AfterInvariant,
             _ if name.starts_with("fixture") => Self::Fixture,
@@ -156,6 +164,7 @@ impl TestFunctionKind {
             Self::FuzzTest { should_fail: false } => "fuzz",
             Self::FuzzTest { should_fail: true } => "fuzz fail",
             Self::InvariantTest => "invariant",
+            Self::SymbolicTest => "symbolic",
             Self::AfterInvariant => "afterInvariant",
             Self::Fixture => "fixture",
             Self::Unknown => "unknown",
@@ -169,6 +178,8 @@ impl TestFunctionKind {
     }
 
     /// Returns `true` if this function is a unit, fuzz, or invariant test.
+    ///
+    /// Symbolic tests are not included, as they are only tests if symbolic testing is enabled.
     #[inline]
     pub const fn is_any_test(&self) -> bool {
         matches!(self, Self::UnitTest { .. } | Self::FuzzTest { .. } | Self::InvariantTest)
@@ -198,6 +209,12 @@ impl TestFunctionKind {
         matches!(self, Self::InvariantTest)
     }
 
+    /// Returns `true` if this function is a symbolic test.
+    #[inline]
+    pub const fn is_symbolic_test(&self) -> bool {
+        matches!(self, Self::SymbolicTest)
+    }
+
     /// Returns `true` if this function is an `afterInvariant` function.
     #[inline]
     pub const fn is_after_invariant(&self) -> bool {
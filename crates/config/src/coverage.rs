@@ -0,0 +1,68 @@
+//! Configuration specific to the `forge coverage` command
+
+use crate::filter::GlobMatcher;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Contains the minimum coverage that `forge coverage` requires
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageConfig {
+    /// The minimum coverage of the entire project.
+    #[serde(flatten)]
+    pub thresholds: CoverageThresholds,
+    /// The minimum coverage of each source file matching a glob.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PathCoverageThresholds>,
+}
+
+impl CoverageConfig {
+    /// Returns the thresholds that apply to the source file at `path`.
+    ///
+    /// The thresholds of every matching glob are merged in order, so a later glob overrides the
+    /// percentages set by an earlier one. Returns `None` if no glob matches.
+    pub fn thresholds_for(&self, path: &Path) -> Option<CoverageThresholds> {
+        self.paths
+            .iter()
+            .filter(|p| p.path.is_match(path))
+            .fold(None, |acc, p| Some(acc.unwrap_or_default().merged(&p.thresholds)))
+    }
+}
+
+/// The minimum coverage of source files matching a glob
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PathCoverageThresholds {
+    /// The glob matching the source file paths, relative to the project root.
+    pub path: GlobMatcher,
+    /// The minimum coverage of each matching source file.
+    #[serde(flatten)]
+    pub thresholds: CoverageThresholds,
+}
+
+/// Minimum coverage percentages, between 0 and 100
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageThresholds {
+    /// The minimum percentage of lines hit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lines: Option<f64>,
+    /// The minimum percentage of statements hit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_statements: Option<f64>,
+    /// The minimum percentage of branches hit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_branches: Option<f64>,
+    /// The minimum percentage of functions hit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_functions: Option<f64>,
+}
+
+impl CoverageThresholds {
+    /// Returns these thresholds with the minimums set in `other` taking precedence.
+    pub fn merged(self, other: &Self) -> Self {
+        Self {
+            min_lines: other.min_lines.or(self.min_lines),
+            min_statements: other.min_statements.or(self.min_statements),
+            min_branches: other.min_branches.or(self.min_branches),
+            min_functions: other.min_functions.or(self.min_functions),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Configuration for credible layer assertion execution
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Contains the config for executing credible layer assertions with `assertionEx`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredibleConfig {
+    /// The gas limit for executing a single assertion function.
+    pub assertion_gas_limit: u64,
+    /// The gas limit for transactions triggering assertions. Defaults to the block gas limit.
+    pub tx_gas_limit: Option<u64>,
+    /// Whether to report the gas used by triggering transactions and assertion functions.
+    pub gas_report: bool,
+    /// Fails the test if an assertion reverts, otherwise the failure is only reported as a
+    /// warning.
+    pub fail_on_revert: bool,
+    /// Directory to write replay files of failed `assertionEx` calls to, relative to the project
+    /// root. No replay files are written if unset.
+    pub replay_dir: Option<PathBuf>,
+    /// Whether to look up the signatures of assertion functions which aren't part of any local
+    /// ABI with the remote signature service.
+    pub identify_selectors: bool,
+}
+
+/// Pairs an adopter contract with the assertion contract that protects it in fuzz and invariant
+/// tests.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssertionTarget {
+    /// The name or identifier of the adopter contract. Every instance of it deployed in the test's
+    /// `setUp` is protected.
+    pub adopter: String,
+    /// The name or identifier of the assertion contract. If its constructor takes an argument, it
+    /// is deployed with the adopter address.
+    pub assertion: String,
+}
+
+impl Default for CredibleConfig {
+    fn default() -> Self {
+        Self {
+            assertion_gas_limit: 3_000_000,
+            tx_gas_limit: None,
+            gas_report: true,
+            fail_on_revert: true,
+            replay_dir: None,
+            identify_selectors: false,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Configuration for fuzz testing.
 
+use crate::AssertionTarget;
 use alloy_primitives::U256;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -30,6 +31,15 @@ pub struct FuzzConfig {
     pub show_logs: bool,
     /// Optional timeout (in seconds) for each property test
     pub timeout: Option<u32>,
+    /// Whether to guide the fuzzer with coverage feedback, keeping the inputs that reach new code
+    /// in a corpus and mutating them, defaults to `false`
+    pub coverage_guided: bool,
+    /// Path where the corpus of coverage-guided fuzzing is persisted between runs, defaults to
+    /// the `corpus` dir in `failure_persist_dir`. Unlike the default, this dir is not removed by
+    /// `forge clean`, so it can be committed and shared
+    pub corpus_dir: Option<PathBuf>,
+    /// Assertion contracts that the fuzzed test calls are validated against
+    pub assertions: Vec<AssertionTarget>,
 }
 
 impl Default for FuzzConfig {
@@ -44,6 +54,9 @@ impl Default for FuzzConfig {
             failure_persist_file: None,
             show_logs: false,
             timeout: None,
+            coverage_guided: false,
+            corpus_dir: None,
+            assertions: Vec::new(),
         }
     }
 }
@@ -57,6 +70,19 @@ impl FuzzConfig {
             ..Default::default()
         }
     }
+
+    /// Returns path to the corpus file of the given fuzz test, if coverage-guided fuzzing is
+    /// enabled.
+    pub fn corpus_file(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        if !self.coverage_guided {
+            return None
+        }
+        let dir = match &self.corpus_dir {
+            Some(dir) => dir.clone(),
+            None => self.failure_persist_dir.as_ref()?.join("corpus"),
+        };
+        Some(dir.join(contract_name.split(':').last().unwrap()).join(format!("{test_name}.json")))
+    }
 }
 
 /// Contains for fuzz testing
@@ -1,6 +1,6 @@
 //! Configuration for invariant testing
 
-use crate::fuzz::FuzzDictionaryConfig;
+use crate::{fuzz::FuzzDictionaryConfig, AssertionTarget};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -30,8 +30,22 @@ pub struct InvariantConfig {
     pub failure_persist_dir: Option<PathBuf>,
     /// Whether to collect and display fuzzed selectors metrics.
     pub show_metrics: bool,
+    /// Whether to record and display the storage changes of each call of a counterexample.
+    pub show_state_diffs: bool,
     /// Optional timeout (in seconds) for each invariant test.
     pub timeout: Option<u32>,
+    /// The number of workers the runs of each invariant test are split across, `0` uses as many
+    /// as the test runner has threads. Workers share the threads of the test runner.
+    pub workers: u32,
+    /// Whether to keep the call sequences that reach new code in a corpus, replayed and mutated
+    /// by later campaigns.
+    pub coverage_guided: bool,
+    /// Path where the corpus of call sequences is persisted, defaults to the `corpus` dir in
+    /// `failure_persist_dir`. Unlike the default, this dir is not removed by `forge clean`, so
+    /// it can be committed and shared.
+    pub corpus_dir: Option<PathBuf>,
+    /// Assertion contracts that the calls of each run are validated against.
+    pub assertions: Vec<AssertionTarget>,
 }
 
 impl Default for InvariantConfig {
@@ -47,7 +61,12 @@ impl Default for InvariantConfig {
             gas_report_samples: 256,
             failure_persist_dir: None,
             show_metrics: false,
+            show_state_diffs: false,
             timeout: None,
+            workers: 1,
+            coverage_guided: false,
+            corpus_dir: None,
+            assertions: Vec::new(),
         }
     }
 }
@@ -66,7 +85,12 @@ impl InvariantConfig {
             gas_report_samples: 256,
             failure_persist_dir: Some(cache_dir),
             show_metrics: false,
+            show_state_diffs: false,
             timeout: None,
+            workers: 1,
+            coverage_guided: false,
+            corpus_dir: None,
+            assertions: Vec::new(),
         }
     }
 
@@ -77,4 +101,19 @@ impl InvariantConfig {
             .join("failures")
             .join(contract_name.split(':').last().unwrap())
     }
+
+    /// Returns path to the corpus file of the given invariant test, if the corpus is enabled.
+    pub fn corpus_file(&self, contract_name: &str, invariant_name: &str) -> Option<PathBuf> {
+        if !self.coverage_guided {
+            return None
+        }
+        let dir = match &self.corpus_dir {
+            Some(dir) => dir.clone(),
+            None => self.failure_persist_dir.as_ref()?.join("corpus"),
+        };
+        Some(
+            dir.join(contract_name.split(':').last().unwrap())
+                .join(format!("{invariant_name}.json")),
+        )
+    }
 }
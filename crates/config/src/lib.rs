@@ -109,6 +109,18 @@ pub use fuzz::{FuzzConfig, FuzzDictionaryConfig};
 mod invariant;
 pub use invariant::InvariantConfig;
 
+mod symbolic;
+pub use symbolic::SymbolicConfig;
+
+mod credible;
+pub use credible::{AssertionTarget, CredibleConfig};
+
+mod coverage;
+pub use coverage::{CoverageConfig, CoverageThresholds, PathCoverageThresholds};
+
+mod lint;
+pub use lint::LintConfig;
+
 mod inline;
 pub use inline::{InlineConfig, InlineConfigError, NatSpec};
 
@@ -315,6 +327,14 @@ pub struct Config {
     pub fuzz: FuzzConfig,
     /// Configuration for invariant testing
     pub invariant: InvariantConfig,
+    /// Configuration for symbolic testing
+    pub symbolic: SymbolicConfig,
+    /// Configuration for credible layer assertion execution
+    pub credible: CredibleConfig,
+    /// Configuration for `forge coverage`
+    pub coverage: CoverageConfig,
+    /// Configuration for `forge lint`
+    pub lint: LintConfig,
     /// Whether to allow ffi cheatcodes in test
     pub ffi: bool,
     /// Use the create 2 factory in all cases including tests and non-broadcasting scripts.
@@ -553,6 +573,10 @@ impl Config {
         "doc",
         "fuzz",
         "invariant",
+        "symbolic",
+        "credible",
+        "coverage",
+        "lint",
         "labels",
         "dependencies",
         "soldeer",
@@ -2324,6 +2348,10 @@ impl Default for Config {
             show_progress: false,
             fuzz: FuzzConfig::new("cache/fuzz".into()),
             invariant: InvariantConfig::new("cache/invariant".into()),
+            symbolic: Default::default(),
+            credible: Default::default(),
+            coverage: Default::default(),
+            lint: Default::default(),
             always_use_create_2_factory: false,
             ffi: false,
             prompt_timeout: 120,
@@ -3960,6 +3988,253 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fuzz_corpus_file() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [fuzz]
+                coverage_guided = true
+            ",
+            )?;
+            let config = Config::load();
+            assert_eq!(
+                config.fuzz.corpus_file("test/Counter.t.sol:CounterTest", "testFuzz_SetNumber"),
+                Some(PathBuf::from("cache/fuzz/corpus/CounterTest/testFuzz_SetNumber.json"))
+            );
+            assert_eq!(Config::default().fuzz.corpus_file("CounterTest", "testFuzz"), None);
+
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [fuzz]
+                coverage_guided = true
+                corpus_dir = "corpus"
+            "#,
+            )?;
+            let config = Config::load();
+            assert_eq!(
+                config.fuzz.corpus_file("CounterTest", "testFuzz"),
+                Some(PathBuf::from("corpus/CounterTest/testFuzz.json"))
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_invariant_corpus_file() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [invariant]
+                coverage_guided = true
+            ",
+            )?;
+            let config = Config::load();
+            assert_eq!(
+                config.invariant.corpus_file("test/Counter.t.sol:CounterTest", "invariant_Count"),
+                Some(PathBuf::from("cache/invariant/corpus/CounterTest/invariant_Count.json"))
+            );
+
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [invariant]
+                coverage_guided = true
+                corpus_dir = "test/corpus"
+            "#,
+            )?;
+            let config = Config::load();
+            assert_eq!(
+                config.invariant.corpus_file("CounterTest", "invariant_Count"),
+                Some(PathBuf::from("test/corpus/CounterTest/invariant_Count.json"))
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_credible_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [credible]
+                assertion_gas_limit = 1000000
+                gas_report = false
+
+                [profile.ci.credible]
+                fail_on_revert = false
+            ",
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.credible,
+                CredibleConfig {
+                    assertion_gas_limit: 1_000_000,
+                    gas_report: false,
+                    ..Default::default()
+                }
+            );
+
+            jail.set_env("FOUNDRY_PROFILE", "ci");
+            let config = Config::load();
+            assert_eq!(
+                config.credible,
+                CredibleConfig {
+                    assertion_gas_limit: 1_000_000,
+                    gas_report: false,
+                    fail_on_revert: false,
+                    ..Default::default()
+                }
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_assertion_targets() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [fuzz]
+                assertions = [{ adopter = "Counter", assertion = "CounterAssertion" }]
+
+                [invariant]
+                assertions = [{ adopter = "src/Vault.sol:Vault", assertion = "VaultAssertion" }]
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.fuzz.assertions,
+                vec![AssertionTarget {
+                    adopter: "Counter".to_string(),
+                    assertion: "CounterAssertion".to_string(),
+                }]
+            );
+            assert_eq!(
+                config.invariant.assertions,
+                vec![AssertionTarget {
+                    adopter: "src/Vault.sol:Vault".to_string(),
+                    assertion: "VaultAssertion".to_string(),
+                }]
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_coverage_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [coverage]
+                min_lines = 80
+                min_branches = 62.5
+
+                [[coverage.paths]]
+                path = "src/core/**"
+                min_lines = 95
+                min_functions = 100
+
+                [[coverage.paths]]
+                path = "src/core/Vault.sol"
+                min_lines = 100
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.coverage.thresholds,
+                CoverageThresholds {
+                    min_lines: Some(80.),
+                    min_branches: Some(62.5),
+                    ..Default::default()
+                }
+            );
+            assert_eq!(config.coverage.paths.len(), 2);
+            assert_eq!(config.coverage.thresholds_for(Path::new("src/Counter.sol")), None);
+            assert_eq!(
+                config.coverage.thresholds_for(Path::new("src/core/Pool.sol")),
+                Some(CoverageThresholds {
+                    min_lines: Some(95.),
+                    min_functions: Some(100.),
+                    ..Default::default()
+                })
+            );
+            assert_eq!(
+                config.coverage.thresholds_for(Path::new("src/core/Vault.sol")),
+                Some(CoverageThresholds {
+                    min_lines: Some(100.),
+                    min_functions: Some(100.),
+                    ..Default::default()
+                })
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_lint_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [lint]
+                ignore = ["src/legacy/**"]
+
+                [lint.rules]
+                missing-natspec = false
+                naming = true
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(config.lint.ignore, vec!["src/legacy/**".to_string()]);
+            assert!(!config.lint.is_enabled("missing-natspec"));
+            assert!(config.lint.is_enabled("naming"));
+            assert!(config.lint.is_enabled("tx-origin"));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_symbolic_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [symbolic]
+                solver = "cvc5"
+                solver_args = ["--lang", "smt2", "--incremental"]
+                loop_bound = 2
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.symbolic,
+                SymbolicConfig {
+                    solver: "cvc5".to_string(),
+                    solver_args: vec![
+                        "--lang".to_string(),
+                        "smt2".to_string(),
+                        "--incremental".to_string()
+                    ],
+                    loop_bound: 2,
+                    ..Default::default()
+                }
+            );
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_fallback_provider() {
         figment::Jail::expect_with(|jail| {
@@ -0,0 +1,24 @@
+//! Configuration specific to the `forge lint` command
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Contains the lints that `forge lint` runs and the files it skips
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Globs of the files to skip.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// Lints enabled or disabled by id, e.g. `missing-natspec = false`.
+    ///
+    /// Lints that are not listed are enabled.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, bool>,
+}
+
+impl LintConfig {
+    /// Returns true if the lint with the given id is enabled.
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.rules.get(id).copied().unwrap_or(true)
+    }
+}
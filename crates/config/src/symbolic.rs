@@ -0,0 +1,33 @@
+//! Configuration for symbolic testing
+
+use serde::{Deserialize, Serialize};
+
+/// Contains the configuration of the symbolic execution of tests
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolicConfig {
+    /// Whether tests prefixed with `check_` are run, exploring them symbolically. `check_`
+    /// functions are not tests otherwise.
+    pub enabled: bool,
+    /// The SMT solver binary path conditions are discharged to.
+    pub solver: String,
+    /// The arguments the solver is started with, which must make it read SMT-LIB commands from
+    /// its standard input.
+    pub solver_args: Vec<String>,
+    /// The maximum number of paths explored for each test.
+    pub max_paths: u32,
+    /// The maximum number of times a path takes a branch on symbolic values at the same
+    /// instruction, bounding loops over symbolic values.
+    pub loop_bound: u32,
+}
+
+impl Default for SymbolicConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            solver: "z3".to_string(),
+            solver_args: vec!["-in".to_string()],
+            max_paths: 1024,
+            loop_bound: 4,
+        }
+    }
+}
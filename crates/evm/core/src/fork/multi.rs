@@ -8,9 +8,7 @@ use alloy_consensus::BlockHeader;
 use alloy_primitives::{map::HashMap, U256};
 use alloy_provider::network::BlockResponse;
 use alloy_transport::layers::RetryBackoffService;
-use foundry_common::provider::{
-    runtime_transport::RuntimeTransport, ProviderBuilder, RetryProvider,
-};
+use foundry_common::provider::{failover::FailoverTransport, ProviderBuilder, RetryProvider};
 use foundry_config::Config;
 use foundry_fork_db::{cache::BlockchainDbMeta, BackendHandler, BlockchainDb, SharedBackend};
 use futures::{
@@ -184,7 +182,7 @@ impl MultiFork {
     }
 }
 
-type Handler = BackendHandler<RetryBackoffService<RuntimeTransport>, Arc<RetryProvider>>;
+type Handler = BackendHandler<RetryBackoffService<FailoverTransport>, Arc<RetryProvider>>;
 
 type CreateFuture =
     Pin<Box<dyn Future<Output = eyre::Result<(ForkId, CreatedFork, Handler)>> + Send>>;
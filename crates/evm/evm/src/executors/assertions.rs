@@ -0,0 +1,212 @@
+//! Validation of fuzzed calls against credible-layer assertions.
+
+use crate::executors::Executor;
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::{map::HashMap, Address, Bytes, Selector, TxKind, U256};
+use assertion_executor::{
+    db::fork_db::ForkDb,
+    store::{AssertionState, AssertionStore},
+    ExecutorConfig, ExecutorError,
+};
+use eyre::{eyre, Result};
+use foundry_common::{contracts::ContractsByAddress, get_contract_name, ContractsByArtifact};
+use foundry_config::{AssertionTarget, CredibleConfig};
+use foundry_evm_core::decode::RevertDecoder;
+use std::fmt;
+
+/// An assertion contract targeted by a fuzz or invariant test.
+#[derive(Clone, Debug)]
+struct TargetedAssertion {
+    /// The address of the contract the assertions protect.
+    adopter: Address,
+    /// The creation code of the assertion contract, including constructor arguments.
+    code: Bytes,
+    /// The label of the assertion contract.
+    label: String,
+    /// Assertion function names by selector.
+    functions: HashMap<Selector, String>,
+}
+
+/// The assertion contracts that fuzzed calls are validated against.
+///
+/// Assertion contracts are targeted with the `assertions` fuzz and invariant config, which pairs
+/// an adopter contract with an assertion contract. Every instance of the adopter deployed in the
+/// test's `setUp` is protected by its own instance of the assertion contract. Every generated call
+/// is executed by the assertion executor on top of the current state before it is committed, and
+/// a call that invalidates an assertion fails the test.
+///
+/// The assertion executor runs the call without cheatcodes, so calls that revert there are not
+/// validated.
+#[derive(Clone, Debug)]
+pub struct FuzzAssertions {
+    /// The targeted assertion contracts.
+    assertions: Vec<TargetedAssertion>,
+    /// The store holding the deployed assertion contracts, shared by all validated calls.
+    store: AssertionStore,
+    /// The assertion executor settings.
+    executor_config: ExecutorConfig,
+    /// The gas limit of validated calls, if not the block gas limit.
+    tx_gas_limit: Option<u64>,
+}
+
+impl FuzzAssertions {
+    /// Deploys the configured assertion contracts for the adopters deployed by the test setup.
+    ///
+    /// Returns `None` if no assertion contracts are targeted.
+    pub fn new(
+        executor: &Executor,
+        targets: &[AssertionTarget],
+        deployed_contracts: &ContractsByAddress,
+        known_contracts: &ContractsByArtifact,
+        config: &CredibleConfig,
+    ) -> Result<Option<Self>> {
+        if targets.is_empty() {
+            return Ok(None)
+        }
+
+        let mut assertions = Vec::new();
+        for target in targets {
+            let (id, contract) = known_contracts
+                .find_by_name_or_identifier(&target.assertion)?
+                .ok_or_else(|| eyre!("assertion contract `{}` not found", target.assertion))?;
+            let creation_code = contract
+                .bytecode()
+                .ok_or_else(|| eyre!("assertion contract `{}` has no bytecode", id.name))?;
+            let constructor_inputs = contract.abi.constructor().map_or(0, |c| c.inputs.len());
+            let functions: HashMap<_, _> =
+                contract.abi.functions().map(|f| (f.selector(), f.name.clone())).collect();
+
+            let adopters = deployed_contracts
+                .iter()
+                .filter(|(_, (identifier, _))| {
+                    identifier == &target.adopter || get_contract_name(identifier) == target.adopter
+                })
+                .map(|(address, _)| *address);
+            for adopter in adopters {
+                // The adopter is passed to the assertion contract's constructor, if it takes one.
+                let code = match constructor_inputs {
+                    0 => creation_code.clone(),
+                    1 => [&creation_code[..], &DynSolValue::Address(adopter).abi_encode()]
+                        .concat()
+                        .into(),
+                    _ => eyre::bail!(
+                        "the constructor of assertion contract `{}` must take the adopter address as its only argument",
+                        id.name
+                    ),
+                };
+                assertions.push(TargetedAssertion {
+                    adopter,
+                    code,
+                    label: id.name.clone(),
+                    functions: functions.clone(),
+                });
+            }
+        }
+        if assertions.is_empty() {
+            return Ok(None)
+        }
+
+        let executor_config = ExecutorConfig {
+            spec_id: executor.spec_id(),
+            chain_id: executor.env().cfg.chain_id,
+            assertion_gas_limit: config.assertion_gas_limit,
+        };
+        let store = AssertionStore::new_ephemeral()
+            .map_err(|e| eyre!("Assertion store creation failed: {e}"))?;
+        for assertion in &assertions {
+            let assertion_state =
+                AssertionState::new_active(assertion.code.clone(), &executor_config)
+                    .map_err(|e| eyre!("Assertion Executor Error: {e:#?}"))?;
+            store
+                .insert(assertion.adopter, assertion_state)
+                .map_err(|e| eyre!("Assertion insertion failed: {e}"))?;
+        }
+
+        Ok(Some(Self { assertions, store, executor_config, tx_gas_limit: config.tx_gas_limit }))
+    }
+
+    /// Validates the call against the assertion contracts on top of the executor state, without
+    /// committing it.
+    ///
+    /// Returns the first assertion function that the call invalidated, if any.
+    pub fn validate(
+        &self,
+        executor: &Executor,
+        sender: Address,
+        target: Address,
+        calldata: Bytes,
+    ) -> Result<Option<AssertionFailure>> {
+        let mut env = executor.build_test_env(sender, TxKind::Call(target), calldata, U256::ZERO);
+        if let Some(gas_limit) = self.tx_gas_limit {
+            env.tx.gas_limit = gas_limit;
+        }
+
+        // The assertion contracts are deployed once, only the executor is set up on top of the
+        // current state, which it reads through a reference.
+        let mut assertion_executor =
+            self.executor_config.clone().build(executor.backend(), self.store.clone());
+        let mut fork_db = ForkDb::new(assertion_executor.db.clone());
+        let result = match assertion_executor.validate_transaction(
+            env.block.clone(),
+            env.tx.clone(),
+            &mut fork_db,
+        ) {
+            Ok(result) => result,
+            // Calls which cannot be executed outside of the test EVM are not validated.
+            Err(ExecutorError::TxError(err)) => {
+                trace!(target: "forge::test", ?err, "failed to execute call in assertion executor");
+                return Ok(None)
+            }
+            Err(err) => return Err(eyre!("{err:?}")),
+        };
+        if result.is_valid() {
+            return Ok(None)
+        }
+
+        let failure = result.assertions_executions.iter().find_map(|assertion_contract| {
+            let assertion =
+                self.assertions.iter().find(|a| a.adopter == assertion_contract.adopter)?;
+            let assertion_fn =
+                assertion_contract.assertion_fns_results.iter().find(|f| !f.is_success())?;
+            let selector = assertion_fn.id.fn_selector;
+            let revert_data = assertion_fn.as_result().clone().into_output().unwrap_or_default();
+            Some(AssertionFailure {
+                adopter: assertion.adopter,
+                label: assertion.label.clone(),
+                selector,
+                function_name: assertion.functions.get(&selector).cloned(),
+                revert_reason: RevertDecoder::new().decode(&revert_data, None),
+                revert_data,
+            })
+        });
+        Ok(failure)
+    }
+}
+
+/// An assertion function invalidated by a fuzzed call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssertionFailure {
+    /// The address of the contract the assertion protects.
+    pub adopter: Address,
+    /// The label of the assertion contract.
+    pub label: String,
+    /// The selector of the assertion function.
+    pub selector: Selector,
+    /// The name of the assertion function, if it could be decoded from the assertion contract's
+    /// ABI.
+    pub function_name: Option<String>,
+    /// The raw revert data of the assertion function.
+    pub revert_data: Bytes,
+    /// The decoded revert reason of the assertion function.
+    pub revert_reason: String,
+}
+
+impl fmt::Display for AssertionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.function_name {
+            Some(name) => write!(f, "{}::{name}", self.label)?,
+            None => write!(f, "{}::{}", self.label, self.selector)?,
+        }
+        write!(f, " assertion failed: {}", self.revert_reason)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::executors::{Executor, FuzzTestTimer, RawCallResult};
+use crate::executors::{Executor, FuzzAssertions, FuzzTestTimer, RawCallResult};
 use alloy_dyn_abi::JsonAbiExt;
 use alloy_json_abi::Function;
 use alloy_primitives::{map::HashMap, Address, Bytes, Log, U256};
@@ -11,17 +11,26 @@ use foundry_evm_core::{
 };
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
-    strategies::{fuzz_calldata, fuzz_calldata_from_state, EvmFuzzState},
-    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzFixtures, FuzzTestResult,
+    strategies::{
+        fuzz_calldata, fuzz_calldata_from_corpus, fuzz_calldata_from_state, EvmFuzzState,
+    },
+    BaseCounterExample, CounterExample, FuzzCase, FuzzCorpus, FuzzError, FuzzFixtures,
+    FuzzTestResult,
 };
 use foundry_evm_traces::SparsedTraceArena;
 use indicatif::ProgressBar;
-use proptest::test_runner::{TestCaseError, TestError, TestRunner};
-use std::{cell::RefCell, collections::BTreeMap};
+use proptest::{
+    strategy::Strategy,
+    test_runner::{TestCaseError, TestError, TestRunner},
+};
+use std::{cell::RefCell, collections::BTreeMap, path::PathBuf};
 
 mod types;
 pub use types::{CaseOutcome, CounterExampleOutcome, FuzzOutcome};
 
+/// The weight of the corpus mutation strategy in coverage-guided fuzzing, out of 100.
+const CORPUS_WEIGHT: u32 = 50;
+
 /// Contains data collected during fuzz test runs.
 #[derive(Default)]
 pub struct FuzzTestData {
@@ -59,17 +68,32 @@ pub struct FuzzedExecutor {
     sender: Address,
     /// The fuzz configuration
     config: FuzzConfig,
+    /// The assertion contracts targeted by the test contract, if any
+    assertions: Option<FuzzAssertions>,
+    /// Path where the corpus of coverage-guided fuzzing is persisted, if any
+    corpus_file: Option<PathBuf>,
+    /// Whether the coverage of fuzz cases is reported in the result
+    report_coverage: bool,
 }
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
+    ///
+    /// Coverage-guided fuzzing collects the coverage of every fuzz case, and persists its corpus
+    /// at `corpus_file` if set.
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
+        assertions: Option<FuzzAssertions>,
+        corpus_file: Option<PathBuf>,
     ) -> Self {
-        Self { executor, runner, sender, config }
+        let report_coverage = executor.inspector().coverage.is_some();
+        if config.coverage_guided {
+            executor.inspector_mut().collect_coverage(true);
+        }
+        Self { executor, runner, sender, config, assertions, corpus_file, report_coverage }
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -96,6 +120,19 @@ impl FuzzedExecutor {
             100 - dictionary_weight => fuzz_calldata(func.clone(), fuzz_fixtures),
             dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
         ];
+        let corpus = self.config.coverage_guided.then(|| self.load_corpus(address, should_fail));
+        let strategy = match &corpus {
+            Some(corpus) => proptest::prop_oneof![
+                CORPUS_WEIGHT => fuzz_calldata_from_corpus(
+                    func.clone(),
+                    corpus.clone(),
+                    fuzz_calldata(func.clone(), fuzz_fixtures),
+                ),
+                100 - CORPUS_WEIGHT => strategy,
+            ]
+            .boxed(),
+            None => strategy.boxed(),
+        };
         // We want to collect at least one trace which will be displayed to user.
         let max_traces_to_collect = std::cmp::max(1, self.config.gas_report_samples) as usize;
         let show_logs = self.config.show_logs;
@@ -118,6 +155,10 @@ impl FuzzedExecutor {
 
             match fuzz_res {
                 FuzzOutcome::Case(case) => {
+                    if let (Some(corpus), Some(coverage)) = (&corpus, &case.coverage) {
+                        corpus.record(&case.case.calldata, coverage);
+                    }
+
                     let mut data = execution_data.borrow_mut();
                     data.gas_by_case.push((case.case.gas, case.case.stipend));
 
@@ -137,7 +178,9 @@ impl FuzzedExecutor {
                         data.logs.extend(case.logs);
                     }
 
-                    HitMaps::merge_opt(&mut data.coverage, case.coverage);
+                    if self.report_coverage {
+                        HitMaps::merge_opt(&mut data.coverage, case.coverage);
+                    }
 
                     data.deprecated_cheatcodes = case.deprecated_cheatcodes;
 
@@ -146,13 +189,17 @@ impl FuzzedExecutor {
                 FuzzOutcome::CounterExample(CounterExampleOutcome {
                     exit_reason: status,
                     counterexample: outcome,
+                    assertion_failure,
                     ..
                 }) => {
                     // We cannot use the calldata returned by the test runner in `TestError::Fail`,
                     // since that input represents the last run case, which may not correspond with
                     // our failure - when a fuzz case fails, proptest will try to run at least one
                     // more case to find a minimal failure case.
-                    let reason = rd.maybe_decode(&outcome.1.result, Some(status));
+                    let reason = match assertion_failure {
+                        Some(failure) => Some(failure.to_string()),
+                        None => rd.maybe_decode(&outcome.1.result, Some(status)),
+                    };
                     execution_data.borrow_mut().logs.extend(outcome.1.logs.clone());
                     execution_data.borrow_mut().counterexample = outcome;
                     // HACK: we have to use an empty string here to denote `None`.
@@ -161,6 +208,12 @@ impl FuzzedExecutor {
             }
         });
 
+        if let (Some(corpus), Some(path)) = (&corpus, &self.corpus_file) {
+            if let Err(err) = corpus.save(path) {
+                warn!(%err, "failed to persist fuzz corpus");
+            }
+        }
+
         let fuzz_result = execution_data.into_inner();
         let (calldata, call) = fuzz_result.counterexample;
 
@@ -257,7 +310,16 @@ impl FuzzedExecutor {
             });
 
         let success = self.executor.is_raw_call_mut_success(address, &mut call, should_fail);
-        if success {
+
+        // Validate successful calls against the targeted assertion contracts.
+        let assertion_failure = match &self.assertions {
+            Some(assertions) if success && !call.reverted => assertions
+                .validate(&self.executor, self.sender, address, calldata.clone())
+                .map_err(|e| TestCaseError::fail(e.to_string()))?,
+            _ => None,
+        };
+
+        if success && assertion_failure.is_none() {
             Ok(FuzzOutcome::Case(CaseOutcome {
                 case: FuzzCase { calldata, gas: call.gas_used, stipend: call.stipend },
                 traces: call.traces,
@@ -271,10 +333,27 @@ impl FuzzedExecutor {
                 exit_reason: call.exit_reason,
                 counterexample: (calldata, call),
                 breakpoints,
+                assertion_failure,
             }))
         }
     }
 
+    /// Loads the persisted corpus of coverage-guided fuzzing and replays its inputs, so that only
+    /// inputs reaching code that the corpus does not are added to it.
+    fn load_corpus(&self, address: Address, should_fail: bool) -> FuzzCorpus {
+        let corpus = self.corpus_file.as_deref().map(FuzzCorpus::load).unwrap_or_default();
+        for input in corpus.entries() {
+            if let Ok(FuzzOutcome::Case(case)) =
+                self.single_fuzz(address, should_fail, input.clone())
+            {
+                if let Some(coverage) = &case.coverage {
+                    corpus.record(&input, coverage);
+                }
+            }
+        }
+        corpus
+    }
+
     /// Stores fuzz state for use with [fuzz_calldata_from_state]
     pub fn build_fuzz_state(&self, deployed_libs: &[Address]) -> EvmFuzzState {
         if let Some(fork_db) = self.executor.backend().active_fork_db() {
@@ -1,4 +1,4 @@
-use crate::executors::RawCallResult;
+use crate::executors::{AssertionFailure, RawCallResult};
 use alloy_primitives::{map::HashMap, Bytes, Log};
 use foundry_common::evm::Breakpoints;
 use foundry_evm_coverage::HitMaps;
@@ -32,6 +32,8 @@ pub struct CounterExampleOutcome {
     pub exit_reason: InstructionResult,
     /// Breakpoints char pc map.
     pub breakpoints: Breakpoints,
+    /// The targeted assertion invalidated by the call, if any.
+    pub assertion_failure: Option<AssertionFailure>,
 }
 
 /// Outcome of a single fuzz
@@ -1,5 +1,5 @@
 use super::{BasicTxDetails, InvariantContract};
-use crate::executors::RawCallResult;
+use crate::executors::{AssertionFailure, FuzzAssertions, RawCallResult};
 use alloy_primitives::{Address, Bytes};
 use foundry_config::InvariantConfig;
 use foundry_evm_core::decode::RevertDecoder;
@@ -65,6 +65,8 @@ pub struct FailedInvariantCaseData {
     pub shrink_run_limit: u32,
     /// Fail on revert, used to check sequence when shrinking.
     pub fail_on_revert: bool,
+    /// Targeted assertion contracts, used to check sequence when shrinking an assertion failure.
+    pub assertions: Option<FuzzAssertions>,
 }
 
 impl FailedInvariantCaseData {
@@ -97,6 +99,29 @@ impl FailedInvariantCaseData {
             inner_sequence: inner_sequence.to_vec(),
             shrink_run_limit: invariant_config.shrink_run_limit,
             fail_on_revert: invariant_config.fail_on_revert,
+            assertions: None,
+        }
+    }
+
+    /// Creates the case data of a call sequence whose last call invalidated a targeted assertion.
+    pub fn from_assertion_failure(
+        invariant_contract: &InvariantContract<'_>,
+        invariant_config: &InvariantConfig,
+        calldata: &[BasicTxDetails],
+        failure: AssertionFailure,
+        assertions: &FuzzAssertions,
+    ) -> Self {
+        let revert_reason = failure.to_string();
+        Self {
+            test_error: TestError::Fail(revert_reason.clone().into(), calldata.to_vec()),
+            return_reason: "".into(),
+            revert_reason,
+            addr: invariant_contract.address,
+            calldata: invariant_contract.invariant_function.selector().to_vec().into(),
+            inner_sequence: vec![],
+            shrink_run_limit: invariant_config.shrink_run_limit,
+            fail_on_revert: invariant_config.fail_on_revert,
+            assertions: Some(assertions.clone()),
         }
     }
 }
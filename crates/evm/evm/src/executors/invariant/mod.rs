@@ -1,5 +1,5 @@
 use crate::{
-    executors::{Executor, RawCallResult},
+    executors::{Executor, FuzzAssertions, RawCallResult},
     inspectors::Fuzzer,
 };
 use alloy_primitives::{Address, Bytes, FixedBytes, Selector, U256};
@@ -20,25 +20,32 @@ use foundry_evm_fuzz::{
         RandomCallGenerator, SenderFilters, TargetedContract, TargetedContracts,
     },
     strategies::{invariant_strat, override_call_strat, EvmFuzzState},
-    FuzzCase, FuzzFixtures, FuzzedCases,
+    FuzzCase, FuzzFixtures, FuzzedCases, InvariantCorpus,
 };
 use foundry_evm_traces::{CallTraceArena, SparsedTraceArena};
 use indicatif::ProgressBar;
 use parking_lot::RwLock;
 use proptest::{
+    arbitrary::any,
     strategy::{Strategy, ValueTree},
     test_runner::{TestCaseError, TestRunner},
 };
+use rayon::prelude::*;
 use result::{assert_after_invariant, assert_invariants, can_continue};
 use revm::primitives::HashMap;
 use shrink::shrink_sequence;
 use std::{
     cell::RefCell,
-    collections::{btree_map::Entry, HashMap as Map},
-    sync::Arc,
+    collections::{btree_map::Entry, HashMap as Map, VecDeque},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 mod error;
+use error::FailedInvariantCaseData;
 pub use error::{InvariantFailures, InvariantFuzzError};
 use foundry_evm_coverage::HitMaps;
 
@@ -53,6 +60,12 @@ mod shrink;
 use crate::executors::{EvmError, FuzzTestTimer};
 pub use shrink::check_sequence;
 
+/// The probability, out of 100, that a run starts from a mutated sequence of the corpus.
+const CORPUS_WEIGHT: u32 = 50;
+
+/// The reason the runs of a worker end with once another worker of the campaign failed.
+const CAMPAIGN_STOPPED: &str = "Another worker failed.";
+
 sol! {
     interface IInvariantTest {
         #[derive(Default)]
@@ -134,6 +147,8 @@ pub struct InvariantTestData {
     pub coverage: Option<HitMaps>,
     // Metrics for each fuzzed selector.
     pub metrics: Map<String, InvariantMetrics>,
+    // Corpus sequences left to be replayed before mutating the corpus.
+    pub corpus_replays: Vec<Vec<BasicTxDetails>>,
 
     // Proptest runner to query for random values.
     // The strategy only comes with the first `input`. We fill the rest of the `inputs`
@@ -142,6 +157,22 @@ pub struct InvariantTestData {
     pub branch_runner: TestRunner,
 }
 
+impl InvariantTestData {
+    /// Merges the data collected by another worker of the campaign.
+    fn merge(&mut self, other: Self) {
+        self.fuzz_cases.extend(other.fuzz_cases);
+        self.failures.reverts += other.failures.reverts;
+        self.gas_report_traces.extend(other.gas_report_traces);
+        HitMaps::merge_opt(&mut self.coverage, other.coverage);
+        for (selector, metrics) in other.metrics {
+            let merged = self.metrics.entry(selector).or_default();
+            merged.calls += metrics.calls;
+            merged.reverts += metrics.reverts;
+            merged.discards += metrics.discards;
+        }
+    }
+}
+
 /// Contains invariant test data.
 pub struct InvariantTest {
     // Fuzz state of invariant test.
@@ -150,6 +181,10 @@ pub struct InvariantTest {
     pub targeted_contracts: FuzzRunIdentifiedContracts,
     // Data collected during invariant runs.
     pub execution_data: RefCell<InvariantTestData>,
+    // Call sequences that reached new code, if the corpus is enabled.
+    pub corpus: Option<InvariantCorpus>,
+    // Set once any worker of the campaign failed, shared by all workers so they stop running.
+    pub stopped: Arc<AtomicBool>,
 }
 
 impl InvariantTest {
@@ -160,6 +195,7 @@ impl InvariantTest {
         failures: InvariantFailures,
         last_call_results: Option<RawCallResult>,
         branch_runner: TestRunner,
+        corpus: Option<InvariantCorpus>,
     ) -> Self {
         let mut fuzz_cases = vec![];
         if last_call_results.is_none() {
@@ -173,9 +209,50 @@ impl InvariantTest {
             last_call_results,
             coverage: None,
             metrics: Map::default(),
+            corpus_replays: corpus
+                .as_ref()
+                .map(|corpus| corpus.entries().into_iter().rev().collect())
+                .unwrap_or_default(),
+            branch_runner,
+        });
+        Self { fuzz_state, targeted_contracts, execution_data, corpus, stopped: Default::default() }
+    }
+
+    /// Instantiates the invariant test of another worker of the campaign.
+    ///
+    /// The worker gets its own fuzz dictionary, targeted contracts and corpus, forked from the
+    /// ones of this test, and shares its stop flag. The dictionary can't be shared while the
+    /// workers run: the values a run collects from the state are reverted when the run ends, which
+    /// would drop the values of the runs other workers are in the middle of. The sample values that
+    /// persist across runs are merged back once the campaign ends.
+    pub fn new_worker(&self, branch_runner: TestRunner) -> Self {
+        let execution_data = RefCell::new(InvariantTestData {
+            fuzz_cases: vec![],
+            failures: InvariantFailures::new(),
+            last_run_inputs: vec![],
+            gas_report_traces: vec![],
+            last_call_results: None,
+            coverage: None,
+            metrics: Map::default(),
+            corpus_replays: vec![],
             branch_runner,
         });
-        Self { fuzz_state, targeted_contracts, execution_data }
+        Self {
+            fuzz_state: self.fuzz_state.fork(),
+            targeted_contracts: self.targeted_contracts.fork(),
+            execution_data,
+            corpus: self.corpus.as_ref().map(InvariantCorpus::fork),
+            stopped: self.stopped.clone(),
+        }
+    }
+
+    /// Merges the fuzz dictionary and the corpus of another worker of the campaign into this
+    /// test.
+    fn merge_worker(&self, worker: &Self) {
+        self.fuzz_state.merge(&worker.fuzz_state);
+        if let (Some(corpus), Some(worker_corpus)) = (&self.corpus, &worker.corpus) {
+            corpus.merge(worker_corpus);
+        }
     }
 
     /// Returns number of invariant test reverts.
@@ -188,9 +265,20 @@ impl InvariantTest {
         self.execution_data.borrow().failures.error.is_some()
     }
 
-    /// Set invariant test error.
+    /// Set invariant test error, stopping all workers of the campaign.
     pub fn set_error(&self, error: InvariantFuzzError) {
         self.execution_data.borrow_mut().failures.error = Some(error);
+        self.stop();
+    }
+
+    /// Stops the runs of all workers of the campaign.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether a worker of the campaign failed and all workers should stop.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
     }
 
     /// Set last invariant test call results.
@@ -208,6 +296,24 @@ impl InvariantTest {
         HitMaps::merge_opt(&mut self.execution_data.borrow_mut().coverage, new_coverage);
     }
 
+    /// Returns the call sequence a new run starts from, if any. The sequences of the corpus are
+    /// replayed first, then runs start from mutated sequences of the corpus.
+    pub fn next_corpus_sequence(&self) -> Option<Vec<BasicTxDetails>> {
+        let corpus = self.corpus.as_ref()?;
+        let mut data = self.execution_data.borrow_mut();
+        if let Some(sequence) = data.corpus_replays.pop() {
+            return Some(sequence)
+        }
+        let (weight, entry, other, split, seed) = any::<(u32, usize, usize, usize, u64)>()
+            .new_tree(&mut data.branch_runner)
+            .ok()?
+            .current();
+        if weight % 100 >= CORPUS_WEIGHT {
+            return None
+        }
+        corpus.mutate(entry, other, split, seed)
+    }
+
     /// Update metrics for a fuzzed selector, extracted from tx details.
     /// Always increments number of calls; discarded runs (through assume cheatcodes) are tracked
     /// separated from reverts.
@@ -228,7 +334,13 @@ impl InvariantTest {
 
     /// End invariant test run by collecting results, cleaning collected artifacts and reverting
     /// created fuzz state.
-    pub fn end_run(&self, run: InvariantTestRun, gas_samples: usize) {
+    pub fn end_run(&self, mut run: InvariantTestRun, gas_samples: usize) {
+        // Keep the executed call sequence if it reached new code.
+        if let (Some(corpus), Some(coverage)) = (&self.corpus, &run.coverage) {
+            run.inputs.truncate(run.fuzz_runs.len());
+            corpus.record(&run.inputs, coverage);
+        }
+
         // We clear all the targeted contracts created during this run.
         self.targeted_contracts.clear_created_contracts(run.created_contracts);
 
@@ -261,6 +373,10 @@ pub struct InvariantTestRun {
     pub depth: u32,
     // Current assume rejects of the invariant run.
     pub assume_rejects_counter: u32,
+    // Calls of a corpus sequence left to be executed before generating new calls.
+    pub planned_inputs: VecDeque<BasicTxDetails>,
+    // Coverage collected from the calls of the run, if the corpus is enabled.
+    pub coverage: Option<HitMaps>,
 }
 
 impl InvariantTestRun {
@@ -274,6 +390,17 @@ impl InvariantTestRun {
             run_traces: vec![],
             depth: 0,
             assume_rejects_counter: 0,
+            planned_inputs: VecDeque::new(),
+            coverage: None,
+        }
+    }
+
+    /// Starts the run from the given call sequence instead of the generated first input.
+    pub fn start_from(&mut self, sequence: Vec<BasicTxDetails>) {
+        let mut sequence = VecDeque::from(sequence);
+        if let Some(first_input) = sequence.pop_front() {
+            self.inputs = vec![first_input];
+            self.planned_inputs = sequence;
         }
     }
 }
@@ -297,17 +424,32 @@ pub struct InvariantExecutor<'a> {
     project_contracts: &'a ContractsByArtifact,
     /// Filters contracts to be fuzzed through their artifact identifiers.
     artifact_filters: ArtifactFilters,
+    /// Assertion contracts every fuzzed call is validated against.
+    assertions: Option<FuzzAssertions>,
+    /// Path where the corpus of call sequences is persisted, if any.
+    corpus_file: Option<PathBuf>,
+    /// Whether the coverage of fuzzed calls is reported in the result.
+    report_coverage: bool,
 }
 
 impl<'a> InvariantExecutor<'a> {
     /// Instantiates a fuzzed executor EVM given a testrunner
+    ///
+    /// If the corpus is enabled, the coverage of every fuzzed call is collected and the corpus is
+    /// persisted at `corpus_file` if set.
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         config: InvariantConfig,
         setup_contracts: &'a ContractsByAddress,
         project_contracts: &'a ContractsByArtifact,
+        assertions: Option<FuzzAssertions>,
+        corpus_file: Option<PathBuf>,
     ) -> Self {
+        let report_coverage = executor.inspector().coverage.is_some();
+        if config.coverage_guided {
+            executor.inspector_mut().collect_coverage(true);
+        }
         Self {
             executor,
             runner,
@@ -315,6 +457,9 @@ impl<'a> InvariantExecutor<'a> {
             setup_contracts,
             project_contracts,
             artifact_filters: ArtifactFilters::default(),
+            assertions,
+            corpus_file,
+            report_coverage,
         }
     }
 
@@ -331,173 +476,350 @@ impl<'a> InvariantExecutor<'a> {
             return Err(eyre!("Invariant test function should have no inputs"))
         }
 
-        let (invariant_test, invariant_strategy) =
+        let (invariant_test, targeted_senders) =
             self.prepare_test(&invariant_contract, fuzz_fixtures, deployed_libs)?;
 
         // Start timer for this invariant test.
         let timer = FuzzTestTimer::new(self.config.timeout);
 
-        let _ = self.runner.run(&invariant_strategy, |first_input| {
-            // Create current invariant run data.
-            let mut current_run = InvariantTestRun::new(
-                first_input,
-                // Before each run, we must reset the backend state.
-                self.executor.clone(),
-                self.config.depth as usize,
+        // Split the runs across workers, each running its share with its own RNG stream, executor
+        // clones, fuzz dictionary and targeted contracts. All workers stop once one of them fails.
+        let workers = self.workers();
+        let mut runners = if workers == 1 {
+            vec![self.runner.clone()]
+        } else {
+            (0..workers)
+                .map(|worker| {
+                    let mut config = self.runner.config().clone();
+                    config.cases =
+                        self.config.runs / workers + u32::from(worker < self.config.runs % workers);
+                    TestRunner::new_with_rng(config, self.runner.new_rng())
+                })
+                .collect()
+        };
+        let mut invariant_tests = vec![invariant_test];
+        for runner in runners.iter().skip(1) {
+            invariant_tests.push(invariant_tests[0].new_worker(runner.clone()));
+        }
+        let strategies = invariant_tests
+            .iter()
+            .map(|invariant_test| {
+                invariant_strat(
+                    invariant_test.fuzz_state.clone(),
+                    targeted_senders.clone(),
+                    invariant_test.targeted_contracts.clone(),
+                    self.config.dictionary.dictionary_weight,
+                    fuzz_fixtures.clone(),
+                )
+                .no_shrink()
+            })
+            .collect::<Vec<_>>();
+
+        if workers == 1 {
+            self.run_worker(
+                &mut runners[0],
+                &invariant_tests[0],
+                &strategies[0],
+                &invariant_contract,
+                &timer,
+                progress,
             );
+        } else {
+            // The workers run on the thread pool of the test runner, sharing its threads with the
+            // other tests instead of spawning threads of their own.
+            invariant_tests = invariant_tests
+                .into_par_iter()
+                .zip(runners.par_iter_mut())
+                .zip(strategies.par_iter())
+                .map(|((invariant_test, runner), strategy)| {
+                    self.run_worker(
+                        runner,
+                        &invariant_test,
+                        strategy,
+                        &invariant_contract,
+                        &timer,
+                        progress,
+                    );
+                    invariant_test
+                })
+                .collect();
+        }
+
+        // Merge the workers in order, so the merged dictionary and corpus don't depend on the order
+        // in which the workers finished.
+        for worker in &invariant_tests[1..] {
+            invariant_tests[0].merge_worker(worker);
+        }
 
-            // We stop the run immediately if we have reverted, and `fail_on_revert` is set.
-            if self.config.fail_on_revert && invariant_test.reverts() > 0 {
-                return Err(TestCaseError::fail("Revert occurred."))
+        trace!(?fuzz_fixtures);
+        invariant_tests[0].fuzz_state.log_stats();
+
+        if let (Some(corpus), Some(path)) = (&invariant_tests[0].corpus, &self.corpus_file) {
+            if let Err(err) = corpus.save(path) {
+                warn!(target: "forge::test", %err, "failed to persist invariant corpus");
             }
+        }
 
-            while current_run.depth < self.config.depth {
-                // Check if the timeout has been reached.
-                if timer.is_timed_out() {
-                    // Since we never record a revert here the test is still considered
-                    // successful even though it timed out. We *want*
-                    // this behavior for now, so that's ok, but
-                    // future developers should be aware of this.
-                    return Err(TestCaseError::fail(TEST_TIMEOUT));
-                }
+        // Report the failure of the first worker that failed, if any.
+        let failed_worker =
+            invariant_tests.iter().position(InvariantTest::has_errors).unwrap_or_default();
+        let mut result = invariant_tests.swap_remove(failed_worker).execution_data.into_inner();
+        for invariant_test in invariant_tests {
+            result.merge(invariant_test.execution_data.into_inner());
+        }
+        result.gas_report_traces.truncate(self.config.gas_report_samples as usize);
 
-                let tx = current_run.inputs.last().ok_or_else(|| {
-                    TestCaseError::fail("No input generated to call fuzzed target.")
-                })?;
+        Ok(InvariantFuzzTestResult {
+            error: result.failures.error,
+            cases: result.fuzz_cases,
+            reverts: result.failures.reverts,
+            last_run_inputs: result.last_run_inputs,
+            gas_report_traces: result.gas_report_traces,
+            coverage: result.coverage,
+            metrics: result.metrics,
+        })
+    }
 
-                // Execute call from the randomly generated sequence without committing state.
-                // State is committed only if call is not a magic assume.
-                let mut call_result = current_run
-                    .executor
-                    .call_raw(
-                        tx.sender,
-                        tx.call_details.target,
-                        tx.call_details.calldata.clone(),
-                        U256::ZERO,
-                    )
-                    .map_err(|e| {
-                        TestCaseError::fail(format!("Could not make raw evm call: {e}"))
-                    })?;
+    /// Executes the runs of a worker of the campaign until they are done or a run of any worker
+    /// fails.
+    fn run_worker(
+        &self,
+        runner: &mut TestRunner,
+        invariant_test: &InvariantTest,
+        invariant_strategy: &impl Strategy<Value = BasicTxDetails>,
+        invariant_contract: &InvariantContract<'_>,
+        timer: &FuzzTestTimer,
+        progress: Option<&ProgressBar>,
+    ) {
+        let _ = runner.run(invariant_strategy, |first_input| {
+            let result = self.execute_run(
+                first_input,
+                invariant_test,
+                invariant_strategy,
+                invariant_contract,
+                timer,
+                progress,
+            );
+            // Errors are also recorded directly into the failures, e.g. by broken invariants.
+            if invariant_test.has_errors() {
+                invariant_test.stop();
+            }
+            result
+        });
+    }
 
-                let discarded = call_result.result.as_ref() == MAGIC_ASSUME;
-                if self.config.show_metrics {
-                    invariant_test.record_metrics(tx, call_result.reverted, discarded);
-                }
+    /// Executes a single run of the campaign, starting with `first_input`.
+    fn execute_run(
+        &self,
+        first_input: BasicTxDetails,
+        invariant_test: &InvariantTest,
+        invariant_strategy: &impl Strategy<Value = BasicTxDetails>,
+        invariant_contract: &InvariantContract<'_>,
+        timer: &FuzzTestTimer,
+        progress: Option<&ProgressBar>,
+    ) -> Result<(), TestCaseError> {
+        // Before each run, we must reset the backend state. The values collected while executing
+        // the run go to the dictionary of the worker.
+        let mut executor = self.executor.clone();
+        if let Some(fuzzer) = &mut executor.inspector_mut().fuzzer {
+            fuzzer.fuzz_state = invariant_test.fuzz_state.clone();
+        }
 
-                // Collect coverage from last fuzzed call.
-                invariant_test.merge_coverage(call_result.coverage.clone());
+        // Create current invariant run data.
+        let mut current_run =
+            InvariantTestRun::new(first_input, executor, self.config.depth as usize);
+        if let Some(sequence) = invariant_test.next_corpus_sequence() {
+            current_run.start_from(sequence);
+        }
 
-                if discarded {
-                    current_run.inputs.pop();
-                    current_run.assume_rejects_counter += 1;
-                    if current_run.assume_rejects_counter > self.config.max_assume_rejects {
-                        invariant_test.set_error(InvariantFuzzError::MaxAssumeRejects(
-                            self.config.max_assume_rejects,
-                        ));
-                        return Err(TestCaseError::fail("Max number of vm.assume rejects reached."))
-                    }
-                } else {
-                    // Commit executed call result.
-                    current_run.executor.commit(&mut call_result);
-
-                    // Collect data for fuzzing from the state changeset.
-                    let mut state_changeset = call_result.state_changeset.clone();
-                    if !call_result.reverted {
-                        collect_data(
-                            &invariant_test,
-                            &mut state_changeset,
-                            tx,
-                            &call_result,
-                            self.config.depth,
-                        );
-                    }
+        // We stop the run immediately if we have reverted, and `fail_on_revert` is set.
+        if self.config.fail_on_revert && invariant_test.reverts() > 0 {
+            return Err(TestCaseError::fail("Revert occurred."))
+        }
 
-                    // Collect created contracts and add to fuzz targets only if targeted contracts
-                    // are updatable.
-                    if let Err(error) =
-                        &invariant_test.targeted_contracts.collect_created_contracts(
-                            &state_changeset,
-                            self.project_contracts,
-                            self.setup_contracts,
-                            &self.artifact_filters,
-                            &mut current_run.created_contracts,
+        while current_run.depth < self.config.depth {
+            // Stop as soon as any worker of the campaign failed.
+            if invariant_test.is_stopped() {
+                return Err(TestCaseError::fail(CAMPAIGN_STOPPED))
+            }
+
+            // Check if the timeout has been reached.
+            if timer.is_timed_out() {
+                // Since we never record a revert here the test is still considered
+                // successful even though it timed out. We *want*
+                // this behavior for now, so that's ok, but
+                // future developers should be aware of this.
+                return Err(TestCaseError::fail(TEST_TIMEOUT));
+            }
+
+            let tx = current_run
+                .inputs
+                .last()
+                .ok_or_else(|| TestCaseError::fail("No input generated to call fuzzed target."))?;
+
+            // Execute call from the randomly generated sequence without committing state.
+            // State is committed only if call is not a magic assume.
+            let mut call_result = current_run
+                .executor
+                .call_raw(
+                    tx.sender,
+                    tx.call_details.target,
+                    tx.call_details.calldata.clone(),
+                    U256::ZERO,
+                )
+                .map_err(|e| TestCaseError::fail(format!("Could not make raw evm call: {e}")))?;
+
+            let discarded = call_result.result.as_ref() == MAGIC_ASSUME;
+            if self.config.show_metrics {
+                invariant_test.record_metrics(tx, call_result.reverted, discarded);
+            }
+
+            // Collect coverage from last fuzzed call.
+            if self.report_coverage {
+                invariant_test.merge_coverage(call_result.coverage.clone());
+            }
+
+            if discarded {
+                current_run.inputs.pop();
+                current_run.assume_rejects_counter += 1;
+                if current_run.assume_rejects_counter > self.config.max_assume_rejects {
+                    invariant_test.set_error(InvariantFuzzError::MaxAssumeRejects(
+                        self.config.max_assume_rejects,
+                    ));
+                    return Err(TestCaseError::fail("Max number of vm.assume rejects reached."))
+                }
+            } else {
+                // Validate the call against the targeted assertions before committing it. A
+                // failed assertion is treated as a broken invariant.
+                if let Some(assertions) = self.assertions.as_ref().filter(|_| !call_result.reverted)
+                {
+                    let failure = assertions
+                        .validate(
+                            &current_run.executor,
+                            tx.sender,
+                            tx.call_details.target,
+                            tx.call_details.calldata.clone(),
                         )
-                    {
-                        warn!(target: "forge::test", "{error}");
-                    }
-                    current_run.fuzz_runs.push(FuzzCase {
-                        calldata: tx.call_details.calldata.clone(),
-                        gas: call_result.gas_used,
-                        stipend: call_result.stipend,
-                    });
-
-                    // Determine if test can continue or should exit.
-                    let result = can_continue(
-                        &invariant_contract,
-                        &invariant_test,
-                        &mut current_run,
-                        &self.config,
-                        call_result,
-                        &state_changeset,
-                    )
-                    .map_err(|e| TestCaseError::fail(e.to_string()))?;
-                    if !result.can_continue || current_run.depth == self.config.depth - 1 {
+                        .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                    if let Some(failure) = failure {
+                        if !invariant_test.has_errors() {
+                            let case_data = FailedInvariantCaseData::from_assertion_failure(
+                                invariant_contract,
+                                &self.config,
+                                &current_run.inputs,
+                                failure,
+                                assertions,
+                            );
+                            invariant_test
+                                .set_error(InvariantFuzzError::BrokenInvariant(case_data));
+                        }
                         invariant_test.set_last_run_inputs(&current_run.inputs);
+                        return Err(TestCaseError::fail("Assertion failed."))
                     }
-                    // If test cannot continue then stop current run and exit test suite.
-                    if !result.can_continue {
-                        return Err(TestCaseError::fail("Test cannot continue."))
-                    }
+                }
 
-                    invariant_test.set_last_call_results(result.call_result);
-                    current_run.depth += 1;
+                // Collect coverage of the run for the corpus.
+                if invariant_test.corpus.is_some() {
+                    HitMaps::merge_opt(&mut current_run.coverage, call_result.coverage.clone());
                 }
 
-                // Generates the next call from the run using the recently updated
-                // dictionary.
-                current_run.inputs.push(
-                    invariant_strategy
-                        .new_tree(&mut invariant_test.execution_data.borrow_mut().branch_runner)
-                        .map_err(|_| TestCaseError::Fail("Could not generate case".into()))?
-                        .current(),
-                );
-            }
+                // Commit executed call result.
+                current_run.executor.commit(&mut call_result);
+
+                // Collect data for fuzzing from the state changeset.
+                let mut state_changeset = call_result.state_changeset.clone();
+                if !call_result.reverted {
+                    collect_data(
+                        invariant_test,
+                        &mut state_changeset,
+                        tx,
+                        &call_result,
+                        self.config.depth,
+                    );
+                }
 
-            // Call `afterInvariant` only if it is declared and test didn't fail already.
-            if invariant_contract.call_after_invariant && !invariant_test.has_errors() {
-                assert_after_invariant(
-                    &invariant_contract,
-                    &invariant_test,
-                    &current_run,
+                // Collect created contracts and add to fuzz targets only if targeted contracts
+                // are updatable.
+                if let Err(error) = &invariant_test.targeted_contracts.collect_created_contracts(
+                    &state_changeset,
+                    self.project_contracts,
+                    self.setup_contracts,
+                    &self.artifact_filters,
+                    &mut current_run.created_contracts,
+                ) {
+                    warn!(target: "forge::test", "{error}");
+                }
+                current_run.fuzz_runs.push(FuzzCase {
+                    calldata: tx.call_details.calldata.clone(),
+                    gas: call_result.gas_used,
+                    stipend: call_result.stipend,
+                });
+
+                // Determine if test can continue or should exit.
+                let result = can_continue(
+                    invariant_contract,
+                    invariant_test,
+                    &mut current_run,
                     &self.config,
+                    call_result,
+                    &state_changeset,
                 )
-                .map_err(|_| TestCaseError::Fail("Failed to call afterInvariant".into()))?;
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                if !result.can_continue || current_run.depth == self.config.depth - 1 {
+                    invariant_test.set_last_run_inputs(&current_run.inputs);
+                }
+                // If test cannot continue then stop current run and exit test suite.
+                if !result.can_continue {
+                    return Err(TestCaseError::fail("Test cannot continue."))
+                }
+
+                invariant_test.set_last_call_results(result.call_result);
+                current_run.depth += 1;
             }
 
-            // End current invariant test run.
-            invariant_test.end_run(current_run, self.config.gas_report_samples as usize);
+            // Continues the corpus sequence of the run, if any, or generates the next call
+            // from the run using the recently updated dictionary.
+            let next_input = match current_run.planned_inputs.pop_front() {
+                Some(input) => input,
+                None => invariant_strategy
+                    .new_tree(&mut invariant_test.execution_data.borrow_mut().branch_runner)
+                    .map_err(|_| TestCaseError::Fail("Could not generate case".into()))?
+                    .current(),
+            };
+            current_run.inputs.push(next_input);
+        }
 
-            // If running with progress then increment completed runs.
-            if let Some(progress) = progress {
-                progress.inc(1);
-            }
+        // Call `afterInvariant` only if it is declared and test didn't fail already.
+        if invariant_contract.call_after_invariant && !invariant_test.has_errors() {
+            assert_after_invariant(invariant_contract, invariant_test, &current_run, &self.config)
+                .map_err(|_| TestCaseError::Fail("Failed to call afterInvariant".into()))?;
+        }
 
-            Ok(())
-        });
+        // End current invariant test run.
+        invariant_test.end_run(current_run, self.config.gas_report_samples as usize);
 
-        trace!(?fuzz_fixtures);
-        invariant_test.fuzz_state.log_stats();
+        // If running with progress then increment completed runs.
+        if let Some(progress) = progress {
+            progress.inc(1);
+        }
 
-        let result = invariant_test.execution_data.into_inner();
-        Ok(InvariantFuzzTestResult {
-            error: result.failures.error,
-            cases: result.fuzz_cases,
-            reverts: result.failures.reverts,
-            last_run_inputs: result.last_run_inputs,
-            gas_report_traces: result.gas_report_traces,
-            coverage: result.coverage,
-            metrics: result.metrics,
-        })
+        Ok(())
+    }
+
+    /// Returns the number of workers the campaign is split across.
+    ///
+    /// `0` uses as many workers as the thread pool of the test runner has threads. Campaigns
+    /// overriding calls run on a single worker, as the call generator keeps the sequence of
+    /// overridden calls of the current run.
+    fn workers(&self) -> u32 {
+        if self.config.call_override {
+            return 1
+        }
+        let workers = match self.config.workers {
+            0 => rayon::current_num_threads() as u32,
+            workers => workers,
+        };
+        workers.clamp(1, self.config.runs.max(1))
     }
 
     /// Prepares certain structures to execute the invariant tests:
@@ -508,7 +830,7 @@ impl<'a> InvariantExecutor<'a> {
         invariant_contract: &InvariantContract<'_>,
         fuzz_fixtures: &FuzzFixtures,
         deployed_libs: &[Address],
-    ) -> Result<(InvariantTest, impl Strategy<Value = BasicTxDetails>)> {
+    ) -> Result<(InvariantTest, SenderFilters)> {
         // Finds out the chosen deployed contracts and/or senders.
         self.select_contract_artifacts(invariant_contract.address)?;
         let (targeted_senders, targeted_contracts) =
@@ -521,16 +843,6 @@ impl<'a> InvariantExecutor<'a> {
             deployed_libs,
         );
 
-        // Creates the invariant strategy.
-        let strategy = invariant_strat(
-            fuzz_state.clone(),
-            targeted_senders,
-            targeted_contracts.clone(),
-            self.config.dictionary.dictionary_weight,
-            fuzz_fixtures.clone(),
-        )
-        .no_shrink();
-
         // Allows `override_call_strat` to use the address given by the Fuzzer inspector during
         // EVM execution.
         let mut call_generator = None;
@@ -577,8 +889,11 @@ impl<'a> InvariantExecutor<'a> {
                 failures,
                 last_call_results,
                 self.runner.clone(),
+                self.config.coverage_guided.then(|| {
+                    self.corpus_file.as_deref().map(InvariantCorpus::load).unwrap_or_default()
+                }),
             ),
-            strategy,
+            targeted_senders,
         ))
     }
 
@@ -7,10 +7,11 @@ use alloy_dyn_abi::JsonAbiExt;
 use alloy_primitives::{map::HashMap, Log};
 use eyre::Result;
 use foundry_common::{ContractsByAddress, ContractsByArtifact};
+use foundry_compilers::artifacts::StorageLayout;
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
     invariant::{BasicTxDetails, InvariantContract},
-    BaseCounterExample,
+    BaseCounterExample, StorageDiff,
 };
 use foundry_evm_traces::{load_contracts, TraceKind, TraceMode, Traces};
 use indicatif::ProgressBar;
@@ -21,6 +22,9 @@ use std::sync::Arc;
 
 /// Replays a call sequence for collecting logs and traces.
 /// Returns counterexample to be used when the call sequence is a failed scenario.
+///
+/// If `storage_layouts` are given, the storage changes of each call are recorded in the
+/// counterexample, named after the state variables of the layout of the changed contract.
 #[allow(clippy::too_many_arguments)]
 pub fn replay_run(
     invariant_contract: &InvariantContract<'_>,
@@ -32,6 +36,7 @@ pub fn replay_run(
     coverage: &mut Option<HitMaps>,
     deprecated_cheatcodes: &mut HashMap<&'static str, Option<&'static str>>,
     inputs: &[BasicTxDetails],
+    storage_layouts: Option<&HashMap<String, StorageLayout>>,
 ) -> Result<Vec<BaseCounterExample>> {
     // We want traces for a failed case.
     if executor.inspector().tracer.is_none() {
@@ -42,6 +47,9 @@ pub fn replay_run(
 
     // Replay each call from the sequence, collect logs, traces and coverage.
     for tx in inputs {
+        if storage_layouts.is_some() {
+            start_state_diff_recording(&mut executor);
+        }
         let call_result = executor.transact_raw(
             tx.sender,
             tx.call_details.target,
@@ -58,13 +66,18 @@ pub fn replay_run(
             .extend(load_contracts(call_result.traces.iter().map(|a| &a.arena), known_contracts));
 
         // Create counter example to be used in failed case.
-        counterexample_sequence.push(BaseCounterExample::from_invariant_call(
+        let mut counterexample = BaseCounterExample::from_invariant_call(
             tx.sender,
             tx.call_details.target,
             &tx.call_details.calldata,
             &ided_contracts,
             call_result.traces,
-        ));
+        );
+        if let Some(storage_layouts) = storage_layouts {
+            counterexample.state_diff =
+                take_storage_diffs(&mut executor, &ided_contracts, storage_layouts);
+        }
+        counterexample_sequence.push(counterexample);
     }
 
     // Replay invariant to collect logs and traces.
@@ -110,6 +123,7 @@ pub fn replay_error(
     coverage: &mut Option<HitMaps>,
     deprecated_cheatcodes: &mut HashMap<&'static str, Option<&'static str>>,
     progress: Option<&ProgressBar>,
+    storage_layouts: Option<&HashMap<String, StorageLayout>>,
 ) -> Result<Vec<BaseCounterExample>> {
     match failed_case.test_error {
         // Don't use at the moment.
@@ -137,11 +151,60 @@ pub fn replay_error(
                 coverage,
                 deprecated_cheatcodes,
                 &calls,
+                storage_layouts,
             )
         }
     }
 }
 
+/// Starts recording the account accesses of the next call with the cheatcode inspector.
+fn start_state_diff_recording(executor: &mut Executor) {
+    if let Some(cheatcodes) = &mut executor.inspector_mut().cheatcodes {
+        cheatcodes.recorded_account_diffs_stack = Some(Default::default());
+    }
+}
+
+/// Stops recording account accesses and returns the storage slots changed by the recorded call.
+fn take_storage_diffs(
+    executor: &mut Executor,
+    ided_contracts: &ContractsByAddress,
+    storage_layouts: &HashMap<String, StorageLayout>,
+) -> Vec<StorageDiff> {
+    let Some(cheatcodes) = &mut executor.inspector_mut().cheatcodes else { return vec![] };
+    let accesses = cheatcodes.recorded_account_diffs_stack.take().unwrap_or_default();
+
+    let mut diffs: Vec<StorageDiff> = vec![];
+    let writes = accesses
+        .into_iter()
+        .flatten()
+        .flat_map(|access| access.storageAccesses)
+        .filter(|access| access.isWrite && !access.reverted);
+    for write in writes {
+        match diffs.iter_mut().find(|diff| diff.address == write.account && diff.slot == write.slot)
+        {
+            Some(diff) => diff.new_value = write.newValue,
+            None => diffs.push(StorageDiff {
+                address: write.account,
+                slot: write.slot,
+                label: None,
+                previous_value: write.previousValue,
+                new_value: write.newValue,
+            }),
+        }
+    }
+    diffs.retain(|diff| diff.previous_value != diff.new_value);
+
+    for diff in &mut diffs {
+        if let Some(layout) = ided_contracts
+            .get(&diff.address)
+            .and_then(|(identifier, _)| storage_layouts.get(identifier))
+        {
+            diff.label_from_layout(layout);
+        }
+    }
+    diffs
+}
+
 /// Sets up the calls generated by the internal fuzzer, if they exist.
 fn set_up_inner_replay(executor: &mut Executor, inner_sequence: &[Option<BasicTxDetails>]) {
     if let Some(fuzzer) = &mut executor.inspector_mut().fuzzer {
@@ -2,7 +2,7 @@ use crate::executors::{
     invariant::{
         call_after_invariant_function, call_invariant_function, error::FailedInvariantCaseData,
     },
-    Executor,
+    Executor, FuzzAssertions,
 };
 use alloy_primitives::{Address, Bytes, U256};
 use foundry_evm_core::constants::MAGIC_ASSUME;
@@ -120,6 +120,7 @@ pub(crate) fn shrink_sequence(
             failed_case.calldata.clone(),
             failed_case.fail_on_revert,
             call_after_invariant,
+            failed_case.assertions.as_ref(),
         ) {
             // If candidate sequence still fails then shrink more if possible.
             Ok((false, _)) if !shrinker.simplify() => break,
@@ -137,12 +138,14 @@ pub(crate) fn shrink_sequence(
     Ok(shrinker.current().map(|idx| &calls[idx]).cloned().collect())
 }
 
-/// Checks if the given call sequence breaks the invariant.
+/// Checks if the given call sequence breaks the invariant or, if `assertions` are given, any of
+/// the targeted assertions.
 ///
 /// Used in shrinking phase for checking candidate sequences and in replay failures phase to test
 /// persisted failures.
 /// Returns the result of invariant check (and afterInvariant call if needed) and if sequence was
 /// entirely applied.
+#[allow(clippy::too_many_arguments)]
 pub fn check_sequence(
     mut executor: Executor,
     calls: &[BasicTxDetails],
@@ -151,10 +154,27 @@ pub fn check_sequence(
     calldata: Bytes,
     fail_on_revert: bool,
     call_after_invariant: bool,
+    assertions: Option<&FuzzAssertions>,
 ) -> eyre::Result<(bool, bool)> {
     // Apply the call sequence.
-    for call_index in sequence {
+    let sequence_len = sequence.len();
+    for (sequence_index, call_index) in sequence.into_iter().enumerate() {
         let tx = &calls[call_index];
+        // Validate the call against the targeted assertions before applying it.
+        if let Some(assertions) = assertions {
+            if assertions
+                .validate(
+                    &executor,
+                    tx.sender,
+                    tx.call_details.target,
+                    tx.call_details.calldata.clone(),
+                )?
+                .is_some()
+            {
+                return Ok((false, sequence_index + 1 == sequence_len));
+            }
+        }
+
         let call_result = executor.transact_raw(
             tx.sender,
             tx.call_details.target,
@@ -41,6 +41,9 @@ use std::{
     time::{Duration, Instant},
 };
 
+pub mod assertions;
+pub use assertions::{AssertionFailure, FuzzAssertions};
+
 mod builder;
 pub use builder::ExecutorBuilder;
 
@@ -50,6 +53,9 @@ pub use fuzz::FuzzedExecutor;
 pub mod invariant;
 pub use invariant::InvariantExecutor;
 
+pub mod symbolic;
+pub use symbolic::SymbolicExecutor;
+
 mod trace;
 pub use trace::TracingExecutor;
 
@@ -0,0 +1,1547 @@
+use super::solver::{literal, Check, Solver, WORD};
+use crate::executors::Executor;
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::{
+    hex, keccak256,
+    map::{HashMap, HashSet},
+    Address, Bytes, B256, I256, U256,
+};
+use alloy_sol_types::{Revert, SolError};
+use eyre::Result;
+use foundry_cheatcodes::Vm;
+use foundry_evm_core::{
+    backend::GLOBAL_FAIL_SLOT,
+    constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+};
+use revm::{
+    db::DatabaseRef,
+    interpreter::{opcode, OpCode},
+    primitives::KECCAK_EMPTY,
+};
+use std::{fmt, sync::Arc};
+
+/// The maximum number of instructions executed on a single path.
+const MAX_STEPS: usize = 1_000_000;
+
+/// The maximum size of the memory of a call frame.
+const MAX_MEMORY: usize = 1 << 20;
+
+/// The maximum call depth.
+const MAX_DEPTH: usize = 1024;
+
+/// Hashes are assumed not to fall below this bound, so they do not alias small storage slots.
+const MIN_HASH: U256 = U256::from_limbs([0, 1, 0, 0]);
+
+/// The storage of accounts, as the writes to their slots in order.
+type Storage = HashMap<Address, Vec<(Word, Word)>>;
+
+/// A byte of memory, calldata or return data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Byte {
+    Concrete(u8),
+    /// The byte at `index` of a symbolic word, most significant first.
+    Symbolic(Arc<str>, u8),
+}
+
+/// A word of the stack or of storage.
+#[derive(Clone, Debug)]
+pub enum Word {
+    Concrete(U256),
+    /// A term defined in the solver, with the bytes it was assembled from, if any.
+    Symbolic(Arc<str>, Option<Arc<[Byte]>>),
+}
+
+impl PartialEq for Word {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Concrete(a), Self::Concrete(b)) => a == b,
+            (Self::Symbolic(a, _), Self::Symbolic(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Word {
+    /// Returns the word of a declared symbol.
+    pub fn symbol(name: &str) -> Self {
+        Self::Symbolic(name.into(), None)
+    }
+
+    /// Returns the value of the word if it is concrete.
+    pub fn as_concrete(&self) -> Option<U256> {
+        match self {
+            Self::Concrete(value) => Some(*value),
+            Self::Symbolic(..) => None,
+        }
+    }
+
+    /// Returns the SMT term of the word.
+    pub fn smt(&self) -> String {
+        match self {
+            Self::Concrete(value) => literal(*value),
+            Self::Symbolic(name, _) => name.to_string(),
+        }
+    }
+
+    /// Returns the bytes of the word, most significant first.
+    pub fn bytes(&self) -> Vec<Byte> {
+        match self {
+            Self::Concrete(value) => {
+                value.to_be_bytes::<32>().into_iter().map(Byte::Concrete).collect()
+            }
+            Self::Symbolic(_, Some(bytes)) => bytes.to_vec(),
+            Self::Symbolic(name, None) => {
+                (0..32).map(|i| Byte::Symbolic(name.clone(), i)).collect()
+            }
+        }
+    }
+}
+
+/// Returns the SMT condition under which a word is not zero.
+pub fn nonzero(word: &Word) -> String {
+    format!("(not (= {} {}))", word.smt(), literal(U256::ZERO))
+}
+
+/// How a path through the test call ended.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The test call returned. The test failed if the word in the global failure slot is not
+    /// zero.
+    Returned(Word),
+    /// The test call reverted.
+    Reverted,
+    /// The path cannot be taken, e.g. after a `vm.assume` which cannot hold.
+    Infeasible,
+    /// The path was cut off at the loop bound or at the instruction limit.
+    Bounded,
+    /// The path reached an instruction or a cheatcode which cannot be executed symbolically.
+    Unsupported(String),
+}
+
+/// An instruction or a cheatcode which cannot be executed symbolically.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct Unsupported(String);
+
+/// An exceptional halt, which reverts the current call frame.
+#[derive(Debug, thiserror::Error)]
+#[error("exceptional halt")]
+struct Halt;
+
+fn unsupported(what: impl fmt::Display) -> eyre::Report {
+    Unsupported(what.to_string()).into()
+}
+
+/// The code of an account, with its valid jump destinations.
+struct Code {
+    bytes: Bytes,
+    jumpdests: Vec<bool>,
+}
+
+impl Code {
+    fn new(bytes: Bytes) -> Self {
+        let mut jumpdests = vec![false; bytes.len()];
+        let mut pc = 0;
+        while pc < bytes.len() {
+            let op = bytes[pc];
+            if op == opcode::JUMPDEST {
+                jumpdests[pc] = true;
+            }
+            pc += 1;
+            if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+                pc += (op - opcode::PUSH1 + 1) as usize;
+            }
+        }
+        Self { bytes, jumpdests }
+    }
+
+    /// Returns the counter of the jump destination `dest`.
+    fn jump_target(&self, dest: &Word) -> Result<usize> {
+        let dest = dest.as_concrete().ok_or_else(|| unsupported("jumps to symbolic targets"))?;
+        let dest = dest.saturating_to::<usize>();
+        if self.jumpdests.get(dest).copied().unwrap_or_default() {
+            Ok(dest)
+        } else {
+            Err(Halt.into())
+        }
+    }
+}
+
+/// A call frame of a path.
+#[derive(Clone)]
+struct Frame {
+    /// The account whose storage the code runs against.
+    address: Address,
+    /// The account whose code runs.
+    code_address: Address,
+    code: Arc<Code>,
+    pc: usize,
+    stack: Vec<Word>,
+    memory: Vec<Byte>,
+    calldata: Vec<Byte>,
+    return_data: Vec<Byte>,
+    caller: Address,
+    value: U256,
+    is_static: bool,
+    /// The memory range of the caller the output of the frame is copied to.
+    output: (usize, usize),
+    /// The storage and transient storage when the frame was entered, restored if it reverts.
+    checkpoint: (Storage, Storage),
+    /// The revert expected of the frame with `vm.expectRevert`, if any.
+    expected_revert: Option<ExpectedRevert>,
+}
+
+impl Frame {
+    fn pop(&mut self) -> Result<Word> {
+        Ok(self.stack.pop().ok_or(Halt)?)
+    }
+
+    fn push(&mut self, word: Word) -> Result<()> {
+        if self.stack.len() >= 1024 {
+            return Err(Halt.into())
+        }
+        self.stack.push(word);
+        Ok(())
+    }
+
+    /// Expands the memory to the range at `offset` of `size` bytes, and returns the range.
+    fn memory_range(&mut self, offset: &Word, size: &Word) -> Result<(usize, usize)> {
+        let size = size.as_concrete().ok_or_else(|| unsupported("symbolic memory sizes"))?;
+        if size.is_zero() {
+            return Ok((0, 0))
+        }
+        let offset = offset.as_concrete().ok_or_else(|| unsupported("symbolic memory offsets"))?;
+        let end = offset
+            .checked_add(size)
+            .filter(|end| *end <= U256::from(MAX_MEMORY))
+            .ok_or(Halt)?
+            .to::<usize>();
+        if end > self.memory.len() {
+            self.memory.resize(end.div_ceil(32) * 32, Byte::Concrete(0));
+        }
+        Ok((offset.to::<usize>(), size.to::<usize>()))
+    }
+
+    fn read(&mut self, offset: &Word, size: &Word) -> Result<Vec<Byte>> {
+        let (offset, size) = self.memory_range(offset, size)?;
+        Ok(self.memory[offset..offset + size].to_vec())
+    }
+
+    /// Writes to memory which has been expanded by [`Self::memory_range`].
+    fn write(&mut self, offset: usize, bytes: &[Byte]) {
+        self.memory[offset..offset + bytes.len()].clone_from_slice(bytes);
+    }
+
+    /// Resumes the frame after a call, with its success flag and output.
+    fn resume(&mut self, success: bool, output: Vec<Byte>, range: (usize, usize)) -> Result<()> {
+        let (offset, size) = range;
+        let len = size.min(output.len());
+        self.write(offset, &output[..len]);
+        self.return_data = output;
+        self.push(Word::Concrete(U256::from(success)))
+    }
+}
+
+/// A caller set with `vm.prank` or `vm.startPrank`.
+#[derive(Clone)]
+struct Prank {
+    caller: Address,
+    /// The depth of the frame whose calls are pranked.
+    depth: usize,
+    /// Whether all calls of the frame are pranked, or only the next one.
+    persistent: bool,
+}
+
+/// A revert expected with `vm.expectRevert`.
+#[derive(Clone)]
+struct ExpectedRevert {
+    /// The depth of the frame whose next call is expected to revert.
+    depth: usize,
+    /// The expected revert data, any revert data matches if `None`.
+    data: Option<Vec<u8>>,
+}
+
+impl ExpectedRevert {
+    /// Returns whether the revert data matches the expected data.
+    ///
+    /// Like the cheatcode, expected selectors match errors with arguments, and expected bytes
+    /// match the reason of `Error(string)` reverts.
+    fn matches(&self, output: &[Byte]) -> bool {
+        let Some(expected) = &self.data else { return true };
+        let Some(actual) = concrete_slice(output) else { return false };
+        actual == *expected ||
+            (expected.len() == 4 && actual.starts_with(expected)) ||
+            Revert::abi_decode(&actual, false)
+                .is_ok_and(|revert| revert.reason.as_bytes() == expected.as_slice())
+    }
+
+    /// Returns the success flag and the output of the call the revert is expected of, given the
+    /// ones it returned. A matching revert succeeds, anything else reverts the call.
+    fn outcome(&self, success: bool, output: Vec<Byte>) -> (bool, Vec<Byte>) {
+        let reason = if success {
+            "call did not revert as expected"
+        } else if self.matches(&output) {
+            return (true, vec![])
+        } else {
+            "call reverted with unexpected data"
+        };
+        (false, concrete_bytes(&Revert { reason: reason.to_string() }.abi_encode()))
+    }
+}
+
+/// A path through the test call.
+#[derive(Clone)]
+pub struct Path {
+    frames: Vec<Frame>,
+    /// The path condition, as SMT boolean terms.
+    pub constraints: Vec<String>,
+    origin: Address,
+    storage: Storage,
+    transient: Storage,
+    /// The balances set with `vm.deal`.
+    balances: HashMap<Address, Word>,
+    /// The block timestamp set with `vm.warp`.
+    timestamp: Option<Word>,
+    /// The block number set with `vm.roll`.
+    number: Option<Word>,
+    prank: Option<Prank>,
+    expected_revert: Option<ExpectedRevert>,
+    /// The number of times the path branched at each instruction, by code address and counter.
+    branches: HashMap<(Address, usize), u32>,
+    steps: usize,
+}
+
+/// Executes paths through a call symbolically, over the state of an [`Executor`].
+///
+/// Storage slots which are not written on a path read the state of the executor. Hashes of
+/// symbolic data are uninterpreted functions, assumed to be injective.
+pub struct Machine<'a> {
+    executor: &'a Executor,
+    solver: Solver,
+    loop_bound: u32,
+    codes: HashMap<Address, Arc<Code>>,
+    /// The lengths of the inputs symbolic data was hashed with.
+    hash_lengths: HashSet<usize>,
+    /// The concrete hashes computed so far, by input length.
+    hashes: HashMap<usize, Vec<(Vec<u8>, B256)>>,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(executor: &'a Executor, solver: Solver, loop_bound: u32) -> Self {
+        Self {
+            executor,
+            solver,
+            loop_bound,
+            codes: Default::default(),
+            hash_lengths: Default::default(),
+            hashes: Default::default(),
+        }
+    }
+
+    pub fn solver(&mut self) -> &mut Solver {
+        &mut self.solver
+    }
+
+    /// Returns the path of a call of `address` by `caller`, constrained by `constraints`.
+    pub fn start(
+        &mut self,
+        caller: Address,
+        address: Address,
+        calldata: Vec<Byte>,
+        constraints: Vec<String>,
+    ) -> Result<Path> {
+        let frame = Frame {
+            address,
+            code_address: address,
+            code: self.code(address)?,
+            pc: 0,
+            stack: vec![],
+            memory: vec![],
+            calldata,
+            return_data: vec![],
+            caller,
+            value: U256::ZERO,
+            is_static: false,
+            output: (0, 0),
+            checkpoint: Default::default(),
+            expected_revert: None,
+        };
+        Ok(Path {
+            frames: vec![frame],
+            constraints,
+            origin: caller,
+            storage: Default::default(),
+            transient: Default::default(),
+            balances: Default::default(),
+            timestamp: None,
+            number: None,
+            prank: None,
+            expected_revert: None,
+            branches: Default::default(),
+            steps: 0,
+        })
+    }
+
+    /// Runs `path` until it ends. The paths it forks into are pushed to `forks`.
+    pub fn run(&mut self, path: &mut Path, forks: &mut Vec<Path>) -> Result<Outcome> {
+        loop {
+            path.steps += 1;
+            if path.steps > MAX_STEPS {
+                return Ok(Outcome::Bounded)
+            }
+            let result = match self.step(path, forks) {
+                Err(err) if err.is::<Halt>() => self.exit(path, false, vec![]),
+                result => result,
+            };
+            match result {
+                Ok(Some(outcome)) => return Ok(outcome),
+                Ok(None) => {}
+                Err(err) => {
+                    return match err.downcast::<Unsupported>() {
+                        Ok(Unsupported(what)) => Ok(Outcome::Unsupported(what)),
+                        Err(err) => Err(err),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Executes the next instruction of `path`, returning the outcome of the path if it ended.
+    fn step(&mut self, path: &mut Path, forks: &mut Vec<Path>) -> Result<Option<Outcome>> {
+        let frame = path.frames.last_mut().expect("path has a frame");
+        let Some(&op) = frame.code.bytes.get(frame.pc) else {
+            return self.exit(path, true, vec![])
+        };
+        frame.pc += 1;
+        match op {
+            opcode::STOP => return self.exit(path, true, vec![]),
+            opcode::ADD |
+            opcode::MUL |
+            opcode::SUB |
+            opcode::DIV |
+            opcode::SDIV |
+            opcode::MOD |
+            opcode::SMOD |
+            opcode::LT |
+            opcode::GT |
+            opcode::SLT |
+            opcode::SGT |
+            opcode::EQ |
+            opcode::AND |
+            opcode::OR |
+            opcode::XOR |
+            opcode::SHL |
+            opcode::SHR |
+            opcode::SAR => {
+                let (a, b) = (frame.pop()?, frame.pop()?);
+                let word = self.binary(op, a, b)?;
+                frame.push(word)?;
+            }
+            opcode::ADDMOD | opcode::MULMOD => {
+                let (a, b, n) = (frame.pop()?, frame.pop()?, frame.pop()?);
+                let word = self.modular(op, a, b, n)?;
+                frame.push(word)?;
+            }
+            opcode::EXP => {
+                let (base, exponent) = (frame.pop()?, frame.pop()?);
+                let word = self.exp(base, exponent)?;
+                frame.push(word)?;
+            }
+            opcode::SIGNEXTEND => {
+                let (size, value) = (frame.pop()?, frame.pop()?);
+                let word = self.sign_extend(size, value)?;
+                frame.push(word)?;
+            }
+            opcode::ISZERO => {
+                let a = frame.pop()?;
+                let word = match a.as_concrete() {
+                    Some(a) => Word::Concrete(U256::from(a.is_zero())),
+                    None => self.define(bool_word(&format!("(not {})", nonzero(&a))))?,
+                };
+                frame.push(word)?;
+            }
+            opcode::NOT => {
+                let a = frame.pop()?;
+                let word = match a.as_concrete() {
+                    Some(a) => Word::Concrete(!a),
+                    None => self.define(format!("(bvnot {})", a.smt()))?,
+                };
+                frame.push(word)?;
+            }
+            opcode::BYTE => {
+                let (index, value) = (frame.pop()?, frame.pop()?);
+                let index = index
+                    .as_concrete()
+                    .ok_or_else(|| unsupported("BYTE with a symbolic index"))?
+                    .saturating_to::<usize>();
+                let mut bytes = vec![Byte::Concrete(0); 31];
+                bytes.push(value.bytes().get(index).cloned().unwrap_or(Byte::Concrete(0)));
+                let word = self.word_from_bytes(&bytes)?;
+                frame.push(word)?;
+            }
+            opcode::KECCAK256 => {
+                let (offset, size) = (frame.pop()?, frame.pop()?);
+                let bytes = frame.read(&offset, &size)?;
+                let word = self.keccak(&bytes)?;
+                frame.push(word)?;
+            }
+            opcode::ADDRESS => frame.push(address_word(frame.address))?,
+            opcode::BALANCE => {
+                let address = concrete_address(&frame.pop()?, "BALANCE")?;
+                frame.push(self.balance(&path.balances, address)?)?;
+            }
+            opcode::ORIGIN => frame.push(address_word(path.origin))?,
+            opcode::CALLER => frame.push(address_word(frame.caller))?,
+            opcode::CALLVALUE => frame.push(Word::Concrete(frame.value))?,
+            opcode::CALLDATALOAD => {
+                let offset = frame.pop()?;
+                let bytes = slice(&frame.calldata, &offset, 32)?;
+                let word = self.word_from_bytes(&bytes)?;
+                frame.push(word)?;
+            }
+            opcode::CALLDATASIZE => frame.push(Word::Concrete(U256::from(frame.calldata.len())))?,
+            opcode::CALLDATACOPY | opcode::CODECOPY | opcode::RETURNDATACOPY => {
+                let (dest, offset, size) = (frame.pop()?, frame.pop()?, frame.pop()?);
+                let (dest, size) = frame.memory_range(&dest, &size)?;
+                let bytes = match op {
+                    opcode::CALLDATACOPY => slice(&frame.calldata, &offset, size)?,
+                    opcode::CODECOPY => slice(&concrete_bytes(&frame.code.bytes), &offset, size)?,
+                    _ => {
+                        let offset = offset
+                            .as_concrete()
+                            .ok_or_else(|| unsupported("symbolic return data offsets"))?;
+                        let end = offset
+                            .checked_add(U256::from(size))
+                            .filter(|end| *end <= U256::from(frame.return_data.len()))
+                            .ok_or(Halt)?;
+                        frame.return_data[offset.to::<usize>()..end.to::<usize>()].to_vec()
+                    }
+                };
+                frame.write(dest, &bytes);
+            }
+            opcode::CODESIZE => frame.push(Word::Concrete(U256::from(frame.code.bytes.len())))?,
+            opcode::EXTCODESIZE => {
+                let address = concrete_address(&frame.pop()?, "EXTCODESIZE")?;
+                frame.push(Word::Concrete(U256::from(self.code(address)?.bytes.len())))?;
+            }
+            opcode::EXTCODECOPY => {
+                let address = concrete_address(&frame.pop()?, "EXTCODECOPY")?;
+                let (dest, offset, size) = (frame.pop()?, frame.pop()?, frame.pop()?);
+                let (dest, size) = frame.memory_range(&dest, &size)?;
+                let bytes = slice(&concrete_bytes(&self.code(address)?.bytes), &offset, size)?;
+                frame.write(dest, &bytes);
+            }
+            opcode::EXTCODEHASH => {
+                let address = concrete_address(&frame.pop()?, "EXTCODEHASH")?;
+                frame.push(Word::Concrete(self.code_hash(address)?))?;
+            }
+            opcode::RETURNDATASIZE => {
+                frame.push(Word::Concrete(U256::from(frame.return_data.len())))?
+            }
+            opcode::TIMESTAMP if path.timestamp.is_some() => {
+                frame.push(path.timestamp.clone().expect("timestamp is set"))?
+            }
+            opcode::NUMBER if path.number.is_some() => {
+                frame.push(path.number.clone().expect("number is set"))?
+            }
+            opcode::GASPRICE |
+            opcode::COINBASE |
+            opcode::TIMESTAMP |
+            opcode::NUMBER |
+            opcode::DIFFICULTY |
+            opcode::GASLIMIT |
+            opcode::CHAINID |
+            opcode::BASEFEE |
+            opcode::BLOBBASEFEE => frame.push(Word::Concrete(self.environment(op)))?,
+            opcode::SELFBALANCE => frame.push(self.balance(&path.balances, frame.address)?)?,
+            opcode::BLOBHASH => {
+                frame.pop()?;
+                frame.push(Word::Concrete(U256::ZERO))?;
+            }
+            opcode::POP => {
+                frame.pop()?;
+            }
+            opcode::MLOAD => {
+                let offset = frame.pop()?;
+                let bytes = frame.read(&offset, &Word::Concrete(U256::from(32)))?;
+                let word = self.word_from_bytes(&bytes)?;
+                frame.push(word)?;
+            }
+            opcode::MSTORE => {
+                let (offset, value) = (frame.pop()?, frame.pop()?);
+                let (offset, _) = frame.memory_range(&offset, &Word::Concrete(U256::from(32)))?;
+                frame.write(offset, &value.bytes());
+            }
+            opcode::MSTORE8 => {
+                let (offset, value) = (frame.pop()?, frame.pop()?);
+                let (offset, _) = frame.memory_range(&offset, &Word::Concrete(U256::from(1)))?;
+                frame.write(offset, &value.bytes()[31..]);
+            }
+            opcode::SLOAD | opcode::TLOAD => {
+                let slot = frame.pop()?;
+                let transient = op == opcode::TLOAD;
+                let storage = if transient { &path.transient } else { &path.storage };
+                let word = self.sload(storage, frame.address, &slot, transient)?;
+                frame.push(word)?;
+            }
+            opcode::SSTORE | opcode::TSTORE => {
+                if frame.is_static {
+                    return Err(Halt.into())
+                }
+                let (slot, value) = (frame.pop()?, frame.pop()?);
+                let storage =
+                    if op == opcode::TSTORE { &mut path.transient } else { &mut path.storage };
+                store(storage, frame.address, slot, value);
+            }
+            opcode::JUMP => {
+                let dest = frame.pop()?;
+                frame.pc = frame.code.jump_target(&dest)?;
+            }
+            opcode::JUMPI => {
+                let (dest, condition) = (frame.pop()?, frame.pop()?);
+                if let Some(condition) = condition.as_concrete() {
+                    if !condition.is_zero() {
+                        frame.pc = frame.code.jump_target(&dest)?;
+                    }
+                    return Ok(None)
+                }
+                let jump = nonzero(&condition);
+                let fall = format!("(not {jump})");
+                let can_jump = self.feasible(&path.constraints, &jump)?;
+                let can_fall = self.feasible(&path.constraints, &fall)?;
+                match (can_jump, can_fall) {
+                    (true, true) => {
+                        let count =
+                            path.branches.entry((frame.code_address, frame.pc - 1)).or_default();
+                        *count += 1;
+                        if *count > self.loop_bound {
+                            return Ok(Some(Outcome::Bounded))
+                        }
+                        let target = frame.code.jump_target(&dest)?;
+                        let mut fork = path.clone();
+                        fork.constraints.push(fall);
+                        forks.push(fork);
+                        path.constraints.push(jump);
+                        path.frames.last_mut().expect("path has a frame").pc = target;
+                    }
+                    (true, false) => frame.pc = frame.code.jump_target(&dest)?,
+                    (false, true) => {}
+                    (false, false) => return Ok(Some(Outcome::Infeasible)),
+                }
+            }
+            opcode::PC => frame.push(Word::Concrete(U256::from(frame.pc - 1)))?,
+            opcode::MSIZE => frame.push(Word::Concrete(U256::from(frame.memory.len())))?,
+            opcode::GAS => frame.push(Word::Concrete(U256::from(self.executor.gas_limit())))?,
+            opcode::JUMPDEST => {}
+            opcode::MCOPY => {
+                let (dest, src, size) = (frame.pop()?, frame.pop()?, frame.pop()?);
+                let bytes = frame.read(&src, &size)?;
+                let (dest, _) = frame.memory_range(&dest, &size)?;
+                frame.write(dest, &bytes);
+            }
+            opcode::PUSH0 => frame.push(Word::Concrete(U256::ZERO))?,
+            opcode::PUSH1..=opcode::PUSH32 => {
+                let size = (op - opcode::PUSH1 + 1) as usize;
+                let code = &frame.code.bytes;
+                let mut bytes =
+                    code[frame.pc.min(code.len())..(frame.pc + size).min(code.len())].to_vec();
+                bytes.resize(size, 0);
+                frame.pc += size;
+                frame.push(Word::Concrete(U256::from_be_slice(&bytes)))?;
+            }
+            opcode::DUP1..=opcode::DUP16 => {
+                let depth = (op - opcode::DUP1 + 1) as usize;
+                let index = frame.stack.len().checked_sub(depth).ok_or(Halt)?;
+                frame.push(frame.stack[index].clone())?;
+            }
+            opcode::SWAP1..=opcode::SWAP16 => {
+                let depth = (op - opcode::SWAP1 + 1) as usize;
+                let top = frame.stack.len().checked_sub(1).ok_or(Halt)?;
+                let index = top.checked_sub(depth).ok_or(Halt)?;
+                frame.stack.swap(top, index);
+            }
+            opcode::LOG0..=opcode::LOG4 => {
+                if frame.is_static {
+                    return Err(Halt.into())
+                }
+                let (offset, size) = (frame.pop()?, frame.pop()?);
+                for _ in opcode::LOG0..op {
+                    frame.pop()?;
+                }
+                frame.memory_range(&offset, &size)?;
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                return self.call(path, forks, op)
+            }
+            opcode::RETURN | opcode::REVERT => {
+                let (offset, size) = (frame.pop()?, frame.pop()?);
+                let output = frame.read(&offset, &size)?;
+                return self.exit(path, op == opcode::RETURN, output)
+            }
+            opcode::INVALID => return Err(Halt.into()),
+            _ => {
+                return Err(match OpCode::new(op) {
+                    Some(op) => unsupported(format!("the {op} instruction")),
+                    None => Halt.into(),
+                })
+            }
+        }
+        Ok(None)
+    }
+
+    /// Executes a call instruction.
+    fn call(&mut self, path: &mut Path, forks: &mut Vec<Path>, op: u8) -> Result<Option<Outcome>> {
+        let depth = path.frames.len();
+        let frame = path.frames.last_mut().expect("path has a frame");
+        let _gas = frame.pop()?;
+        let target = frame.pop()?;
+        let value = if matches!(op, opcode::CALL | opcode::CALLCODE) {
+            frame.pop()?
+        } else {
+            Word::Concrete(U256::ZERO)
+        };
+        let (args_offset, args_size) = (frame.pop()?, frame.pop()?);
+        let (ret_offset, ret_size) = (frame.pop()?, frame.pop()?);
+        let target = concrete_address(&target, "calls")?;
+        if op == opcode::CALLCODE {
+            return Err(unsupported("the CALLCODE instruction"))
+        }
+        match value.as_concrete() {
+            Some(value) if value.is_zero() => {}
+            Some(_) if frame.is_static => return Err(Halt.into()),
+            _ => return Err(unsupported("calls transferring value")),
+        }
+        let input = frame.read(&args_offset, &args_size)?;
+        let output = frame.memory_range(&ret_offset, &ret_size)?;
+
+        if target == CHEATCODE_ADDRESS {
+            return self.cheatcode(path, forks, &input, output)
+        }
+        if target == HARDHAT_CONSOLE_ADDRESS {
+            frame.resume(true, vec![], output)?;
+            return Ok(None)
+        }
+        if target != Address::ZERO && target <= Address::with_last_byte(0x11) {
+            // The identity precompile.
+            if target == Address::with_last_byte(0x04) {
+                frame.resume(true, input, output)?;
+                return Ok(None)
+            }
+            return Err(unsupported(format!("calls to the precompile at {target}")))
+        }
+        let delegate = op == opcode::DELEGATECALL;
+        let mut caller = if delegate { frame.caller } else { frame.address };
+        if let Some(prank) = path.prank.clone().filter(|prank| prank.depth == depth) {
+            if delegate {
+                return Err(unsupported("pranked delegate calls"))
+            }
+            caller = prank.caller;
+            if !prank.persistent {
+                path.prank = None;
+            }
+        }
+        let expected_revert =
+            path.expected_revert.take_if(|expected_revert| expected_revert.depth == depth);
+
+        let code = self.code(target)?;
+        if code.bytes.is_empty() || depth >= MAX_DEPTH {
+            let (success, returned) = match &expected_revert {
+                Some(expected_revert) => expected_revert.outcome(depth < MAX_DEPTH, vec![]),
+                None => (depth < MAX_DEPTH, vec![]),
+            };
+            frame.resume(success, returned, output)?;
+            return Ok(None)
+        }
+
+        let callee = Frame {
+            address: if delegate { frame.address } else { target },
+            code_address: target,
+            code,
+            pc: 0,
+            stack: vec![],
+            memory: vec![],
+            calldata: input,
+            return_data: vec![],
+            caller,
+            value: if delegate { frame.value } else { U256::ZERO },
+            is_static: frame.is_static || op == opcode::STATICCALL,
+            output,
+            checkpoint: (path.storage.clone(), path.transient.clone()),
+            expected_revert,
+        };
+        path.frames.push(callee);
+        Ok(None)
+    }
+
+    /// Executes a call of the cheatcode handler.
+    fn cheatcode(
+        &mut self,
+        path: &mut Path,
+        forks: &mut Vec<Path>,
+        input: &[Byte],
+        output: (usize, usize),
+    ) -> Result<Option<Outcome>> {
+        let selector = input
+            .get(..4)
+            .and_then(|selector| concrete_slice(selector))
+            .ok_or_else(|| unsupported("cheatcode calls with a symbolic selector"))?;
+        let cheatcode = Cheatcode::decode(&selector).ok_or_else(|| {
+            match Vm::CHEATCODES
+                .iter()
+                .find(|cheatcode| cheatcode.func.selector_bytes[..] == selector[..])
+            {
+                Some(cheatcode) => {
+                    unsupported(format!("the `{}` cheatcode", cheatcode.func.signature))
+                }
+                None => {
+                    unsupported(format!("the cheatcode with selector 0x{}", hex::encode(&selector)))
+                }
+            }
+        })?;
+        let mut args = Vec::with_capacity(3);
+        for i in 0..cheatcode.arity() {
+            let bytes = slice(input, &Word::Concrete(U256::from(4 + 32 * i)), 32)?;
+            args.push(self.word_from_bytes(&bytes)?);
+        }
+
+        let mut returned = vec![];
+        match cheatcode {
+            Cheatcode::Ignore => {}
+            Cheatcode::Assume => match args[0].as_concrete() {
+                Some(condition) if condition.is_zero() => return Ok(Some(Outcome::Infeasible)),
+                Some(_) => {}
+                None => {
+                    let condition = nonzero(&args[0]);
+                    if !self.feasible(&path.constraints, &condition)? {
+                        return Ok(Some(Outcome::Infeasible))
+                    }
+                    path.constraints.push(condition);
+                }
+            },
+            Cheatcode::Assert(comparison) => {
+                let (a, b) = (&args[0], args.get(1).unwrap_or(&args[0]));
+                let failure = comparison.failure(a, b);
+                let (can_fail, can_pass) = match a.as_concrete().zip(b.as_concrete()) {
+                    Some((a, b)) => {
+                        let holds = comparison.holds(a, b);
+                        (!holds, holds)
+                    }
+                    None => (
+                        self.feasible(&path.constraints, &failure)?,
+                        self.feasible(&path.constraints, &format!("(not {failure})"))?,
+                    ),
+                };
+                match (can_fail, can_pass) {
+                    (true, true) => {
+                        let mut fork = path.clone();
+                        fork.constraints.push(failure.clone());
+                        fork.frames.last_mut().expect("path has a frame").resume(
+                            false,
+                            vec![],
+                            output,
+                        )?;
+                        forks.push(fork);
+                        path.constraints.push(format!("(not {failure})"));
+                    }
+                    (true, false) => {
+                        let frame = path.frames.last_mut().expect("path has a frame");
+                        frame.resume(false, vec![], output)?;
+                        return Ok(None)
+                    }
+                    (false, true) => {}
+                    (false, false) => return Ok(Some(Outcome::Infeasible)),
+                }
+            }
+            Cheatcode::Store => {
+                let target = concrete_address(&args[0], "vm.store")?;
+                store(&mut path.storage, target, args[1].clone(), args[2].clone());
+            }
+            Cheatcode::Load => {
+                let target = concrete_address(&args[0], "vm.load")?;
+                returned = self.sload(&path.storage, target, &args[1], false)?.bytes();
+            }
+            Cheatcode::Deal => {
+                let target = concrete_address(&args[0], "vm.deal")?;
+                path.balances.insert(target, args[1].clone());
+            }
+            Cheatcode::Warp => path.timestamp = Some(args[0].clone()),
+            Cheatcode::Roll => path.number = Some(args[0].clone()),
+            Cheatcode::Prank { persistent } => {
+                let caller = concrete_address(&args[0], "vm.prank")?;
+                path.prank = Some(Prank { caller, depth: path.frames.len(), persistent });
+            }
+            Cheatcode::StopPrank => path.prank = None,
+            Cheatcode::ExpectRevert { data } => {
+                let data = match data {
+                    None => None,
+                    Some(ty) => {
+                        let input = concrete_slice(input)
+                            .ok_or_else(|| unsupported("vm.expectRevert with symbolic data"))?;
+                        match ty.abi_decode(&input[4..]) {
+                            Ok(DynSolValue::FixedBytes(word, size)) => Some(word[..size].to_vec()),
+                            Ok(DynSolValue::Bytes(data)) => Some(data),
+                            _ => return Err(Halt.into()),
+                        }
+                    }
+                };
+                path.expected_revert = Some(ExpectedRevert { depth: path.frames.len(), data });
+            }
+        }
+        path.frames.last_mut().expect("path has a frame").resume(true, returned, output)?;
+        Ok(None)
+    }
+
+    /// Exits the current frame of `path`, returning the outcome of the path if it was the last.
+    fn exit(
+        &mut self,
+        path: &mut Path,
+        success: bool,
+        output: Vec<Byte>,
+    ) -> Result<Option<Outcome>> {
+        let frame = path.frames.pop().expect("path has a frame");
+        let reverted = !success;
+        let (success, output) = match &frame.expected_revert {
+            Some(expected_revert) => expected_revert.outcome(success, output),
+            None => (success, output),
+        };
+        if reverted || !success {
+            (path.storage, path.transient) = frame.checkpoint;
+        }
+        match path.frames.last_mut() {
+            Some(caller) => {
+                caller.resume(success, output, frame.output)?;
+                Ok(None)
+            }
+            None if success => {
+                let slot = Word::Concrete(GLOBAL_FAIL_SLOT);
+                let failed = self.sload(&path.storage, CHEATCODE_ADDRESS, &slot, false)?;
+                Ok(Some(Outcome::Returned(failed)))
+            }
+            None => Ok(Some(Outcome::Reverted)),
+        }
+    }
+
+    /// Returns whether `condition` may hold on a path constrained by `constraints`.
+    fn feasible(&mut self, constraints: &[String], condition: &str) -> Result<bool> {
+        Ok(!matches!(self.solver.check(constraints, Some(condition), &[])?, Check::Unsat))
+    }
+
+    /// Defines a word term.
+    fn define(&mut self, expr: String) -> Result<Word> {
+        Ok(Word::Symbolic(self.solver.define(WORD, &expr)?, None))
+    }
+
+    /// Returns the word of 32 bytes, most significant first.
+    fn word_from_bytes(&mut self, bytes: &[Byte]) -> Result<Word> {
+        if let Some(bytes) = concrete_slice(bytes) {
+            return Ok(Word::Concrete(U256::from_be_slice(&bytes)))
+        }
+        if let Byte::Symbolic(name, 0) = &bytes[0] {
+            if bytes
+                .iter()
+                .enumerate()
+                .all(|(i, byte)| *byte == Byte::Symbolic(name.clone(), i as u8))
+            {
+                return Ok(Word::symbol(name))
+            }
+        }
+        let name = self.solver.define(WORD, &concat(bytes))?;
+        Ok(Word::Symbolic(name, Some(bytes.into())))
+    }
+
+    fn binary(&mut self, op: u8, a: Word, b: Word) -> Result<Word> {
+        if let (Some(a), Some(b)) = (a.as_concrete(), b.as_concrete()) {
+            return Ok(Word::Concrete(concrete_binary(op, a, b)))
+        }
+        // Shifts and masks by whole bytes are applied to the bytes of the word, which keeps
+        // concrete bytes, e.g. of the selector in the calldata, concrete.
+        match op {
+            opcode::SHL | opcode::SHR => {
+                if let Some(bytes) = a.as_concrete().and_then(|s| shift(&b, s, op == opcode::SHL)) {
+                    return self.word_from_bytes(&bytes)
+                }
+            }
+            opcode::AND => {
+                if let Some(bytes) = mask(&a, &b).or_else(|| mask(&b, &a)) {
+                    return self.word_from_bytes(&bytes)
+                }
+            }
+            _ => {}
+        }
+        let (a, b) = (a.smt(), b.smt());
+        let zero = literal(U256::ZERO);
+        let expr = match op {
+            opcode::ADD => format!("(bvadd {a} {b})"),
+            opcode::MUL => format!("(bvmul {a} {b})"),
+            opcode::SUB => format!("(bvsub {a} {b})"),
+            opcode::DIV => format!("(ite (= {b} {zero}) {zero} (bvudiv {a} {b}))"),
+            opcode::SDIV => format!("(ite (= {b} {zero}) {zero} (bvsdiv {a} {b}))"),
+            opcode::MOD => format!("(ite (= {b} {zero}) {zero} (bvurem {a} {b}))"),
+            opcode::SMOD => format!("(ite (= {b} {zero}) {zero} (bvsrem {a} {b}))"),
+            opcode::LT => bool_word(&format!("(bvult {a} {b})")),
+            opcode::GT => bool_word(&format!("(bvugt {a} {b})")),
+            opcode::SLT => bool_word(&format!("(bvslt {a} {b})")),
+            opcode::SGT => bool_word(&format!("(bvsgt {a} {b})")),
+            opcode::EQ => bool_word(&format!("(= {a} {b})")),
+            opcode::AND => format!("(bvand {a} {b})"),
+            opcode::OR => format!("(bvor {a} {b})"),
+            opcode::XOR => format!("(bvxor {a} {b})"),
+            opcode::SHL => format!("(bvshl {b} {a})"),
+            opcode::SHR => format!("(bvlshr {b} {a})"),
+            opcode::SAR => format!("(bvashr {b} {a})"),
+            _ => unreachable!("not a binary instruction: {op}"),
+        };
+        self.define(expr)
+    }
+
+    fn modular(&mut self, op: u8, a: Word, b: Word, n: Word) -> Result<Word> {
+        if let (Some(a), Some(b), Some(n)) = (a.as_concrete(), b.as_concrete(), n.as_concrete()) {
+            let value = if op == opcode::ADDMOD { a.add_mod(b, n) } else { a.mul_mod(b, n) };
+            return Ok(Word::Concrete(value))
+        }
+        // Computed on 512 bits, so that the intermediate result does not overflow.
+        let wide = |word: &Word| format!("((_ zero_extend 256) {})", word.smt());
+        let function = if op == opcode::ADDMOD { "bvadd" } else { "bvmul" };
+        let zero = literal(U256::ZERO);
+        self.define(format!(
+            "(ite (= {} {zero}) {zero} ((_ extract 255 0) (bvurem ({function} {} {}) {})))",
+            n.smt(),
+            wide(&a),
+            wide(&b),
+            wide(&n)
+        ))
+    }
+
+    fn exp(&mut self, base: Word, exponent: Word) -> Result<Word> {
+        match (base.as_concrete(), exponent.as_concrete()) {
+            (Some(base), Some(exponent)) => Ok(Word::Concrete(base.wrapping_pow(exponent))),
+            (_, Some(exponent)) => {
+                let mut result = Word::Concrete(U256::from(1));
+                for bit in (0..exponent.bit_len()).rev() {
+                    result = self.binary(opcode::MUL, result.clone(), result)?;
+                    if exponent.bit(bit) {
+                        result = self.binary(opcode::MUL, result, base.clone())?;
+                    }
+                }
+                Ok(result)
+            }
+            (Some(base), None) if base == U256::from(2) => {
+                self.define(format!("(bvshl {} {})", literal(U256::from(1)), exponent.smt()))
+            }
+            _ => Err(unsupported("EXP with a symbolic exponent")),
+        }
+    }
+
+    fn sign_extend(&mut self, size: Word, value: Word) -> Result<Word> {
+        let size =
+            size.as_concrete().ok_or_else(|| unsupported("SIGNEXTEND with a symbolic size"))?;
+        if size >= U256::from(31) {
+            return Ok(value)
+        }
+        let bits = (size.to::<usize>() + 1) * 8;
+        match value.as_concrete() {
+            Some(value) => {
+                let mask = (U256::from(1) << bits) - U256::from(1);
+                Ok(Word::Concrete(if value.bit(bits - 1) { value | !mask } else { value & mask }))
+            }
+            None => self.define(format!(
+                "((_ sign_extend {}) ((_ extract {} 0) {}))",
+                256 - bits,
+                bits - 1,
+                value.smt()
+            )),
+        }
+    }
+
+    fn keccak(&mut self, bytes: &[Byte]) -> Result<Word> {
+        let len = bytes.len();
+        let function = format!("keccak256_{len}");
+        if let Some(input) = concrete_slice(bytes) {
+            let hash = keccak256(&input);
+            if self.hash_lengths.contains(&len) {
+                self.solver.assert(&hash_axiom(&function, &input, hash))?;
+            }
+            self.hashes.entry(len).or_default().push((input, hash));
+            return Ok(Word::Concrete(U256::from_be_bytes(hash.0)))
+        }
+
+        let input = concat(bytes);
+        if self.hash_lengths.insert(len) {
+            let sort = format!("(_ BitVec {})", len * 8);
+            self.solver.declare_fun(&function, &sort, WORD)?;
+            self.solver.declare_fun(&format!("{function}_inverse"), WORD, &sort)?;
+            for (input, hash) in self.hashes.get(&len).into_iter().flatten() {
+                self.solver.assert(&hash_axiom(&function, input, *hash))?;
+            }
+        }
+        let word = self.define(format!("({function} {input})"))?;
+        self.solver.assert(&format!("(= ({function}_inverse {}) {input})", word.smt()))?;
+        self.solver.assert(&format!("(bvuge {} {})", word.smt(), literal(MIN_HASH)))?;
+        Ok(word)
+    }
+
+    fn sload(
+        &mut self,
+        storage: &Storage,
+        address: Address,
+        slot: &Word,
+        transient: bool,
+    ) -> Result<Word> {
+        // The writes which may alias the slot, latest first.
+        let mut writes = vec![];
+        let mut base = None;
+        for (key, value) in storage.get(&address).into_iter().flatten().rev() {
+            if key == slot {
+                base = Some(value.clone());
+                break
+            }
+            if key.as_concrete().is_none() || slot.as_concrete().is_none() {
+                writes.push((key, value));
+            }
+        }
+        let base = match base {
+            Some(base) => base,
+            None if transient => Word::Concrete(U256::ZERO),
+            None => match slot.as_concrete() {
+                Some(slot) => Word::Concrete(self.executor.backend().storage_ref(address, slot)?),
+                None => self.initial_storage(address, slot)?,
+            },
+        };
+        if writes.is_empty() {
+            return Ok(base)
+        }
+        let expr = writes.iter().rev().fold(base.smt(), |expr, (key, value)| {
+            format!("(ite (= {} {}) {} {expr})", slot.smt(), key.smt(), value.smt())
+        });
+        self.define(expr)
+    }
+
+    /// Returns the value of a symbolic slot in the storage of the executor.
+    fn initial_storage(&mut self, address: Address, slot: &Word) -> Result<Word> {
+        let name = format!("storage_{}", hex::encode(address));
+        let sort = format!("(Array {WORD} {WORD})");
+        let backend = self.executor.backend();
+        if backend.active_fork_db().is_some() {
+            // The storage of forked accounts is only partially known.
+            self.solver.declare(&name, &sort)?;
+        } else {
+            let mut expr = format!("((as const {sort}) {})", literal(U256::ZERO));
+            if let Some(account) = backend.mem_db().accounts.get(&address) {
+                for (key, value) in &account.storage {
+                    expr = format!("(store {expr} {} {})", literal(*key), literal(*value));
+                }
+            }
+            self.solver.define_named(&name, &sort, &expr)?;
+        }
+        self.define(format!("(select {name} {})", slot.smt()))
+    }
+
+    fn code(&mut self, address: Address) -> Result<Arc<Code>> {
+        if let Some(code) = self.codes.get(&address) {
+            return Ok(code.clone())
+        }
+        let backend = self.executor.backend();
+        let bytes = match backend.basic_ref(address)? {
+            Some(info) => match info.code {
+                Some(code) => code.original_bytes(),
+                None if info.code_hash == KECCAK_EMPTY => Bytes::new(),
+                None => backend.code_by_hash_ref(info.code_hash)?.original_bytes(),
+            },
+            None => Bytes::new(),
+        };
+        let code = Arc::new(Code::new(bytes));
+        self.codes.insert(address, code.clone());
+        Ok(code)
+    }
+
+    fn code_hash(&mut self, address: Address) -> Result<U256> {
+        Ok(match self.executor.backend().basic_ref(address)? {
+            Some(info) if !info.is_empty() => U256::from_be_bytes(info.code_hash.0),
+            _ => U256::ZERO,
+        })
+    }
+
+    /// Returns the balance of `address`, set with `vm.deal` on the path or in the executor state.
+    fn balance(&self, balances: &HashMap<Address, Word>, address: Address) -> Result<Word> {
+        if let Some(balance) = balances.get(&address) {
+            return Ok(balance.clone())
+        }
+        let balance = self
+            .executor
+            .backend()
+            .basic_ref(address)?
+            .map(|info| info.balance)
+            .unwrap_or_default();
+        Ok(Word::Concrete(balance))
+    }
+
+    fn environment(&self, op: u8) -> U256 {
+        let env = self.executor.env();
+        match op {
+            opcode::GASPRICE => env.tx.gas_price,
+            opcode::COINBASE => U256::from_be_slice(env.block.coinbase.as_slice()),
+            opcode::TIMESTAMP => env.block.timestamp,
+            opcode::NUMBER => env.block.number,
+            opcode::DIFFICULTY => env
+                .block
+                .prevrandao
+                .map_or(env.block.difficulty, |prevrandao| U256::from_be_bytes(prevrandao.0)),
+            opcode::GASLIMIT => env.block.gas_limit,
+            opcode::CHAINID => U256::from(env.cfg.chain_id),
+            opcode::BASEFEE => env.block.basefee,
+            opcode::BLOBBASEFEE => U256::from(env.block.get_blob_gasprice().unwrap_or_default()),
+            _ => unreachable!("not an environment instruction: {op}"),
+        }
+    }
+}
+
+/// The cheatcodes which can be executed symbolically.
+///
+/// Counterexamples are replayed with the cheatcodes of the executor, so the symbolic semantics
+/// only need to match them closely enough to find the failing paths.
+#[derive(Clone)]
+enum Cheatcode {
+    Assume,
+    Assert(Comparison),
+    Store,
+    Load,
+    Deal,
+    Warp,
+    Roll,
+    /// `prank` or, if persistent, `startPrank`.
+    Prank {
+        persistent: bool,
+    },
+    StopPrank,
+    /// `expectRevert`, with the type of the expected revert data if any.
+    ExpectRevert {
+        data: Option<DynSolType>,
+    },
+    /// Cheatcodes without effect on the outcome of the test, e.g. `label`.
+    Ignore,
+}
+
+impl Cheatcode {
+    fn decode(selector: &[u8]) -> Option<Self> {
+        use Comparison::*;
+
+        const ASSERTIONS: &[(&str, &[&str], Comparison)] = &[
+            ("assertTrue", &["bool"], True),
+            ("assertFalse", &["bool"], False),
+            ("assertEq", &["bool", "uint256", "int256", "address", "bytes32"], Eq),
+            ("assertNotEq", &["bool", "uint256", "int256", "address", "bytes32"], NotEq),
+            ("assertGt", &["uint256"], Gt),
+            ("assertGt", &["int256"], SignedGt),
+            ("assertGe", &["uint256"], Ge),
+            ("assertGe", &["int256"], SignedGe),
+            ("assertLt", &["uint256"], Lt),
+            ("assertLt", &["int256"], SignedLt),
+            ("assertLe", &["uint256"], Le),
+            ("assertLe", &["int256"], SignedLe),
+        ];
+
+        let matches = |signature: &str| keccak256(signature)[..4] == *selector;
+        if matches("assume(bool)") {
+            return Some(Self::Assume)
+        }
+        for (name, types, comparison) in ASSERTIONS {
+            for ty in *types {
+                let params = if matches!(comparison, True | False) {
+                    ty.to_string()
+                } else {
+                    format!("{ty},{ty}")
+                };
+                if matches(&format!("{name}({params})")) ||
+                    matches(&format!("{name}({params},string)"))
+                {
+                    return Some(Self::Assert(*comparison))
+                }
+            }
+        }
+        [
+            ("store(address,bytes32,bytes32)", Self::Store),
+            ("load(address,bytes32)", Self::Load),
+            ("deal(address,uint256)", Self::Deal),
+            ("warp(uint256)", Self::Warp),
+            ("roll(uint256)", Self::Roll),
+            ("prank(address)", Self::Prank { persistent: false }),
+            ("startPrank(address)", Self::Prank { persistent: true }),
+            ("stopPrank()", Self::StopPrank),
+            ("expectRevert()", Self::ExpectRevert { data: None }),
+            ("expectRevert(bytes4)", Self::ExpectRevert { data: Some(DynSolType::FixedBytes(4)) }),
+            ("expectRevert(bytes)", Self::ExpectRevert { data: Some(DynSolType::Bytes) }),
+            ("label(address,string)", Self::Ignore),
+            ("pauseGasMetering()", Self::Ignore),
+            ("resumeGasMetering()", Self::Ignore),
+        ]
+        .into_iter()
+        .find_map(|(signature, cheatcode)| matches(signature).then_some(cheatcode))
+    }
+
+    /// Returns the number of word arguments the cheatcode reads.
+    fn arity(&self) -> usize {
+        match self {
+            Self::Ignore | Self::StopPrank | Self::ExpectRevert { .. } => 0,
+            Self::Assume |
+            Self::Assert(Comparison::True | Comparison::False) |
+            Self::Warp |
+            Self::Roll |
+            Self::Prank { .. } => 1,
+            Self::Assert(_) | Self::Load | Self::Deal => 2,
+            Self::Store => 3,
+        }
+    }
+}
+
+/// The comparison checked by an assertion cheatcode.
+#[derive(Clone, Copy)]
+enum Comparison {
+    True,
+    False,
+    Eq,
+    NotEq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    SignedGt,
+    SignedGe,
+    SignedLt,
+    SignedLe,
+}
+
+impl Comparison {
+    /// Returns whether the assertion holds on concrete arguments.
+    fn holds(self, a: U256, b: U256) -> bool {
+        let (sa, sb) = (I256::from_raw(a), I256::from_raw(b));
+        match self {
+            Self::True => !a.is_zero(),
+            Self::False => a.is_zero(),
+            Self::Eq => a == b,
+            Self::NotEq => a != b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::SignedGt => sa > sb,
+            Self::SignedGe => sa >= sb,
+            Self::SignedLt => sa < sb,
+            Self::SignedLe => sa <= sb,
+        }
+    }
+
+    /// Returns the SMT condition under which the assertion fails.
+    fn failure(self, a: &Word, b: &Word) -> String {
+        let (a, b) = (a.smt(), b.smt());
+        let zero = literal(U256::ZERO);
+        let holds = match self {
+            Self::True => format!("(not (= {a} {zero}))"),
+            Self::False => format!("(= {a} {zero})"),
+            Self::Eq => format!("(= {a} {b})"),
+            Self::NotEq => format!("(not (= {a} {b}))"),
+            Self::Gt => format!("(bvugt {a} {b})"),
+            Self::Ge => format!("(bvuge {a} {b})"),
+            Self::Lt => format!("(bvult {a} {b})"),
+            Self::Le => format!("(bvule {a} {b})"),
+            Self::SignedGt => format!("(bvsgt {a} {b})"),
+            Self::SignedGe => format!("(bvsge {a} {b})"),
+            Self::SignedLt => format!("(bvslt {a} {b})"),
+            Self::SignedLe => format!("(bvsle {a} {b})"),
+        };
+        format!("(not {holds})")
+    }
+}
+
+fn store(storage: &mut Storage, address: Address, slot: Word, value: Word) {
+    let writes = storage.entry(address).or_default();
+    writes.retain(|(key, _)| *key != slot);
+    writes.push((slot, value));
+}
+
+fn concrete_binary(op: u8, a: U256, b: U256) -> U256 {
+    let (sa, sb) = (I256::from_raw(a), I256::from_raw(b));
+    match op {
+        opcode::ADD => a.wrapping_add(b),
+        opcode::MUL => a.wrapping_mul(b),
+        opcode::SUB => a.wrapping_sub(b),
+        opcode::DIV => a.checked_div(b).unwrap_or_default(),
+        opcode::SDIV if b.is_zero() => U256::ZERO,
+        opcode::SDIV => sa.wrapping_div(sb).into_raw(),
+        opcode::MOD => a.checked_rem(b).unwrap_or_default(),
+        opcode::SMOD if b.is_zero() => U256::ZERO,
+        opcode::SMOD => sa.wrapping_rem(sb).into_raw(),
+        opcode::LT => U256::from(a < b),
+        opcode::GT => U256::from(a > b),
+        opcode::SLT => U256::from(sa < sb),
+        opcode::SGT => U256::from(sa > sb),
+        opcode::EQ => U256::from(a == b),
+        opcode::AND => a & b,
+        opcode::OR => a | b,
+        opcode::XOR => a ^ b,
+        opcode::SHL => b.checked_shl(a.saturating_to()).unwrap_or_default(),
+        opcode::SHR => b.checked_shr(a.saturating_to()).unwrap_or_default(),
+        opcode::SAR if a < U256::from(256) => sb.asr(a.to()).into_raw(),
+        opcode::SAR if sb.is_negative() => U256::MAX,
+        opcode::SAR => U256::ZERO,
+        _ => unreachable!("not a binary instruction: {op}"),
+    }
+}
+
+/// Returns the bytes of `value` shifted by `shift` bits, if it is a multiple of 8.
+fn shift(value: &Word, shift: U256, left: bool) -> Option<Vec<Byte>> {
+    if shift >= U256::from(256) {
+        return Some(vec![Byte::Concrete(0); 32])
+    }
+    let shift = shift.to::<usize>();
+    if shift % 8 != 0 {
+        return None
+    }
+    let (bytes, zeros) = (value.bytes(), vec![Byte::Concrete(0); shift / 8]);
+    Some(if left {
+        bytes[shift / 8..].iter().cloned().chain(zeros).collect()
+    } else {
+        zeros.into_iter().chain(bytes[..32 - shift / 8].iter().cloned()).collect()
+    })
+}
+
+/// Returns the bytes of `value` masked by `mask`, if the mask is concrete and made of whole bytes.
+fn mask(mask: &Word, value: &Word) -> Option<Vec<Byte>> {
+    let mask = mask.as_concrete()?.to_be_bytes::<32>();
+    mask.iter()
+        .zip(value.bytes())
+        .map(|(mask, byte)| match mask {
+            0x00 => Some(Byte::Concrete(0)),
+            0xff => Some(byte),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the SMT term of the concatenation of bytes, most significant first.
+fn concat(bytes: &[Byte]) -> String {
+    let mut parts = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let mut j = i + 1;
+        match &bytes[i] {
+            Byte::Concrete(_) => {
+                while matches!(bytes.get(j), Some(Byte::Concrete(_))) {
+                    j += 1;
+                }
+                let bytes = concrete_slice(&bytes[i..j]).expect("bytes are concrete");
+                parts.push(format!("#x{}", hex::encode(bytes)));
+            }
+            Byte::Symbolic(name, index) => {
+                while bytes.get(j) == Some(&Byte::Symbolic(name.clone(), index + (j - i) as u8)) {
+                    j += 1;
+                }
+                let (high, low) = (255 - 8 * *index as usize, 256 - 8 * (*index as usize + j - i));
+                parts.push(if (high, low) == (255, 0) {
+                    name.to_string()
+                } else {
+                    format!("((_ extract {high} {low}) {name})")
+                });
+            }
+        }
+        i = j;
+    }
+    if parts.len() == 1 {
+        parts.remove(0)
+    } else {
+        format!("(concat {})", parts.join(" "))
+    }
+}
+
+/// Returns the bytes if they are all concrete.
+fn concrete_slice(bytes: &[Byte]) -> Option<Vec<u8>> {
+    bytes
+        .iter()
+        .map(|byte| match byte {
+            Byte::Concrete(byte) => Some(*byte),
+            Byte::Symbolic(..) => None,
+        })
+        .collect()
+}
+
+fn concrete_bytes(bytes: &[u8]) -> Vec<Byte> {
+    bytes.iter().copied().map(Byte::Concrete).collect()
+}
+
+/// Returns `size` bytes of `data` from `offset`, padded with zeros.
+fn slice(data: &[Byte], offset: &Word, size: usize) -> Result<Vec<Byte>> {
+    if size == 0 {
+        return Ok(vec![])
+    }
+    let offset = offset.as_concrete().ok_or_else(|| unsupported("symbolic data offsets"))?;
+    let start = offset.saturating_to::<usize>().min(data.len());
+    let mut bytes = data[start..data.len().min(start.saturating_add(size))].to_vec();
+    bytes.resize(size, Byte::Concrete(0));
+    Ok(bytes)
+}
+
+fn address_word(address: Address) -> Word {
+    Word::Concrete(U256::from_be_slice(address.as_slice()))
+}
+
+fn concrete_address(word: &Word, what: &str) -> Result<Address> {
+    let value =
+        word.as_concrete().ok_or_else(|| unsupported(format!("{what} with a symbolic address")))?;
+    Ok(Address::from_word(B256::from(value)))
+}
+
+fn bool_word(condition: &str) -> String {
+    format!("(ite {condition} {} {})", literal(U256::from(1)), literal(U256::ZERO))
+}
+
+/// Returns the facts relating the hash function to a concrete hash.
+fn hash_axiom(function: &str, input: &[u8], hash: B256) -> String {
+    let (input, hash) = (format!("#x{}", hex::encode(input)), literal(U256::from_be_bytes(hash.0)));
+    format!("(and (= ({function} {input}) {hash}) (= ({function}_inverse {hash}) {input}))")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concatenates_bytes() {
+        let word = Word::symbol("arg0");
+        let mut bytes = vec![Byte::Concrete(0xab), Byte::Concrete(0xcd)];
+        bytes.extend(word.bytes()[..30].iter().cloned());
+        assert_eq!(concat(&bytes), "(concat #xabcd ((_ extract 255 16) arg0))");
+        assert_eq!(concat(&word.bytes()), "arg0");
+    }
+
+    #[test]
+    fn shifts_whole_bytes() {
+        let mut bytes = vec![Byte::Concrete(0x12); 4];
+        bytes.extend(Word::symbol("arg0").bytes()[..28].iter().cloned());
+        let word = Word::Symbolic("t0".into(), Some(bytes.into()));
+        let shifted = shift(&word, U256::from(224), false).unwrap();
+        assert_eq!(concrete_slice(&shifted), Some([vec![0; 28], vec![0x12; 4]].concat()));
+        assert_eq!(shift(&word, U256::from(3), false), None);
+    }
+
+    #[test]
+    fn decodes_cheatcodes() {
+        let selector = |signature: &str| keccak256(signature)[..4].to_vec();
+        assert!(matches!(Cheatcode::decode(&selector("assume(bool)")), Some(Cheatcode::Assume)));
+        assert!(matches!(
+            Cheatcode::decode(&selector("assertEq(uint256,uint256,string)")),
+            Some(Cheatcode::Assert(Comparison::Eq))
+        ));
+        assert!(matches!(
+            Cheatcode::decode(&selector("assertLt(int256,int256)")),
+            Some(Cheatcode::Assert(Comparison::SignedLt))
+        ));
+        assert!(matches!(
+            Cheatcode::decode(&selector("startPrank(address)")),
+            Some(Cheatcode::Prank { persistent: true })
+        ));
+        assert!(matches!(
+            Cheatcode::decode(&selector("expectRevert(bytes4)")),
+            Some(Cheatcode::ExpectRevert { data: Some(DynSolType::FixedBytes(4)) })
+        ));
+        assert!(Cheatcode::decode(&selector("mockCall(address,bytes,bytes)")).is_none());
+    }
+}
@@ -0,0 +1,303 @@
+use crate::executors::{Executor, RawCallResult};
+use alloy_dyn_abi::{DynSolType, DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
+use eyre::Result;
+use foundry_config::SymbolicConfig;
+use foundry_evm_core::{constants::MAGIC_ASSUME, decode::RevertDecoder};
+use foundry_evm_fuzz::{BaseCounterExample, CounterExample, FuzzCase, FuzzTestResult};
+
+mod machine;
+use machine::{nonzero, Byte, Machine, Outcome, Word};
+
+mod solver;
+use solver::{literal, Check, Solver, WORD};
+
+/// The maximum number of models of a failing path which are replayed, when the concrete replay
+/// does not fail.
+const MAX_MODELS: usize = 4;
+
+/// Explores the paths of test functions symbolically, with symbolic arguments, over the state of
+/// an [`Executor`].
+///
+/// Path conditions are discharged to an external SMT solver. Models of the paths are replayed
+/// concretely, so that counterexamples are reported like failing fuzz cases.
+pub struct SymbolicExecutor {
+    /// The EVM executor.
+    executor: Executor,
+    /// The account that calls tests.
+    sender: Address,
+    /// The symbolic execution configuration.
+    config: SymbolicConfig,
+}
+
+/// The test function being explored.
+struct TestCall<'a> {
+    func: &'a Function,
+    address: Address,
+    should_fail: bool,
+    /// The types of the arguments.
+    types: Vec<DynSolType>,
+    /// The names of the symbolic arguments.
+    args: Vec<String>,
+}
+
+/// What was found while exploring a test.
+#[derive(Default)]
+struct Exploration {
+    /// The number of paths which ended.
+    paths: usize,
+    /// The number of paths cut off at the loop bound, the instruction limit or the path limit.
+    bounded: usize,
+    /// The passing replays.
+    cases: Vec<FuzzCase>,
+    /// The last passing replay.
+    last_call: Option<RawCallResult>,
+    /// The calldata, arguments and replay of a counterexample.
+    counterexample: Option<(Bytes, Vec<DynSolValue>, RawCallResult)>,
+    /// What kept some paths from being explored.
+    incomplete: Option<String>,
+}
+
+impl SymbolicExecutor {
+    /// Instantiates a symbolic executor given an executor, the test sender and the configuration.
+    pub fn new(executor: Executor, sender: Address, config: SymbolicConfig) -> Self {
+        Self { executor, sender, config }
+    }
+
+    /// Explores `func` of the contract at `address` symbolically.
+    ///
+    /// If `should_fail` is set, the test passes only if it fails on every path.
+    pub fn explore(
+        &self,
+        func: &Function,
+        address: Address,
+        should_fail: bool,
+        rd: &RevertDecoder,
+    ) -> FuzzTestResult {
+        let mut exploration = Exploration::default();
+        let error = self.try_explore(func, address, should_fail, &mut exploration).err();
+        let Exploration { paths, bounded, cases, last_call, counterexample, incomplete } =
+            exploration;
+        debug!(target: "forge::symbolic", paths, bounded, "explored {}", func.name);
+
+        let mut result = FuzzTestResult {
+            first_case: cases.first().cloned().unwrap_or_default(),
+            gas_by_case: cases.iter().map(|case| (case.gas, case.stipend)).collect(),
+            success: true,
+            skipped: false,
+            reason: None,
+            counterexample: None,
+            logs: vec![],
+            labeled_addresses: Default::default(),
+            traces: None,
+            gas_report_traces: vec![],
+            coverage: None,
+            breakpoints: None,
+            deprecated_cheatcodes: Default::default(),
+        };
+        if let Some(call) = last_call {
+            result.labeled_addresses = call.labels;
+            result.traces = call.traces;
+        }
+
+        if let Some((calldata, args, call)) = counterexample {
+            result.success = false;
+            result.reason = rd.maybe_decode(&call.result, Some(call.exit_reason));
+            result.logs = call.logs;
+            result.labeled_addresses = call.labels;
+            result.traces = call.traces.clone();
+            result.counterexample = Some(CounterExample::Single(
+                BaseCounterExample::from_fuzz_call(calldata, args, call.traces),
+            ));
+        } else if let Some(error) = error {
+            result.success = false;
+            result.reason = Some(format!("symbolic execution failed: {error:#}"));
+        } else if let Some(what) = incomplete {
+            result.success = false;
+            result.reason = Some(format!("symbolic execution does not support {what}"));
+        } else if bounded > 0 {
+            warn!(
+                target: "forge::symbolic",
+                bounded,
+                "{} was not explored completely, paths were cut off at the loop bound or the path \
+                 limit",
+                func.name
+            );
+        }
+        result
+    }
+
+    fn try_explore(
+        &self,
+        func: &Function,
+        address: Address,
+        should_fail: bool,
+        exploration: &mut Exploration,
+    ) -> Result<()> {
+        let types = func
+            .inputs
+            .iter()
+            .map(|input| input.selector_type().parse::<DynSolType>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let args = (0..types.len()).map(|i| format!("arg{i}")).collect::<Vec<_>>();
+        let test = TestCall { func, address, should_fail, types, args };
+
+        let mut constraints = vec![];
+        let mut calldata = func.selector().iter().copied().map(Byte::Concrete).collect::<Vec<_>>();
+        for (ty, name) in test.types.iter().zip(&test.args) {
+            let Some(constraint) = range_constraint(ty, name) else {
+                eyre::bail!("parameters of type `{ty}` cannot be symbolic")
+            };
+            constraints.extend(constraint);
+            calldata.extend(Word::symbol(name).bytes());
+        }
+
+        let solver = Solver::spawn(&self.config)?;
+        let mut machine = Machine::new(&self.executor, solver, self.config.loop_bound);
+        for name in &test.args {
+            machine.solver().declare(name, WORD)?;
+        }
+
+        let mut pending = vec![machine.start(self.sender, address, calldata, constraints)?];
+        while let Some(mut path) = pending.pop() {
+            if exploration.paths >= self.config.max_paths as usize {
+                exploration.bounded += pending.len() + 1;
+                break
+            }
+            // The conditions under which the test fails and passes at the end of the path.
+            let (failure, success) = match machine.run(&mut path, &mut pending)? {
+                Outcome::Returned(failed) => {
+                    let failure = nonzero(&failed);
+                    let success = format!("(not {failure})");
+                    (Some(failure), Some(success))
+                }
+                Outcome::Reverted => (Some("true".to_string()), None),
+                Outcome::Infeasible => continue,
+                Outcome::Bounded => {
+                    exploration.bounded += 1;
+                    continue
+                }
+                Outcome::Unsupported(what) => {
+                    exploration.incomplete.get_or_insert(what);
+                    continue
+                }
+            };
+            exploration.paths += 1;
+
+            let (bad, good) = if should_fail { (success, failure) } else { (failure, success) };
+            if let Some(bad) = bad {
+                let constraints = path.constraints.clone();
+                if self.replay_models(
+                    &mut machine,
+                    &test,
+                    constraints,
+                    &bad,
+                    MAX_MODELS,
+                    exploration,
+                )? {
+                    return Ok(())
+                }
+            }
+            if let Some(good) = good {
+                self.replay_models(&mut machine, &test, path.constraints, &good, 1, exploration)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replays up to `attempts` distinct models of a path satisfying `condition`.
+    ///
+    /// Returns true once a replay fails, recording it as the counterexample.
+    fn replay_models(
+        &self,
+        machine: &mut Machine<'_>,
+        test: &TestCall<'_>,
+        mut constraints: Vec<String>,
+        condition: &str,
+        attempts: usize,
+        exploration: &mut Exploration,
+    ) -> Result<bool> {
+        let values = test.args.iter().map(String::as_str).collect::<Vec<_>>();
+        constraints.push(condition.to_string());
+        for _ in 0..attempts {
+            let model = match machine.solver().check(&constraints, None, &values)? {
+                Check::Sat(model) => model,
+                Check::Unsat => break,
+                Check::Unknown => {
+                    exploration
+                        .incomplete
+                        .get_or_insert_with(|| "path conditions the solver cannot decide".into());
+                    break
+                }
+            };
+            let args = decode_args(&test.types, &model);
+            let calldata: Bytes = test.func.abi_encode_input(&args)?.into();
+            let mut call =
+                self.executor.call_raw(self.sender, test.address, calldata.clone(), U256::ZERO)?;
+            if call.result.as_ref() != MAGIC_ASSUME {
+                if self.executor.is_raw_call_mut_success(test.address, &mut call, test.should_fail)
+                {
+                    exploration.cases.push(FuzzCase {
+                        calldata,
+                        gas: call.gas_used,
+                        stipend: call.stipend,
+                    });
+                    exploration.last_call = Some(call);
+                } else {
+                    exploration.counterexample = Some((calldata, args, call));
+                    return Ok(true)
+                }
+            }
+            if values.is_empty() {
+                break
+            }
+            // The replay disagrees with the symbolic execution, look for another model.
+            let model = values
+                .iter()
+                .zip(&model)
+                .map(|(name, value)| format!("(= {name} {})", literal(*value)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            constraints.push(format!("(not (and true {model}))"));
+        }
+        Ok(false)
+    }
+}
+
+/// Returns the constraint on the word encoding an argument of type `ty`, or `None` if arguments
+/// of the type cannot be symbolic.
+fn range_constraint(ty: &DynSolType, name: &str) -> Option<Option<String>> {
+    let max = |bits: usize| literal((U256::from(1) << bits) - U256::from(1));
+    Some(match ty {
+        DynSolType::Uint(256) | DynSolType::Int(256) | DynSolType::FixedBytes(32) => None,
+        DynSolType::Uint(bits) => Some(format!("(bvule {name} {})", max(*bits))),
+        DynSolType::Address => Some(format!("(bvule {name} {})", max(160))),
+        DynSolType::Bool => Some(format!("(bvule {name} {})", max(1))),
+        DynSolType::Int(bits) => Some(format!(
+            "(= {name} ((_ sign_extend {}) ((_ extract {} 0) {name})))",
+            256 - bits,
+            bits - 1
+        )),
+        DynSolType::FixedBytes(size) => {
+            Some(format!("(= (bvand {name} {}) {})", max(256 - size * 8), literal(U256::ZERO)))
+        }
+        _ => return None,
+    })
+}
+
+/// Decodes the arguments of a model.
+fn decode_args(types: &[DynSolType], model: &[U256]) -> Vec<DynSolValue> {
+    types
+        .iter()
+        .zip(model)
+        .map(|(ty, value)| match ty {
+            DynSolType::Uint(bits) => DynSolValue::Uint(*value, *bits),
+            DynSolType::Int(bits) => DynSolValue::Int(I256::from_raw(*value), *bits),
+            DynSolType::Bool => DynSolValue::Bool(!value.is_zero()),
+            DynSolType::Address => DynSolValue::Address(Address::from_word(B256::from(*value))),
+            DynSolType::FixedBytes(size) => DynSolValue::FixedBytes(B256::from(*value), *size),
+            _ => unreachable!("arguments of type `{ty}` are not symbolic"),
+        })
+        .collect()
+}
@@ -0,0 +1,187 @@
+use alloy_primitives::{hex, map::HashSet, U256};
+use eyre::{bail, ensure, eyre, Result, WrapErr};
+use foundry_config::SymbolicConfig;
+use std::{
+    io::{BufRead, BufReader, BufWriter, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::Arc,
+};
+
+/// The SMT sort of EVM words.
+pub const WORD: &str = "(_ BitVec 256)";
+
+/// Returns the SMT literal of a word.
+pub fn literal(value: U256) -> String {
+    format!("#x{}", hex::encode(value.to_be_bytes::<32>()))
+}
+
+/// The result of a satisfiability check.
+#[derive(Debug)]
+pub enum Check {
+    /// The constraints are satisfiable, with the values of the requested terms.
+    Sat(Vec<U256>),
+    /// The constraints are unsatisfiable.
+    Unsat,
+    /// The solver could not decide the constraints.
+    Unknown,
+}
+
+/// An SMT solver process, driven through SMT-LIB commands on its standard input.
+///
+/// Terms are defined once at the top level and referred to by name, so that paths share their
+/// common terms. Path conditions are asserted in a scope which is popped after each check.
+pub struct Solver {
+    child: Child,
+    stdin: BufWriter<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    /// The number of terms defined so far.
+    terms: usize,
+    /// The symbols declared so far.
+    declared: HashSet<String>,
+}
+
+impl Solver {
+    /// Starts the solver configured in `config`.
+    pub fn spawn(config: &SymbolicConfig) -> Result<Self> {
+        let mut child = Command::new(&config.solver)
+            .args(&config.solver_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .wrap_err_with(|| format!("failed to start the SMT solver `{}`", config.solver))?;
+        let stdin = BufWriter::new(child.stdin.take().expect("stdin is piped"));
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut solver = Self { child, stdin, stdout, terms: 0, declared: HashSet::default() };
+        solver.send("(set-option :produce-models true)")?;
+        solver.send("(set-logic QF_AUFBV)")?;
+        Ok(solver)
+    }
+
+    /// Defines a new term of `sort` and returns its name.
+    pub fn define(&mut self, sort: &str, expr: &str) -> Result<Arc<str>> {
+        let name = format!("t{}", self.terms);
+        self.terms += 1;
+        self.send(&format!("(define-fun {name} () {sort} {expr})"))?;
+        Ok(name.into())
+    }
+
+    /// Defines the term `name` of `sort`, unless it is already declared.
+    pub fn define_named(&mut self, name: &str, sort: &str, expr: &str) -> Result<()> {
+        if self.declared.insert(name.to_string()) {
+            self.send(&format!("(define-fun {name} () {sort} {expr})"))?;
+        }
+        Ok(())
+    }
+
+    /// Declares the unconstrained constant `name` of `sort`, unless it is already declared.
+    pub fn declare(&mut self, name: &str, sort: &str) -> Result<()> {
+        self.declare_fun(name, "", sort)
+    }
+
+    /// Declares the uninterpreted function `name`, unless it is already declared.
+    ///
+    /// Returns true if the function was not declared before.
+    pub fn declare_fun(&mut self, name: &str, args: &str, sort: &str) -> Result<bool> {
+        if !self.declared.insert(name.to_string()) {
+            return Ok(false)
+        }
+        self.send(&format!("(declare-fun {name} ({args}) {sort})"))?;
+        Ok(true)
+    }
+
+    /// Asserts a fact holding on every path.
+    pub fn assert(&mut self, term: &str) -> Result<()> {
+        self.send(&format!("(assert {term})"))
+    }
+
+    /// Checks whether the conjunction of `constraints` and `extra` is satisfiable, returning the
+    /// values of the word terms `values` in a model if it is.
+    pub fn check(
+        &mut self,
+        constraints: &[String],
+        extra: Option<&str>,
+        values: &[&str],
+    ) -> Result<Check> {
+        self.send("(push 1)")?;
+        for constraint in constraints.iter().map(String::as_str).chain(extra) {
+            self.send(&format!("(assert {constraint})"))?;
+        }
+        self.send("(check-sat)")?;
+        let response = self.read_response()?;
+        let check = match response.trim() {
+            "sat" if values.is_empty() => Check::Sat(vec![]),
+            "sat" => {
+                self.send(&format!("(get-value ({}))", values.join(" ")))?;
+                let model = self.read_response()?;
+                Check::Sat(parse_values(&model, values.len())?)
+            }
+            "unsat" => Check::Unsat,
+            "unknown" => Check::Unknown,
+            other => bail!("unexpected SMT solver response: {other}"),
+        };
+        self.send("(pop 1)")?;
+        Ok(check)
+    }
+
+    fn send(&mut self, command: &str) -> Result<()> {
+        trace!(target: "forge::symbolic", command);
+        writeln!(self.stdin, "{command}").wrap_err("failed to write to the SMT solver")
+    }
+
+    /// Reads a complete response, which may span several lines.
+    fn read_response(&mut self) -> Result<String> {
+        self.stdin.flush().wrap_err("failed to write to the SMT solver")?;
+        let mut response = String::new();
+        loop {
+            if self.stdout.read_line(&mut response)? == 0 {
+                bail!("the SMT solver exited unexpectedly")
+            }
+            let depth =
+                response.matches('(').count() as isize - response.matches(')').count() as isize;
+            if depth <= 0 && !response.trim().is_empty() {
+                break
+            }
+        }
+        if response.trim_start().starts_with("(error") {
+            bail!("SMT solver error: {}", response.trim())
+        }
+        Ok(response)
+    }
+}
+
+impl Drop for Solver {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Parses the bit vector values of a `get-value` response, in order.
+fn parse_values(response: &str, count: usize) -> Result<Vec<U256>> {
+    let values = response
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter_map(|token| {
+            if let Some(digits) = token.strip_prefix("#x") {
+                Some(U256::from_str_radix(digits, 16))
+            } else {
+                token.strip_prefix("#b").map(|digits| U256::from_str_radix(digits, 2))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| eyre!("invalid SMT solver model: {err}"))?;
+    ensure!(values.len() == count, "unexpected SMT solver model: {}", response.trim());
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_models() {
+        let model = "((arg0 #x000000000000000000000000000000000000000000000000000000000000002a)\n (arg1 #b1))";
+        assert_eq!(parse_values(model, 2).unwrap(), vec![U256::from(42), U256::from(1)]);
+        assert!(parse_values(model, 3).is_err());
+    }
+}
@@ -11,7 +11,9 @@ extern crate tracing;
 pub mod executors;
 pub mod inspectors;
 
-pub use foundry_evm_core::{backend, constants, decode, fork, opts, utils, InspectorExt};
+pub use foundry_evm_core::{
+    backend, constants, decode, fork, opts, state_snapshot, utils, InspectorExt,
+};
 pub use foundry_evm_coverage as coverage;
 pub use foundry_evm_fuzz as fuzz;
 pub use foundry_evm_traces as traces;
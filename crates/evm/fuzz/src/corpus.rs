@@ -0,0 +1,341 @@
+use crate::invariant::BasicTxDetails;
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{map::HashSet, Bytes, B256, I256, U256};
+use foundry_common::fs;
+use foundry_evm_coverage::HitMaps;
+use parking_lot::RwLock;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{path::Path, sync::Arc};
+
+/// Corpus of the call sequences that reached new code during invariant campaigns.
+pub type InvariantCorpus = FuzzCorpus<Vec<BasicTxDetails>>;
+
+/// Inputs that reached new code during coverage-guided fuzzing, kept to be mutated into new inputs.
+///
+/// Wrapped in a shareable container, so the strategy mutating the entries sees the inputs added
+/// while fuzzing.
+#[derive(Debug)]
+pub struct FuzzCorpus<T = Bytes> {
+    inner: Arc<RwLock<CorpusInner<T>>>,
+}
+
+impl<T> Clone for FuzzCorpus<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Default for FuzzCorpus<T> {
+    fn default() -> Self {
+        Self { inner: Default::default() }
+    }
+}
+
+#[derive(Debug)]
+struct CorpusInner<T> {
+    /// The inputs, in the order they were added.
+    entries: Vec<T>,
+    /// The program counters reached by the inputs, with the hash of their bytecode.
+    coverage: HashSet<(B256, usize)>,
+}
+
+impl<T> Default for CorpusInner<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new(), coverage: Default::default() }
+    }
+}
+
+impl<T: Clone + PartialEq + Serialize + DeserializeOwned> FuzzCorpus<T> {
+    /// Loads the corpus persisted at `path`, or returns an empty corpus if there is none.
+    pub fn load(path: &Path) -> Self {
+        let mut entries = Vec::new();
+        if path.exists() {
+            match fs::read_json_file::<Vec<T>>(path) {
+                Ok(persisted) => entries = persisted,
+                Err(err) => warn!(%err, "failed to read fuzz corpus"),
+            }
+        }
+        Self { inner: Arc::new(RwLock::new(CorpusInner { entries, ..Default::default() })) }
+    }
+
+    /// Persists the corpus at `path`.
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_pretty_json_file(path, &self.inner.read().entries)?;
+        Ok(())
+    }
+
+    /// Returns a copy of the corpus which doesn't share the inputs added to it with this corpus.
+    pub fn fork(&self) -> Self {
+        let inner = self.inner.read();
+        let inner =
+            CorpusInner { entries: inner.entries.clone(), coverage: inner.coverage.clone() };
+        Self { inner: Arc::new(RwLock::new(inner)) }
+    }
+
+    /// Adds the inputs and the coverage of a forked corpus which this corpus doesn't have yet.
+    pub fn merge(&self, other: &Self) {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return
+        }
+        let other = other.inner.read();
+        let mut inner = self.inner.write();
+        for entry in &other.entries {
+            if !inner.entries.contains(entry) {
+                inner.entries.push(entry.clone());
+            }
+        }
+        inner.coverage.extend(other.coverage.iter().copied());
+    }
+
+    /// Returns the inputs of the corpus.
+    pub fn entries(&self) -> Vec<T> {
+        self.inner.read().entries.clone()
+    }
+
+    /// Returns the number of inputs in the corpus.
+    pub fn len(&self) -> usize {
+        self.inner.read().entries.len()
+    }
+
+    /// Returns true if the corpus has no inputs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Records the coverage reached by an input, adding the input to the corpus if it reached
+    /// code that no earlier input did.
+    ///
+    /// Returns true if the input reached new code.
+    pub fn record(&self, input: &T, coverage: &HitMaps) -> bool {
+        let mut inner = self.inner.write();
+        let mut new_coverage = false;
+        for (hash, map) in coverage.iter() {
+            for (pc, _) in map.iter() {
+                new_coverage |= inner.coverage.insert((*hash, pc));
+            }
+        }
+        if new_coverage && !inner.entries.contains(input) {
+            inner.entries.push(input.clone());
+        }
+        new_coverage
+    }
+
+    /// Returns the entry selected by the random `index`, if the corpus is not empty.
+    pub fn select(&self, index: usize) -> Option<T> {
+        let inner = self.inner.read();
+        (!inner.entries.is_empty()).then(|| inner.entries[index % inner.entries.len()].clone())
+    }
+}
+
+impl FuzzCorpus {
+    /// Mutates an input of the corpus into a new input of `func`.
+    ///
+    /// A single argument of the selected entry is either replaced by the same argument of `fresh`,
+    /// a newly generated input, or of another entry, or slightly changed if it is a scalar. The
+    /// entries and the argument are selected by the random `entry`, `other` and `arg` numbers.
+    ///
+    /// Returns `None` if the corpus is empty or the entry cannot be decoded.
+    pub fn mutate(
+        &self,
+        func: &Function,
+        fresh: &Bytes,
+        entry: usize,
+        other: usize,
+        arg: usize,
+        seed: u64,
+    ) -> Option<Bytes> {
+        let (entry, other) = (self.select(entry)?, self.select(other)?);
+        let decode = |input: &Bytes| func.abi_decode_input(input.get(4..)?, false).ok();
+
+        let mut values = decode(&entry)?;
+        if values.is_empty() {
+            return None
+        }
+        let arg = arg % values.len();
+        let replacement = match seed % 3 {
+            0 => decode(fresh),
+            1 => decode(&other),
+            _ => (!tweak(&mut values[arg], seed / 3)).then(|| decode(fresh)).flatten(),
+        };
+        if let Some(mut replacement) = replacement {
+            if replacement.len() != values.len() {
+                return None
+            }
+            values[arg] = replacement.swap_remove(arg);
+        }
+        func.abi_encode_input(&values).ok().map(Into::into)
+    }
+}
+
+impl InvariantCorpus {
+    /// Mutates a call sequence of the corpus into the start of a new sequence.
+    ///
+    /// The new sequence is a prefix of the selected entry, either alone, to be continued by
+    /// generated calls, or followed by a suffix of another entry. The entries and the split points
+    /// are selected by the random `entry`, `other` and `split` numbers.
+    ///
+    /// Returns `None` if the corpus is empty.
+    pub fn mutate(
+        &self,
+        entry: usize,
+        other: usize,
+        split: usize,
+        seed: u64,
+    ) -> Option<Vec<BasicTxDetails>> {
+        let (mut entry, other) = (self.select(entry)?, self.select(other)?);
+        if entry.is_empty() {
+            return None
+        }
+        entry.truncate(split % entry.len() + 1);
+        if seed % 2 == 1 && !other.is_empty() {
+            let start = (seed / 2) as usize % other.len();
+            entry.extend_from_slice(&other[start..]);
+        }
+        Some(entry)
+    }
+}
+
+/// Slightly changes a scalar value: adds or subtracts a small delta to integers and flips a single
+/// bit of booleans and byte values.
+///
+/// Returns false, leaving the value unchanged, if the value is not a supported scalar.
+fn tweak(value: &mut DynSolValue, seed: u64) -> bool {
+    let delta = U256::from(seed % 16 + 1);
+    let add = seed & 0x10 == 0;
+    let bit = (seed >> 5) as usize;
+    match value {
+        DynSolValue::Bool(value) => *value = !*value,
+        DynSolValue::Uint(value, size) => {
+            let raw = if add { value.wrapping_add(delta) } else { value.wrapping_sub(delta) };
+            *value = raw & mask(*size);
+        }
+        DynSolValue::Int(value, size) => {
+            let raw = value.into_raw();
+            let raw = if add { raw.wrapping_add(delta) } else { raw.wrapping_sub(delta) };
+            *value = I256::from_raw(sign_extend(raw, *size));
+        }
+        DynSolValue::FixedBytes(word, size) if *size > 0 => {
+            let bit = bit % (*size * 8);
+            word[bit / 8] ^= 1 << (bit % 8);
+        }
+        DynSolValue::Bytes(bytes) if !bytes.is_empty() => {
+            let bit = bit % (bytes.len() * 8);
+            bytes[bit / 8] ^= 1 << (bit % 8);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Returns the mask of the low `bits` bits.
+fn mask(bits: usize) -> U256 {
+    if bits >= 256 {
+        U256::MAX
+    } else {
+        (U256::from(1) << bits) - U256::from(1)
+    }
+}
+
+/// Sign-extends the two's complement integer in the low `bits` bits of `raw`.
+fn sign_extend(raw: U256, bits: usize) -> U256 {
+    if bits == 0 || bits >= 256 {
+        return raw
+    }
+    if raw.bit(bits - 1) {
+        raw | !mask(bits)
+    } else {
+        raw & mask(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invariant::CallDetails;
+    use alloy_primitives::{map::B256HashMap, Address};
+    use foundry_evm_coverage::HitMap;
+
+    fn hits(pcs: &[usize]) -> HitMaps {
+        let mut map = HitMap::new(Bytes::new());
+        for pc in pcs {
+            map.hit(*pc);
+        }
+        HitMaps(B256HashMap::from_iter([(B256::ZERO, map)]))
+    }
+
+    #[test]
+    fn records_new_coverage() {
+        let corpus = FuzzCorpus::default();
+        assert!(corpus.record(&Bytes::from_static(&[1]), &hits(&[0, 1])));
+        assert!(!corpus.record(&Bytes::from_static(&[2]), &hits(&[1])));
+        assert!(corpus.record(&Bytes::from_static(&[3]), &hits(&[1, 2])));
+        assert_eq!(corpus.entries(), vec![Bytes::from_static(&[1]), Bytes::from_static(&[3])]);
+    }
+
+    #[test]
+    fn tweaks_values_in_range() {
+        for seed in 0..64 {
+            let mut value = DynSolValue::Uint(U256::from(250), 8);
+            assert!(tweak(&mut value, seed));
+            assert!(value.as_uint().unwrap().0 <= U256::from(u8::MAX));
+
+            let mut value = DynSolValue::Int(I256::try_from(-126i64).unwrap(), 8);
+            assert!(tweak(&mut value, seed));
+            let int = value.as_int().unwrap().0;
+            assert!(
+                int >= I256::try_from(-128i64).unwrap() && int <= I256::try_from(127i64).unwrap()
+            );
+        }
+        assert!(!tweak(&mut DynSolValue::String("a".to_string()), 0));
+    }
+
+    #[test]
+    fn mutates_entries() {
+        let func = Function::parse("testFuzz(uint256 a, bool b)").unwrap();
+        let input = |a: u64, b: bool| -> Bytes {
+            func.abi_encode_input(&[DynSolValue::from(U256::from(a)), DynSolValue::Bool(b)])
+                .unwrap()
+                .into()
+        };
+        let corpus = FuzzCorpus::default();
+        let fresh = input(7, true);
+        assert_eq!(corpus.mutate(&func, &fresh, 0, 0, 0, 0), None);
+
+        corpus.record(&input(1, false), &hits(&[0]));
+        // Replaces the first argument with the one of the fresh input.
+        assert_eq!(corpus.mutate(&func, &fresh, 0, 0, 0, 0), Some(input(7, false)));
+        // Changes the first argument by a small delta.
+        assert_eq!(corpus.mutate(&func, &fresh, 0, 0, 0, 2), Some(input(2, false)));
+        // Flips the second argument.
+        assert_eq!(corpus.mutate(&func, &fresh, 0, 0, 1, 2), Some(input(1, true)));
+    }
+
+    #[test]
+    fn mutates_sequences() {
+        let sequence = |calls: &[u8]| -> Vec<BasicTxDetails> {
+            calls
+                .iter()
+                .map(|call| BasicTxDetails {
+                    sender: Address::ZERO,
+                    call_details: CallDetails {
+                        target: Address::ZERO,
+                        calldata: Bytes::copy_from_slice(&[*call]),
+                    },
+                })
+                .collect()
+        };
+        let corpus = InvariantCorpus::default();
+        assert_eq!(corpus.mutate(0, 0, 0, 0), None);
+
+        corpus.record(&sequence(&[1, 2, 3]), &hits(&[0]));
+        corpus.record(&sequence(&[4, 5]), &hits(&[1]));
+        // Keeps a prefix of the first entry.
+        assert_eq!(corpus.mutate(0, 0, 1, 0), Some(sequence(&[1, 2])));
+        // Follows a prefix of the first entry with a suffix of the second one.
+        assert_eq!(corpus.mutate(0, 1, 0, 3), Some(sequence(&[1, 5])));
+    }
+}
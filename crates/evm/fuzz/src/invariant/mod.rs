@@ -2,6 +2,7 @@ use alloy_json_abi::{Function, JsonAbi};
 use alloy_primitives::{Address, Bytes, Selector};
 use itertools::Either;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, sync::Arc};
 
 mod call_override;
@@ -30,6 +31,12 @@ impl FuzzRunIdentifiedContracts {
         Self { targets: Arc::new(Mutex::new(targets)), is_updatable }
     }
 
+    /// Returns a copy of the targets which doesn't share the contracts created during a run with
+    /// these targets.
+    pub fn fork(&self) -> Self {
+        Self::new(self.targets.lock().clone(), self.is_updatable)
+    }
+
     /// If targets are updatable, collect all contracts created during an invariant run (which
     /// haven't been discovered yet).
     pub fn collect_created_contracts(
@@ -211,7 +218,7 @@ impl TargetedContract {
 }
 
 /// Details of a transaction generated by invariant strategy for fuzzing a target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BasicTxDetails {
     // Transaction sender address.
     pub sender: Address,
@@ -220,7 +227,7 @@ pub struct BasicTxDetails {
 }
 
 /// Call details of a transaction generated to fuzz invariant target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CallDetails {
     // Address of target contract.
     pub target: Address,
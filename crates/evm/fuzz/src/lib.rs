@@ -11,9 +11,10 @@ extern crate tracing;
 use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
 use alloy_primitives::{
     map::{AddressHashMap, HashMap},
-    Address, Bytes, Log,
+    Address, Bytes, Log, B256, U256,
 };
 use foundry_common::{calc, contracts::ContractsByAddress, evm::Breakpoints};
+use foundry_compilers::artifacts::StorageLayout;
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_traces::{CallTraceArena, SparsedTraceArena};
 use itertools::Itertools;
@@ -28,6 +29,9 @@ pub use error::FuzzError;
 pub mod invariant;
 pub mod strategies;
 
+mod corpus;
+pub use corpus::{FuzzCorpus, InvariantCorpus};
+
 mod inspector;
 pub use inspector::Fuzzer;
 
@@ -56,6 +60,9 @@ pub struct BaseCounterExample {
     /// Traces
     #[serde(skip)]
     pub traces: Option<SparsedTraceArena>,
+    /// Storage slots changed by the call, if recorded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_diff: Vec<StorageDiff>,
 }
 
 impl BaseCounterExample {
@@ -81,6 +88,7 @@ impl BaseCounterExample {
                             foundry_common::fmt::format_tokens(&args).format(", ").to_string(),
                         ),
                         traces,
+                        state_diff: vec![],
                     };
                 }
             }
@@ -94,6 +102,7 @@ impl BaseCounterExample {
             signature: None,
             args: None,
             traces,
+            state_diff: vec![],
         }
     }
 
@@ -111,6 +120,7 @@ impl BaseCounterExample {
             signature: None,
             args: Some(foundry_common::fmt::format_tokens(&args).format(", ").to_string()),
             traces,
+            state_diff: vec![],
         }
     }
 }
@@ -143,6 +153,56 @@ impl fmt::Display for BaseCounterExample {
     }
 }
 
+/// A storage slot changed by a call of an invariant counterexample.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageDiff {
+    /// Address of the contract owning the slot
+    pub address: Address,
+    /// The changed slot
+    pub slot: B256,
+    /// Names of the variables stored in the slot, if known from the storage layout
+    pub label: Option<String>,
+    /// Value of the slot before the call
+    pub previous_value: B256,
+    /// Value of the slot after the call
+    pub new_value: B256,
+}
+
+impl StorageDiff {
+    /// Names the slot after the state variables the storage layout places in it.
+    ///
+    /// Slots derived from mappings and dynamic arrays are left unnamed.
+    pub fn label_from_layout(&mut self, layout: &StorageLayout) {
+        let slot = U256::from_be_bytes(self.slot.0);
+        let names = layout
+            .storage
+            .iter()
+            .filter(|storage| storage.slot.parse::<U256>().is_ok_and(|s| s == slot))
+            .map(|storage| storage.label.as_str())
+            .collect::<Vec<_>>();
+        if !names.is_empty() {
+            self.label = Some(names.join(", "));
+        }
+    }
+}
+
+impl fmt::Display for StorageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slot = U256::from_be_bytes(self.slot.0);
+        match &self.label {
+            Some(label) => write!(f, "@ {label} (slot {slot})")?,
+            None => write!(f, "@ {slot}")?,
+        }
+        write!(
+            f,
+            " on {}: {} → {}",
+            self.address,
+            U256::from_be_bytes(self.previous_value.0),
+            U256::from_be_bytes(self.new_value.0)
+        )
+    }
+}
+
 /// The outcome of a fuzz test
 #[derive(Debug)]
 pub struct FuzzTestResult {
@@ -1,11 +1,11 @@
 use crate::{
     strategies::{fuzz_param_from_state, fuzz_param_with_fixtures, EvmFuzzState},
-    FuzzFixtures,
+    FuzzCorpus, FuzzFixtures,
 };
 use alloy_dyn_abi::JsonAbiExt;
 use alloy_json_abi::Function;
 use alloy_primitives::Bytes;
-use proptest::prelude::Strategy;
+use proptest::prelude::{any, Strategy};
 
 /// Given a function, it returns a strategy which generates valid calldata
 /// for that function's input types, following declared test fixtures.
@@ -60,6 +60,21 @@ pub fn fuzz_calldata_from_state(
         .no_shrink()
 }
 
+/// Given a function and a corpus of inputs that reached new code, it returns a strategy which
+/// generates calldata by mutating the corpus entries. Inputs generated by `fresh` are used while
+/// the corpus is empty, and as replacement arguments in mutations.
+pub fn fuzz_calldata_from_corpus(
+    func: Function,
+    corpus: FuzzCorpus,
+    fresh: impl Strategy<Value = Bytes>,
+) -> impl Strategy<Value = Bytes> {
+    (fresh, any::<usize>(), any::<usize>(), any::<usize>(), any::<u64>())
+        .prop_map(move |(fresh, entry, other, arg, seed)| {
+            corpus.mutate(&func, &fresh, entry, other, arg, seed).unwrap_or(fresh)
+        })
+        .no_shrink()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{strategies::fuzz_calldata, FuzzFixtures};
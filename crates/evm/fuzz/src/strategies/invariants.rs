@@ -9,7 +9,7 @@ use alloy_primitives::Address;
 use parking_lot::RwLock;
 use proptest::prelude::*;
 use rand::seq::IteratorRandom;
-use std::{rc::Rc, sync::Arc};
+use std::sync::Arc;
 
 /// Given a target address, we generate random calldata.
 pub fn override_call_strat(
@@ -62,8 +62,8 @@ pub fn invariant_strat(
     contracts: FuzzRunIdentifiedContracts,
     dictionary_weight: u32,
     fuzz_fixtures: FuzzFixtures,
-) -> impl Strategy<Value = BasicTxDetails> {
-    let senders = Rc::new(senders);
+) -> impl Strategy<Value = BasicTxDetails> + Send + Sync {
+    let senders = Arc::new(senders);
     any::<prop::sample::Selector>()
         .prop_flat_map(move |selector| {
             let contracts = contracts.targets.lock();
@@ -86,7 +86,7 @@ pub fn invariant_strat(
 /// * If `senders` is not empty, a random address is chosen from the list of senders.
 fn select_random_sender(
     fuzz_state: &EvmFuzzState,
-    senders: Rc<SenderFilters>,
+    senders: Arc<SenderFilters>,
     dictionary_weight: u32,
 ) -> impl Strategy<Value = Address> {
     if !senders.targeted.is_empty() {
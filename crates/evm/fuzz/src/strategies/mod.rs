@@ -8,7 +8,7 @@ mod param;
 pub use param::{fuzz_param, fuzz_param_from_state, fuzz_param_with_fixtures};
 
 mod calldata;
-pub use calldata::{fuzz_calldata, fuzz_calldata_from_state};
+pub use calldata::{fuzz_calldata, fuzz_calldata_from_corpus, fuzz_calldata_from_state};
 
 mod state;
 pub use state::EvmFuzzState;
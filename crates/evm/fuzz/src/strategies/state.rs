@@ -75,6 +75,26 @@ impl EvmFuzzState {
         dict.insert_new_state_values(state_changeset);
     }
 
+    /// Returns a copy of the fuzz state with a dictionary of its own.
+    ///
+    /// Used by the workers of a parallel invariant campaign, so the values collected by the runs
+    /// of one worker are not seen by, or reverted under, the runs of another.
+    pub fn fork(&self) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(self.inner.read().clone())),
+            deployed_libs: self.deployed_libs.clone(),
+        }
+    }
+
+    /// Merges the sample values collected across the runs of a forked state into this state.
+    pub fn merge(&self, other: &Self) {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return
+        }
+        let other = other.inner.read();
+        self.inner.write().merge_sample_values(&other);
+    }
+
     /// Removes all newly added entries from the dictionary.
     ///
     /// Should be called between fuzz/invariant runs to avoid accumulating data derived from fuzz
@@ -95,7 +115,7 @@ impl EvmFuzzState {
 
 // We're using `IndexSet` to have a stable element order when restoring persisted state, as well as
 // for performance when iterating over the sets.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct FuzzDictionary {
     /// Collected state values.
     state_values: AIndexSet<B256>,
@@ -345,6 +365,13 @@ impl FuzzDictionary {
         }
     }
 
+    /// Adds the sample values of another dictionary which this dictionary doesn't have yet.
+    fn merge_sample_values(&mut self, other: &Self) {
+        for (sample_type, values) in &other.sample_values {
+            self.sample_values.entry(sample_type.clone()).or_default().extend(values);
+        }
+    }
+
     pub fn values(&self) -> &AIndexSet<B256> {
         &self.state_values
     }
@@ -0,0 +1,431 @@
+use alloy_consensus::Transaction;
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt, Specifier};
+use alloy_json_abi::{Constructor, JsonAbi};
+use alloy_network::TransactionResponse;
+use alloy_primitives::{Address, Bytes, Selector, TxHash, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockTransactions;
+use assertion_executor::{
+    db::fork_db::ForkDb,
+    store::{AssertionState, AssertionStore},
+    ExecutorConfig,
+};
+use clap::Parser;
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, Cell, Color, Table};
+use eyre::{Context, Result};
+use foundry_cli::{
+    opts::{CoreBuildArgs, RpcOpts},
+    utils::{self, init_progress, remove_contract, LoadConfig},
+};
+use foundry_common::{
+    compile, fmt::parse_tokens, is_known_system_sender, shell, SYSTEM_TRANSACTION_TYPE,
+};
+use foundry_compilers::{artifacts::BytecodeObject, info::ContractInfo, utils::canonicalize};
+use foundry_config::Config;
+use foundry_evm::{
+    decode::RevertDecoder,
+    executors::TracingExecutor,
+    revm::{primitives::EnvWithHandlerCfg, DatabaseCommit},
+    traces::TraceMode,
+    utils::configure_tx_env,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+foundry_config::impl_figment_convert!(AssertArgs, build, rpc);
+
+/// CLI arguments for `forge assert`.
+///
+/// Replays historical transactions on a fork of the chain and runs an assertion contract against
+/// each of them, as the credible layer would have done when the transaction was included.
+#[derive(Clone, Debug, Parser)]
+pub struct AssertArgs {
+    /// The assertion contract identifier in the form `(<path>:)?<contractname>`.
+    pub contract: ContractInfo,
+
+    /// The address of the contract the assertions are registered for.
+    #[arg(long, value_name = "ADDRESS")]
+    pub adopter: Address,
+
+    /// The constructor arguments of the assertion contract.
+    #[arg(long, num_args(1..), value_name = "ARGS", allow_hyphen_values = true)]
+    pub constructor_args: Vec<String>,
+
+    /// The hashes of the transactions to run the assertions against.
+    #[arg(
+        long = "tx",
+        value_name = "TX_HASH",
+        num_args(1..),
+        required_unless_present = "from_block",
+        conflicts_with_all = ["from_block", "to_block"]
+    )]
+    pub txs: Vec<TxHash>,
+
+    /// The first block of the range of blocks whose transactions to run the assertions against.
+    #[arg(long, value_name = "BLOCK", requires = "to_block")]
+    pub from_block: Option<u64>,
+
+    /// The last block (inclusive) of the range of blocks whose transactions to run the
+    /// assertions against.
+    #[arg(long, value_name = "BLOCK", requires = "from_block")]
+    pub to_block: Option<u64>,
+
+    #[command(flatten)]
+    pub rpc: RpcOpts,
+
+    #[command(flatten)]
+    pub build: CoreBuildArgs,
+}
+
+/// The outcome of running the assertions against a single historical transaction.
+#[derive(Debug, Default)]
+struct TxOutcome {
+    /// Hash of the transaction.
+    hash: TxHash,
+    /// Number of the block the transaction was included in.
+    block: u64,
+    /// Gas used by the transaction.
+    tx_gas: u64,
+    /// Whether the transaction succeeded.
+    tx_success: bool,
+    /// Total gas used by the assertion functions triggered by the transaction.
+    assertion_gas: u64,
+    /// Number of assertion functions triggered by the transaction.
+    assertions_ran: u64,
+    /// Failed assertion functions and their revert reasons.
+    failures: Vec<(String, String)>,
+}
+
+impl TxOutcome {
+    fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl AssertArgs {
+    pub async fn run(self) -> Result<()> {
+        if let (Some(from), Some(to)) = (self.from_block, self.to_block) {
+            eyre::ensure!(from <= to, "--from-block must not be greater than --to-block");
+            eyre::ensure!(
+                to > 0,
+                "cannot run assertions against transactions of the genesis block"
+            );
+        }
+
+        let (config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        let (abi, assertion_code) = self.compile_assertion(&config)?;
+        let fn_names: HashMap<Selector, String> =
+            abi.functions().map(|f| (f.selector(), f.signature())).collect();
+        let revert_decoder = RevertDecoder::new().with_abi(&abi);
+
+        let provider = utils::get_provider(&config)?;
+
+        // Collect the target transactions by block. `None` targets every transaction of the block.
+        let mut targets: BTreeMap<u64, Option<HashSet<TxHash>>> = BTreeMap::new();
+        if let (Some(from), Some(to)) = (self.from_block, self.to_block) {
+            // The genesis block has no transactions to replay.
+            targets.extend((from.max(1)..=to).map(|number| (number, None)));
+        } else {
+            for hash in &self.txs {
+                let tx = provider
+                    .get_transaction_by_hash(*hash)
+                    .await
+                    .wrap_err_with(|| format!("tx not found: {hash:?}"))?
+                    .ok_or_else(|| eyre::eyre!("tx not found: {hash:?}"))?;
+                // System transactions are skipped when replaying the block, see below.
+                eyre::ensure!(
+                    !is_known_system_sender(tx.from) &&
+                        tx.transaction_type() != Some(SYSTEM_TRANSACTION_TYPE),
+                    "cannot run assertions against system transaction {hash:?}"
+                );
+                let number = tx
+                    .block_number
+                    .ok_or_else(|| eyre::eyre!("tx may still be pending: {hash:?}"))?;
+                if let Some(hashes) = targets.entry(number).or_insert_with(|| Some(HashSet::new()))
+                {
+                    hashes.insert(*hash);
+                }
+            }
+        }
+
+        if targets.contains_key(&0) {
+            eyre::bail!("cannot run assertions against transactions of the genesis block");
+        }
+
+        // The assertion contract is deployed once and reused for every block.
+        let mut store = Some(
+            AssertionStore::new_ephemeral()
+                .map_err(|e| eyre::eyre!("Assertion store creation failed: {e}"))?,
+        );
+        let mut assertion_executor = None;
+
+        let pb = init_progress(targets.len() as u64, "blocks");
+        pb.set_position(0);
+
+        let mut outcomes = Vec::new();
+        for (number, hashes) in &targets {
+            let block = provider
+                .get_block((*number).into(), true.into())
+                .await?
+                .ok_or_else(|| eyre::eyre!("block not found: {number}"))?;
+            let BlockTransactions::Full(txs) = &block.transactions else {
+                eyre::bail!("Could not get block txs")
+            };
+
+            // Fork off the parent block and replay the block up to the last target transaction.
+            let mut config = config.clone();
+            config.fork_block_number = Some(number - 1);
+            let create2_deployer = evm_opts.create2_deployer;
+            let (mut env, fork, _, odyssey) =
+                TracingExecutor::get_fork_material(&config, evm_opts.clone()).await?;
+
+            env.block.number = U256::from(*number);
+            env.block.timestamp = U256::from(block.header.timestamp);
+            env.block.coinbase = block.header.beneficiary;
+            env.block.difficulty = block.header.difficulty;
+            env.block.prevrandao = Some(block.header.mix_hash.unwrap_or_default());
+            env.block.basefee = U256::from(block.header.base_fee_per_gas.unwrap_or_default());
+            env.block.gas_limit = U256::from(block.header.gas_limit);
+
+            let executor = TracingExecutor::new(
+                env.clone(),
+                fork,
+                Some(config.evm_version),
+                TraceMode::None,
+                odyssey,
+                create2_deployer,
+            );
+            let mut env = EnvWithHandlerCfg::new_with_spec_id(Box::new(env), executor.spec_id());
+            let mut remaining = hashes.as_ref().map(HashSet::len);
+
+            // Replay the block on a single fork of the parent block state, which the assertions of
+            // every target transaction are executed against.
+            let backend = executor.backend().clone();
+            if let Some(assertion_executor) = assertion_executor.as_mut() {
+                assertion_executor.db = backend;
+            } else {
+                let executor_config = ExecutorConfig {
+                    spec_id: executor.spec_id(),
+                    chain_id: env.cfg.chain_id,
+                    assertion_gas_limit: config.credible.assertion_gas_limit,
+                };
+                let store = store.take().expect("store is only taken once");
+                let assertion_state =
+                    AssertionState::new_active(assertion_code.clone(), &executor_config)
+                        .map_err(|e| eyre::eyre!("Assertion Executor Error: {e:#?}"))?;
+                store
+                    .insert(self.adopter, assertion_state)
+                    .map_err(|e| eyre::eyre!("Assertion insertion failed: {e}"))?;
+                assertion_executor = Some(executor_config.build(backend, store));
+            }
+            let assertion_executor = assertion_executor.as_mut().expect("initialized above");
+            let mut fork_db = ForkDb::new(assertion_executor.db.clone());
+
+            for tx in txs {
+                // System transactions such as on L2s don't contain any pricing info so we skip
+                // them otherwise this would cause reverts
+                if is_known_system_sender(tx.from) ||
+                    tx.transaction_type() == Some(SYSTEM_TRANSACTION_TYPE)
+                {
+                    continue;
+                }
+
+                let is_target = hashes.as_ref().is_none_or(|hashes| hashes.contains(&tx.tx_hash()));
+                configure_tx_env(&mut env, &tx.inner);
+
+                let result = assertion_executor
+                    .validate_transaction(env.block.clone(), env.tx.clone(), &mut fork_db)
+                    .map_err(|e| eyre::eyre!("{e:?}"))
+                    .wrap_err_with(|| {
+                        format!(
+                            "Failed to execute transaction: {:?} in block {number}",
+                            tx.tx_hash()
+                        )
+                    })?;
+                if is_target {
+                    let tx_result = &result.result_and_state.result;
+                    let failures = result
+                        .assertions_executions
+                        .iter()
+                        .flat_map(|assertion_contract| {
+                            assertion_contract.assertion_fns_results.iter().enumerate()
+                        })
+                        .filter(|(_, assertion_fn)| !assertion_fn.is_success())
+                        .map(|(index, assertion_fn)| {
+                            let selector = assertion_fn.id.fn_selector;
+                            let function = fn_names
+                                .get(&selector)
+                                .cloned()
+                                .unwrap_or_else(|| format!("[selector {selector}:index {index}]"));
+                            let output =
+                                assertion_fn.as_result().clone().into_output().unwrap_or_default();
+                            (function, revert_decoder.decode(&output, None))
+                        })
+                        .collect();
+                    outcomes.push(TxOutcome {
+                        hash: tx.tx_hash(),
+                        block: *number,
+                        tx_gas: tx_result.gas_used(),
+                        tx_success: tx_result.is_success(),
+                        assertion_gas: result.total_assertions_gas(),
+                        assertions_ran: result.total_assertion_funcs_ran() as u64,
+                        failures,
+                    });
+                }
+
+                // Advance the fork state past the transaction.
+                fork_db.commit(result.result_and_state.state);
+
+                // Stop replaying the block once all of its target transactions were validated.
+                if let Some(remaining) = remaining.as_mut() {
+                    *remaining -= usize::from(is_target);
+                    if *remaining == 0 {
+                        break;
+                    }
+                }
+            }
+
+            pb.inc(1);
+        }
+        pb.finish_and_clear();
+
+        let failed = outcomes.iter().filter(|outcome| !outcome.is_valid()).count();
+        self.report(&outcomes)?;
+
+        if failed > 0 {
+            eyre::bail!("{failed} transaction(s) invalidated the assertions");
+        }
+        Ok(())
+    }
+
+    /// Compiles the assertion contract and returns its ABI and creation code with the encoded
+    /// constructor arguments.
+    fn compile_assertion(&self, config: &Config) -> Result<(JsonAbi, Bytes)> {
+        let project = config.project()?;
+        let target_path = if let Some(path) = &self.contract.path {
+            canonicalize(project.root().join(path))?
+        } else {
+            project.find_contract_path(&self.contract.name)?
+        };
+        let output = compile::compile_target(&target_path, &project, shell::is_json())?;
+        let (abi, bin, _) = remove_contract(output, &target_path, &self.contract.name)?;
+
+        let BytecodeObject::Bytecode(code) = bin.object else {
+            eyre::bail!("Dynamic linking is not supported for assertion contracts")
+        };
+
+        let code = match &abi.constructor {
+            Some(constructor) => {
+                let args = parse_constructor_args(constructor, &self.constructor_args)?;
+                code.iter().copied().chain(constructor.abi_encode_input(&args)?).collect()
+            }
+            None => {
+                eyre::ensure!(
+                    self.constructor_args.is_empty(),
+                    "{} has no constructor, but constructor arguments were provided",
+                    self.contract.name
+                );
+                code
+            }
+        };
+        Ok((abi, code))
+    }
+
+    /// Prints the outcomes of the validated transactions.
+    fn report(&self, outcomes: &[TxOutcome]) -> Result<()> {
+        // In block range mode only transactions which triggered the assertions are relevant.
+        let outcomes = outcomes
+            .iter()
+            .filter(|outcome| self.from_block.is_none() || outcome.assertions_ran > 0)
+            .collect::<Vec<_>>();
+
+        if shell::is_json() {
+            let json = outcomes
+                .iter()
+                .map(|outcome| {
+                    serde_json::json!({
+                        "tx_hash": outcome.hash,
+                        "block": outcome.block,
+                        "tx_gas": outcome.tx_gas,
+                        "tx_success": outcome.tx_success,
+                        "assertion_gas": outcome.assertion_gas,
+                        "assertions_ran": outcome.assertions_ran,
+                        "valid": outcome.is_valid(),
+                        "failures": outcome
+                            .failures
+                            .iter()
+                            .map(|(function, reason)| {
+                                serde_json::json!({ "function": function, "reason": reason })
+                            })
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            sh_println!("{}", serde_json::to_string(&json)?)?;
+            return Ok(());
+        }
+
+        if outcomes.is_empty() {
+            sh_println!("No transaction triggered the assertions of {}", self.contract.name)?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header([
+            "Transaction",
+            "Block",
+            "Tx Gas",
+            "Assertions Ran",
+            "Assertion Gas",
+            "Result",
+        ]);
+        for outcome in &outcomes {
+            let result = if outcome.is_valid() {
+                Cell::new("PASS").fg(Color::Green)
+            } else {
+                Cell::new("FAIL").fg(Color::Red)
+            };
+            table.add_row([
+                Cell::new(outcome.hash),
+                Cell::new(outcome.block),
+                Cell::new(outcome.tx_gas),
+                Cell::new(outcome.assertions_ran),
+                Cell::new(outcome.assertion_gas),
+                result,
+            ]);
+        }
+        sh_println!("{table}")?;
+
+        for outcome in outcomes.iter().filter(|outcome| !outcome.is_valid()) {
+            sh_println!("\n{} Assertions Failed in {:?}:", self.contract.name, outcome.hash)?;
+            for (function, reason) in &outcome.failures {
+                sh_println!("   └─ {function} - Revert Reason: {reason}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the constructor arguments of the assertion contract.
+fn parse_constructor_args(
+    constructor: &Constructor,
+    constructor_args: &[String],
+) -> Result<Vec<DynSolValue>> {
+    eyre::ensure!(
+        constructor.inputs.len() == constructor_args.len(),
+        "expected {} constructor arguments, got {}",
+        constructor.inputs.len(),
+        constructor_args.len()
+    );
+    let mut params = Vec::with_capacity(constructor.inputs.len());
+    for (input, arg) in constructor.inputs.iter().zip(constructor_args) {
+        // resolve the input type directly
+        let ty = input
+            .resolve()
+            .wrap_err_with(|| format!("Could not resolve constructor arg: input={input}"))?;
+        params.push((ty, arg));
+    }
+    let params = params.iter().map(|(ty, arg)| (ty, arg.as_str()));
+    parse_tokens(params).map_err(Into::into)
+}
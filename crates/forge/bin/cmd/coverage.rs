@@ -6,14 +6,15 @@ use forge::{
     coverage::{
         analysis::{SourceAnalysis, SourceAnalyzer, SourceFile, SourceFiles},
         anchors::find_anchors,
-        BytecodeReporter, ContractId, CoverageReport, CoverageReporter, CoverageSummaryReporter,
-        DebugReporter, ItemAnchor, LcovReporter,
+        check_thresholds, print_threshold_failures, BytecodeReporter, ChangedLines,
+        CoberturaReporter, ContractId, CoverageReport, CoverageReporter, CoverageSummaryReporter,
+        DebugReporter, DiffCoverage, HtmlReporter, ItemAnchor, JsonReporter, LcovReporter,
     },
     opts::EvmOpts,
     utils::IcPcMap,
     MultiContractRunnerBuilder,
 };
-use foundry_cli::utils::{LoadConfig, STATIC_FUZZ_SEED};
+use foundry_cli::utils::{CommandUtils, Git, LoadConfig, STATIC_FUZZ_SEED};
 use foundry_common::{compile::ProjectCompiler, fs};
 use foundry_compilers::{
     artifacts::{
@@ -63,7 +64,8 @@ pub struct CoverageArgs {
 
     /// The path to output the report.
     ///
-    /// If not specified, the report will be stored in the root of the project.
+    /// If not specified, the report will be stored in the root of the project. For the `html`
+    /// report, this is the directory the report is written to.
     #[arg(
         long,
         short,
@@ -72,6 +74,16 @@ pub struct CoverageArgs {
     )]
     report_file: Option<PathBuf>,
 
+    /// Report the coverage of the lines changed relative to the given git revision.
+    ///
+    /// Includes uncommitted changes and untracked files in the working tree.
+    #[arg(long, value_name = "REF")]
+    diff_base: Option<String>,
+
+    /// Fail if less than the given percentage of the changed executable lines is covered.
+    #[arg(long, value_name = "PERCENT", requires = "diff_base")]
+    diff_fail_under: Option<f64>,
+
     /// Whether to include libraries in the coverage report.
     #[arg(long)]
     include_libs: bool,
@@ -309,14 +321,73 @@ impl CoverageArgs {
                     let mut file = io::BufWriter::new(fs::create_file(path)?);
                     LcovReporter::new(&mut file, self.lcov_version.clone()).report(&report)
                 }
+                CoverageReportKind::Cobertura => {
+                    let path =
+                        root.join(self.report_file.as_deref().unwrap_or("cobertura.xml".as_ref()));
+                    let mut file = io::BufWriter::new(fs::create_file(path)?);
+                    CoberturaReporter::new(&mut file, root.clone()).report(&report)
+                }
+                CoverageReportKind::Json => {
+                    let path =
+                        root.join(self.report_file.as_deref().unwrap_or("coverage.json".as_ref()));
+                    let mut file = io::BufWriter::new(fs::create_file(path)?);
+                    JsonReporter::new(&mut file).report(&report)
+                }
                 CoverageReportKind::Bytecode => {
                     let destdir = root.join("bytecode-coverage");
                     fs::create_dir_all(&destdir)?;
                     BytecodeReporter::new(root.clone(), destdir).report(&report)
                 }
+                CoverageReportKind::Html => {
+                    let destdir = root
+                        .join(self.report_file.as_deref().unwrap_or("coverage-report".as_ref()));
+                    HtmlReporter::new(root.clone(), destdir).report(&report)
+                }
                 CoverageReportKind::Debug => DebugReporter.report(&report),
             }?;
         }
+
+        if let Some(base) = &self.diff_base {
+            let diff = Git::new(&root)
+                .cmd()
+                .args(["diff", "--no-color", "--no-ext-diff", "--unified=0", "--relative", base])
+                .get_stdout_lossy()
+                .wrap_err_with(|| format!("failed to diff against `{base}`"))?;
+            let mut changed = ChangedLines::from_git_diff(&diff);
+            let untracked = Git::new(&root)
+                .cmd()
+                .args(["ls-files", "--others", "--exclude-standard"])
+                .get_stdout_lossy()
+                .wrap_err("failed to list untracked files")?;
+            for path in untracked.lines().map(PathBuf::from) {
+                // Binary and unreadable files cannot be sources in the report.
+                if let Ok(source) = std::fs::read_to_string(root.join(&path)) {
+                    changed.add_untracked(path, &source);
+                }
+            }
+            let diff_coverage = DiffCoverage::new(&report, &changed);
+            diff_coverage.print()?;
+
+            if let Some(min) = self.diff_fail_under {
+                let percentage = diff_coverage.percentage();
+                if percentage < min {
+                    eyre::bail!(
+                        "coverage of lines changed since `{base}` is {percentage:.2}%, below the required {min:.2}%"
+                    );
+                }
+            }
+        }
+
+        let failures = check_thresholds(&report, &config.coverage);
+        if !failures.is_empty() {
+            print_threshold_failures(&failures)?;
+            eyre::bail!(
+                "{} coverage threshold{} not met",
+                failures.len(),
+                if failures.len() == 1 { "" } else { "s" }
+            );
+        }
+
         Ok(())
     }
 }
@@ -329,6 +400,9 @@ pub enum CoverageReportKind {
     Lcov,
     Debug,
     Bytecode,
+    Html,
+    Cobertura,
+    Json,
 }
 
 /// Helper function that will link references in unlinked bytecode to the 0 address.
@@ -0,0 +1,126 @@
+use clap::{Parser, ValueEnum, ValueHint};
+use eyre::{Context, Result};
+use forge::lint::{json_report, sarif_report, Finding, Linter, Severity};
+use foundry_cli::utils::{FoundryPathExt, LoadConfig};
+use foundry_common::fs;
+use foundry_compilers::{solc::SOLC_EXTENSIONS, utils::source_files_iter};
+use foundry_config::{filter::expand_globs, impl_figment_convert_basic};
+use rayon::prelude::*;
+use std::path::PathBuf;
+use yansi::Paint;
+
+/// CLI arguments for `forge lint`.
+#[derive(Clone, Debug, Parser)]
+pub struct LintArgs {
+    /// Path to the file or directory to lint.
+    ///
+    /// Defaults to the project's source directory.
+    #[arg(value_hint = ValueHint::FilePath, value_name = "PATH", num_args(1..))]
+    paths: Vec<PathBuf>,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// The format of the findings.
+    #[arg(long, value_enum, default_value = "text")]
+    format: LintFormat,
+}
+
+impl_figment_convert_basic!(LintArgs);
+
+impl LintArgs {
+    /// Lints the sources and returns the number of findings.
+    pub fn run(self) -> Result<usize> {
+        let config = self.try_load_config_emit_warnings()?;
+        let linter = Linter::new(&config.lint)?;
+
+        let ignored = expand_globs(&config.root, config.lint.ignore.iter())?
+            .iter()
+            .flat_map(fs::canonicalize_path)
+            .collect::<Vec<_>>();
+
+        let mut inputs = Vec::new();
+        if self.paths.is_empty() {
+            inputs.extend(source_files_iter(config.root.join(&config.src), SOLC_EXTENSIONS));
+        }
+        for path in &self.paths {
+            if path.is_dir() {
+                inputs.extend(source_files_iter(path, SOLC_EXTENSIONS));
+            } else if path.is_sol() {
+                inputs.push(path.clone());
+            } else {
+                warn!("Cannot process path {}", path.display());
+            }
+        }
+        inputs
+            .retain(|path| !fs::canonicalize_path(path).is_ok_and(|path| ignored.contains(&path)));
+
+        let cwd = std::env::current_dir()?;
+        let findings = inputs
+            .par_iter()
+            .map(|path| {
+                let source = fs::read_to_string(path)?;
+                let path = if path.is_absolute() { path.clone() } else { cwd.join(path) };
+                let name = path.strip_prefix(&config.root).unwrap_or(&path);
+                linter.lint(name, &source).wrap_err_with(|| {
+                    format!("Failed to lint Solidity code for {}", name.display())
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        match self.format {
+            LintFormat::Text => {
+                for finding in &findings {
+                    print_finding(finding)?;
+                }
+                if !findings.is_empty() {
+                    sh_println!("{} findings", findings.len())?;
+                }
+            }
+            LintFormat::Json => sh_println!("{}", serde_json::to_string(&json_report(&findings))?)?,
+            LintFormat::Sarif => {
+                sh_println!("{}", serde_json::to_string_pretty(&sarif_report(&findings))?)?
+            }
+        }
+
+        Ok(findings.len())
+    }
+}
+
+fn print_finding(finding: &Finding) -> Result<()> {
+    let severity = finding.lint.severity();
+    let label = format!("{severity}[{}]", finding.lint);
+    let label = match severity {
+        Severity::High => label.red(),
+        Severity::Medium => label.yellow(),
+        Severity::Low | Severity::Info => label.cyan(),
+    };
+    sh_println!("{}: {}", label.bold(), finding.message)?;
+    sh_println!(
+        "  {} {}:{}:{}\n",
+        "-->".blue(),
+        finding.path.display(),
+        finding.line,
+        finding.column
+    )?;
+    Ok(())
+}
+
+/// Output formats of the lint findings.
+#[derive(Clone, Debug, Default, ValueEnum)]
+pub enum LintFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A JSON array of findings.
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning tools.
+    Sarif,
+}
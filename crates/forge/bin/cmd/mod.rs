@@ -39,6 +39,7 @@
 //! let config: Config = From::from(&args);
 //! ```
 
+pub mod assert;
 pub mod bind;
 pub mod bind_json;
 pub mod build;
@@ -58,8 +59,11 @@ pub mod generate;
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod lint;
+pub mod mutate;
 pub mod remappings;
 pub mod remove;
+pub mod replay_assertion;
 pub mod selectors;
 pub mod snapshot;
 pub mod soldeer;
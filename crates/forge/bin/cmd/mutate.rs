@@ -0,0 +1,288 @@
+use super::{install, test::TestArgs};
+use alloy_primitives::U256;
+use clap::{Parser, ValueHint};
+use eyre::{Context, Result};
+use forge::{
+    mutation::{find_mutants, sources_hash, MutantStatus, MutationCache},
+    opts::EvmOpts,
+    revm::primitives::Env,
+    MultiContractRunnerBuilder, TestFilter,
+};
+use foundry_cli::utils::{LoadConfig, STATIC_FUZZ_SEED};
+use foundry_common::{compile::with_compilation_reporter, fs};
+use foundry_compilers::{
+    artifacts::{Source, Sources},
+    compilers::multi::{MultiCompiler, MultiCompilerParsedSource},
+    project::ProjectCompiler,
+    Graph, Project,
+};
+use foundry_config::Config;
+use futures::{stream, StreamExt};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use yansi::Paint;
+
+foundry_config::impl_figment_convert!(MutateArgs, test);
+
+/// CLI arguments for `forge mutate`.
+///
+/// Generates mutants of the source files and runs the tests that import each mutated file against
+/// it. A mutant that no test catches has survived, which points to untested behavior.
+#[derive(Clone, Debug, Parser)]
+pub struct MutateArgs {
+    /// The source files to mutate.
+    ///
+    /// This flag can be used multiple times. Defaults to all Solidity files in the project's
+    /// source directory.
+    #[arg(long = "mutate-path", value_hint = ValueHint::FilePath, value_name = "PATH")]
+    mutate_paths: Vec<PathBuf>,
+
+    /// The number of mutants to test in parallel.
+    ///
+    /// Defaults to the number of available CPUs.
+    #[arg(long, value_name = "JOBS")]
+    jobs: Option<usize>,
+
+    /// Test all mutants, ignoring the outcomes cached by previous runs.
+    #[arg(long)]
+    no_mutation_cache: bool,
+
+    #[command(flatten)]
+    test: TestArgs,
+}
+
+impl MutateArgs {
+    pub async fn run(self) -> Result<()> {
+        let (mut config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+
+        // install missing dependencies
+        if install::install_missing_dependencies(&mut config) && config.auto_detect_remappings {
+            // need to re-configure here to also catch additional remappings
+            config = self.load_config();
+        }
+
+        // Set fuzz seed so every mutant is tested against the same inputs
+        config.fuzz.seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
+
+        let config = Arc::new(config);
+        let project = config.ephemeral_no_artifacts_project()?;
+        let root = project.paths.root.clone();
+        let sources = project.paths.read_input_files()?;
+
+        let targets = if self.mutate_paths.is_empty() {
+            sources
+                .keys()
+                .filter(|path| {
+                    path.starts_with(&project.paths.sources) &&
+                        path.extension().is_some_and(|ext| ext == "sol")
+                })
+                .cloned()
+                .collect()
+        } else {
+            self.mutate_paths
+                .iter()
+                .map(|path| {
+                    fs::canonicalize_path(path)
+                        .wrap_err_with(|| format!("invalid path `{}`", path.display()))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let graph =
+            Graph::<MultiCompilerParsedSource>::resolve_sources(&project.paths, sources.clone())?;
+
+        let mut mutants = Vec::new();
+        for target in &targets {
+            let Some(source) = sources.get(target) else {
+                eyre::bail!("`{}` is not a source file of the project", target.display());
+            };
+            let path = target.strip_prefix(&root).unwrap_or(target);
+            let target_mutants = find_mutants(path, &source.content)?;
+            if target_mutants.is_empty() {
+                continue
+            }
+
+            // The tests that can catch the mutants are the ones that import the mutated file.
+            let tests: HashSet<PathBuf> = graph
+                .files()
+                .keys()
+                .filter(|file| *file == target || graph.imports(file).contains(target))
+                .map(|file| file.strip_prefix(&root).unwrap_or(file).to_path_buf())
+                .collect();
+            let tests = Arc::new(tests);
+            mutants.extend(target_mutants.into_iter().map(|mutant| (mutant, tests.clone())));
+        }
+
+        let verbosity = evm_opts.verbosity;
+        let env = evm_opts.evm_env().await?;
+        let runner = Arc::new(MutantRunner {
+            project,
+            config: config.clone(),
+            evm_opts,
+            env,
+            filter: Arc::new(self.test.filter(&config)),
+        });
+
+        sh_println!("Running tests against the unmutated sources...")?;
+        let baseline = runner.clone();
+        let baseline_sources = sources.clone();
+        match tokio::task::spawn_blocking(move || baseline.run(baseline_sources, None)).await?? {
+            MutantStatus::Survived => {}
+            MutantStatus::Killed => eyre::bail!("tests must pass before mutation testing"),
+            MutantStatus::Invalid => eyre::bail!("project failed to compile"),
+        }
+
+        let sources_hash = sources_hash(sources.iter().map(|(path, source)| {
+            (path.strip_prefix(&root).unwrap_or(path), source.content.as_str())
+        }));
+        let cache_path = config.cache_path.join(MutationCache::FILE_NAME);
+        let mut cache = if self.no_mutation_cache {
+            MutationCache::default()
+        } else {
+            MutationCache::read(&cache_path)
+        };
+
+        let mut results = Vec::with_capacity(mutants.len());
+        let mut pending = Vec::new();
+        for (mutant, tests) in mutants {
+            match cache.mutants.get(&mutant.cache_key(sources_hash)) {
+                Some(status) => results.push((mutant, *status)),
+                None => pending.push((mutant, tests)),
+            }
+        }
+
+        sh_println!(
+            "Testing {} mutants of {} files ({} cached)...",
+            results.len() + pending.len(),
+            targets.len(),
+            results.len()
+        )?;
+
+        let jobs = self
+            .jobs
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+            .unwrap_or(1)
+            .max(1);
+        let tested = stream::iter(pending.into_iter().map(|(mutant, tests)| {
+            let runner = runner.clone();
+            let mut mutated = sources.clone();
+            let path = root.join(&mutant.path);
+            let content = mutant.apply(&mutated[&path].content);
+            mutated.insert(path, Source::new(content));
+            async move {
+                let status =
+                    tokio::task::spawn_blocking(move || runner.run(mutated, Some(tests.as_ref())))
+                        .await??;
+                eyre::Ok((mutant, status))
+            }
+        }))
+        .buffer_unordered(jobs)
+        .collect::<Vec<_>>()
+        .await;
+
+        for result in tested {
+            let (mutant, status) = result?;
+            cache.mutants.insert(mutant.cache_key(sources_hash), status);
+            results.push((mutant, status));
+        }
+        cache.write(&cache_path)?;
+
+        results.sort_by(|(a, _), (b, _)| {
+            (&a.path, a.span.start, a.kind).cmp(&(&b.path, b.span.start, b.kind))
+        });
+
+        let (mut killed, mut survived, mut invalid) = (0, 0, 0);
+        for (mutant, status) in &results {
+            match status {
+                MutantStatus::Killed => killed += 1,
+                MutantStatus::Survived => survived += 1,
+                MutantStatus::Invalid => invalid += 1,
+            }
+            let show = match status {
+                MutantStatus::Survived => true,
+                MutantStatus::Killed => verbosity >= 2,
+                MutantStatus::Invalid => verbosity >= 3,
+            };
+            if show {
+                let label = format!("[{status}]");
+                let label = match status {
+                    MutantStatus::Killed => label.green(),
+                    MutantStatus::Survived => label.red(),
+                    MutantStatus::Invalid => label.dim(),
+                };
+                sh_println!("{label} {mutant}")?;
+            }
+        }
+
+        let score = if killed + survived == 0 {
+            100.
+        } else {
+            killed as f64 / (killed + survived) as f64 * 100.
+        };
+        sh_println!(
+            "\nMutation testing: {killed} killed, {survived} survived, {invalid} invalid; mutation score {score:.2}%"
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Compiles and tests mutated projects.
+struct MutantRunner {
+    project: Project,
+    config: Arc<Config>,
+    evm_opts: EvmOpts,
+    env: Env,
+    filter: Arc<dyn TestFilter>,
+}
+
+impl MutantRunner {
+    /// Compiles the sources and runs the tests matching the filter, restricted to the given test
+    /// files if any.
+    ///
+    /// Returns [`MutantStatus::Killed`] if any test failed, and [`MutantStatus::Invalid`] if the
+    /// sources failed to compile.
+    fn run(&self, sources: Sources, tests: Option<&HashSet<PathBuf>>) -> Result<MutantStatus> {
+        let output = with_compilation_reporter(true, || {
+            ProjectCompiler::with_sources(&self.project, sources)?.compile()
+        })?;
+        if output.has_compiler_errors() {
+            return Ok(MutantStatus::Invalid)
+        }
+
+        let root = &self.project.paths.root;
+        let mut runner = MultiContractRunnerBuilder::new(self.config.clone())
+            .initial_balance(self.evm_opts.initial_balance)
+            .evm_spec(self.config.evm_spec_id())
+            .sender(self.evm_opts.sender)
+            .with_fork(self.evm_opts.get_fork(&self.config, self.env.clone()))
+            .build::<MultiCompiler>(root, &output, self.env.clone(), self.evm_opts.clone())?;
+
+        let filter = MutantTestFilter { filter: self.filter.as_ref(), tests };
+        let killed = runner.test_collect(&filter).values().any(|suite| suite.failed() > 0);
+        Ok(if killed { MutantStatus::Killed } else { MutantStatus::Survived })
+    }
+}
+
+/// Restricts a test filter to the test files that import a mutated file.
+struct MutantTestFilter<'a> {
+    filter: &'a dyn TestFilter,
+    tests: Option<&'a HashSet<PathBuf>>,
+}
+
+impl TestFilter for MutantTestFilter<'_> {
+    fn matches_test(&self, test_name: &str) -> bool {
+        self.filter.matches_test(test_name)
+    }
+
+    fn matches_contract(&self, contract_name: &str) -> bool {
+        self.filter.matches_contract(contract_name)
+    }
+
+    fn matches_path(&self, path: &Path) -> bool {
+        self.filter.matches_path(path) && self.tests.is_none_or(|tests| tests.contains(path))
+    }
+}
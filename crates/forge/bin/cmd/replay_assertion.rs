@@ -0,0 +1,115 @@
+use clap::{Parser, ValueHint};
+use eyre::Result;
+use foundry_common::shell;
+use foundry_evm::{
+    inspectors::cheatcodes::{AssertionReplay, ReplayedTransaction},
+    traces::{
+        decode_trace_arena, render_trace_arena_inner, CallTraceDecoder, CallTraceDecoderBuilder,
+        SparsedTraceArena, TraceMode,
+    },
+};
+use std::path::PathBuf;
+use yansi::Paint;
+
+/// CLI arguments for `forge replay-assertion`.
+///
+/// Re-runs a failed `assertionEx` call from the replay file written by the test, against the
+/// recorded pre-state and without network access, and prints the call traces of the triggering
+/// transactions and the assertion functions.
+#[derive(Clone, Debug, Parser)]
+pub struct ReplayAssertionArgs {
+    /// Path to the replay file.
+    #[arg(value_hint = ValueHint::FilePath, value_name = "PATH")]
+    pub path: PathBuf,
+}
+
+impl ReplayAssertionArgs {
+    pub async fn run(self) -> Result<()> {
+        let replay = AssertionReplay::read(&self.path)?;
+        let replayed = replay.run(TraceMode::Call.into_config())?;
+
+        let mut builder = CallTraceDecoderBuilder::new().with_labels(
+            replay
+                .assertions
+                .iter()
+                .map(|assertion| (assertion.address(), assertion.label.clone())),
+        );
+        for abi in replay.assertions.iter().filter_map(|assertion| assertion.abi.as_ref()) {
+            builder = builder.with_abi(abi);
+        }
+        let decoder = builder.build();
+
+        let failed = replayed
+            .iter()
+            .any(|tx| !tx.success || tx.assertions.iter().any(|result| !result.success));
+
+        if shell::is_json() {
+            let transactions = replayed
+                .iter()
+                .map(|tx| {
+                    serde_json::json!({
+                        "success": tx.success,
+                        "gas_used": tx.gas_used,
+                        "assertions": tx.assertions,
+                    })
+                })
+                .collect::<Vec<_>>();
+            sh_println!("{}", serde_json::to_string(&transactions)?)?;
+        } else {
+            print_replayed(replay.transactions.len() > 1, replayed, &decoder).await?;
+        }
+
+        if failed {
+            eyre::bail!("assertion replay failed");
+        }
+        Ok(())
+    }
+}
+
+/// Prints the outcome and the call traces of each replayed transaction.
+async fn print_replayed(
+    is_bundle: bool,
+    replayed: Vec<ReplayedTransaction>,
+    decoder: &CallTraceDecoder,
+) -> Result<()> {
+    for (tx_index, tx) in replayed.into_iter().enumerate() {
+        let tx_label =
+            if is_bundle { format!("Transaction #{tx_index}") } else { "Transaction".to_string() };
+        let status = if tx.success { "succeeded".green() } else { "reverted".red() };
+        sh_println!("{tx_label} {status} (gas: {})", tx.gas_used)?;
+
+        sh_println!("Traces:")?;
+        for mut arena in tx.traces.into_iter().chain(tx.assertion_traces) {
+            decode_trace_arena(&mut arena, decoder).await?;
+            let arena = SparsedTraceArena { arena, ignored: Default::default() };
+            sh_println!("{}", render_trace_arena_inner(&arena, false, false))?;
+        }
+
+        for result in &tx.assertions {
+            let function = result
+                .function_name
+                .clone()
+                .unwrap_or_else(|| format!("[selector {}]", result.selector));
+            if result.success {
+                sh_println!(
+                    "{} {}::{function} (gas: {})",
+                    "[PASS]".green(),
+                    result.label,
+                    result.gas_used
+                )?;
+            } else {
+                sh_println!(
+                    "{} {}::{function} (gas: {})",
+                    format!(
+                        "[FAIL: {}]",
+                        result.revert_reason.as_deref().unwrap_or("unknown revert reason")
+                    )
+                    .red(),
+                    result.label,
+                    result.gas_used
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -22,7 +22,7 @@ use foundry_cli::{
 };
 use foundry_common::{compile::ProjectCompiler, evm::EvmArgs, fs, shell, TestFunctionExt};
 use foundry_compilers::{
-    artifacts::output_selection::OutputSelection,
+    artifacts::output_selection::{ContractOutputSelection, OutputSelection},
     compilers::{
         multi::{MultiCompiler, MultiCompilerLanguage},
         Language,
@@ -153,6 +153,12 @@ pub struct TestArgs {
     #[arg(long)]
     pub fuzz_input_file: Option<String>,
 
+    /// Run `check_` tests, exploring them symbolically.
+    ///
+    /// Path conditions are discharged to the SMT solver set in the `symbolic` config.
+    #[arg(long)]
+    pub symbolic: bool,
+
     /// Show test execution progress.
     #[arg(long, conflicts_with_all = ["quiet", "json"], help_heading = "Display options")]
     pub show_progress: bool,
@@ -223,7 +229,7 @@ impl TestArgs {
         // Filter sources by their abis and contract names.
         let mut test_sources = abis
             .iter()
-            .filter(|(id, abi)| matches_contract(id, abi, filter))
+            .filter(|(id, abi)| matches_contract(id, abi, filter, config.symbolic.enabled))
             .map(|(id, _)| id.source.clone())
             .collect::<BTreeSet<_>>();
 
@@ -290,6 +296,13 @@ impl TestArgs {
             config = self.load_config();
         }
 
+        // Storage layouts name the slots in the state diffs of invariant counterexamples.
+        if config.invariant.show_state_diffs &&
+            !config.extra_output.contains(&ContractOutputSelection::StorageLayout)
+        {
+            config.extra_output.push(ContractOutputSelection::StorageLayout);
+        }
+
         // Set up the project.
         let project = config.project()?;
 
@@ -671,6 +684,7 @@ impl TestArgs {
 
                 if let Some(gas_report) = &mut gas_report {
                     gas_report.analyze(result.traces.iter().map(|(_, a)| &a.arena), &decoder).await;
+                    gas_report.analyze_assertions(result.assertion_results.iter().flatten());
 
                     for trace in result.gas_report_traces.iter() {
                         decoder.clear_addresses();
@@ -870,6 +884,12 @@ impl Provider for TestArgs {
         }
         dict.insert("fuzz".to_string(), fuzz_dict.into());
 
+        if self.symbolic {
+            let mut symbolic_dict = Dict::default();
+            symbolic_dict.insert("enabled".to_string(), true.into());
+            dict.insert("symbolic".to_string(), symbolic_dict.into());
+        }
+
         if let Some(etherscan_api_key) =
             self.etherscan_api_key.as_ref().filter(|s| !s.trim().is_empty())
         {
@@ -916,7 +936,7 @@ fn persist_run_failures(config: &Config, outcome: &TestOutcome) {
         let mut filter = String::new();
         let mut failures = outcome.failures().peekable();
         while let Some((test_name, _)) = failures.next() {
-            if test_name.is_any_test() {
+            if test_name.is_any_test() || test_name.is_symbolic_test() {
                 if let Some(test_match) = test_name.split("(").next() {
                     filter.push_str(test_match);
                     if failures.peek().is_some() {
@@ -50,6 +50,9 @@ fn run() -> Result<()> {
         }
         ForgeSubcommand::Script(cmd) => utils::block_on(cmd.run_script()),
         ForgeSubcommand::Coverage(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::Mutate(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::Assert(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::ReplayAssertion(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Bind(cmd) => cmd.run(),
         ForgeSubcommand::Build(cmd) => {
             if cmd.is_watch() {
@@ -100,6 +103,12 @@ fn run() -> Result<()> {
             }
         }
         ForgeSubcommand::Fmt(cmd) => cmd.run(),
+        ForgeSubcommand::Lint(cmd) => {
+            if cmd.run()? > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         ForgeSubcommand::Config(cmd) => cmd.run(),
         ForgeSubcommand::Flatten(cmd) => cmd.run(),
         ForgeSubcommand::Inspect(cmd) => cmd.run(),
@@ -1,9 +1,10 @@
 use crate::cmd::{
-    bind::BindArgs, bind_json, build::BuildArgs, cache::CacheArgs, clone::CloneArgs,
-    compiler::CompilerArgs, config, coverage, create::CreateArgs, debug::DebugArgs, doc::DocArgs,
-    eip712, flatten, fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs,
-    remappings::RemappingArgs, remove::RemoveArgs, selectors::SelectorsSubcommands, snapshot,
-    soldeer, test, tree, update,
+    assert::AssertArgs, bind::BindArgs, bind_json, build::BuildArgs, cache::CacheArgs,
+    clone::CloneArgs, compiler::CompilerArgs, config, coverage, create::CreateArgs,
+    debug::DebugArgs, doc::DocArgs, eip712, flatten, fmt::FmtArgs, geiger, generate,
+    init::InitArgs, inspect, install::InstallArgs, lint, mutate, remappings::RemappingArgs,
+    remove::RemoveArgs, replay_assertion::ReplayAssertionArgs, selectors::SelectorsSubcommands,
+    snapshot, soldeer, test, tree, update,
 };
 use clap::{Parser, Subcommand, ValueHint};
 use forge_script::ScriptArgs;
@@ -50,6 +51,15 @@ pub enum ForgeSubcommand {
     /// Generate coverage reports.
     Coverage(coverage::CoverageArgs),
 
+    /// Run mutation tests to find source changes that no test catches.
+    Mutate(mutate::MutateArgs),
+
+    /// Run assertions against historical transactions.
+    Assert(AssertArgs),
+
+    /// Re-run a failed `assertionEx` call from its replay file.
+    ReplayAssertion(ReplayAssertionArgs),
+
     /// Generate Rust bindings for smart contracts.
     #[command(alias = "bi")]
     Bind(BindArgs),
@@ -144,6 +154,9 @@ pub enum ForgeSubcommand {
     /// Format Solidity source files.
     Fmt(FmtArgs),
 
+    /// Lint Solidity source files.
+    Lint(lint::LintArgs),
+
     /// Get specialized information about a smart contract.
     #[command(visible_alias = "in")]
     Inspect(inspect::InspectArgs),
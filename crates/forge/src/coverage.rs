@@ -4,9 +4,10 @@ use alloy_primitives::map::HashMap;
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, Attribute, Cell, Color, Row, Table};
 use evm_disassembler::disassemble_bytes;
 use foundry_common::fs;
+use foundry_config::{CoverageConfig, CoverageThresholds};
 use semver::Version;
 use std::{
-    collections::hash_map,
+    collections::{hash_map, BTreeMap, BTreeSet},
     io::Write,
     path::{Path, PathBuf},
 };
@@ -169,6 +170,477 @@ impl CoverageReporter for LcovReporter<'_> {
     }
 }
 
+/// Writes the coverage report in the [Cobertura] XML format.
+///
+/// Source files are reported as classes, grouped into packages by their parent directory.
+///
+/// [Cobertura]: https://cobertura.github.io/cobertura/
+pub struct CoberturaReporter<'a> {
+    out: &'a mut (dyn Write + 'a),
+    root: PathBuf,
+}
+
+impl<'a> CoberturaReporter<'a> {
+    /// Create a new Cobertura reporter for sources relative to `root`.
+    pub fn new(out: &'a mut (dyn Write + 'a), root: PathBuf) -> Self {
+        Self { out, root }
+    }
+}
+
+impl CoverageReporter for CoberturaReporter<'_> {
+    fn report(mut self, report: &CoverageReport) -> eyre::Result<()> {
+        let mut packages: BTreeMap<String, Vec<(&Path, Vec<&CoverageItem>)>> = BTreeMap::new();
+        let mut total = CoverageSummary::default();
+        for (path, items) in report.items_by_file() {
+            total.add_items(items.iter().copied());
+            let package = path.parent().map(|p| p.display().to_string()).unwrap_or_default();
+            packages.entry(package).or_default().push((path, items));
+        }
+
+        writeln!(self.out, r#"<?xml version="1.0" ?>"#)?;
+        writeln!(
+            self.out,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        )?;
+        writeln!(
+            self.out,
+            r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="{}" timestamp="{}">"#,
+            rate(total.line_hits, total.line_count),
+            rate(total.branch_hits, total.branch_count),
+            total.line_hits,
+            total.line_count,
+            total.branch_hits,
+            total.branch_count,
+            env!("CARGO_PKG_VERSION"),
+            report_timestamp(),
+        )?;
+        writeln!(self.out, "  <sources>")?;
+        writeln!(
+            self.out,
+            "    <source>{}</source>",
+            escape_markup(&self.root.display().to_string())
+        )?;
+        writeln!(self.out, "  </sources>")?;
+
+        writeln!(self.out, "  <packages>")?;
+        for (package, files) in &packages {
+            let mut summary = CoverageSummary::default();
+            for (_, items) in files {
+                summary.add_items(items.iter().copied());
+            }
+            writeln!(
+                self.out,
+                r#"    <package name="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                escape_markup(package),
+                rate(summary.line_hits, summary.line_count),
+                rate(summary.branch_hits, summary.branch_count),
+            )?;
+            writeln!(self.out, "      <classes>")?;
+            for (path, items) in files {
+                self.write_class(path, items)?;
+            }
+            writeln!(self.out, "      </classes>")?;
+            writeln!(self.out, "    </package>")?;
+        }
+        writeln!(self.out, "  </packages>")?;
+        writeln!(self.out, "</coverage>")?;
+
+        sh_println!("Wrote Cobertura report.")?;
+
+        Ok(())
+    }
+}
+
+impl CoberturaReporter<'_> {
+    fn write_class(&mut self, path: &Path, items: &[&CoverageItem]) -> eyre::Result<()> {
+        let summary = CoverageSummary::from_items(items.iter().copied());
+
+        // Line hits and the branch paths starting on each line, by line number.
+        let mut lines: BTreeMap<u32, (u32, Vec<u32>)> = BTreeMap::new();
+        for item in items {
+            let line = item.loc.lines.start;
+            match item.kind {
+                CoverageItemKind::Line => {
+                    let (hits, _) = lines.entry(line).or_default();
+                    *hits = (*hits).max(item.hits);
+                }
+                CoverageItemKind::Branch { .. } => {
+                    lines.entry(line).or_default().1.push(item.hits);
+                }
+                _ => {}
+            }
+        }
+
+        let name = escape_markup(&path.display().to_string());
+        writeln!(
+            self.out,
+            r#"        <class name="{name}" filename="{name}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            rate(summary.line_hits, summary.line_count),
+            rate(summary.branch_hits, summary.branch_count),
+        )?;
+
+        writeln!(self.out, "          <methods>")?;
+        for item in items {
+            let CoverageItemKind::Function { ref name } = item.kind else { continue };
+            let line = item.loc.lines.start;
+            writeln!(
+                self.out,
+                r#"            <method name="{}" signature="" line-rate="{}" branch-rate="1">"#,
+                escape_markup(&format!("{}.{name}", item.loc.contract_name)),
+                if item.hits == 0 { "0" } else { "1" },
+            )?;
+            writeln!(self.out, "              <lines>")?;
+            writeln!(
+                self.out,
+                r#"                <line number="{line}" hits="{}" branch="false"/>"#,
+                item.hits
+            )?;
+            writeln!(self.out, "              </lines>")?;
+            writeln!(self.out, "            </method>")?;
+        }
+        writeln!(self.out, "          </methods>")?;
+
+        writeln!(self.out, "          <lines>")?;
+        for (line, (hits, branches)) in &lines {
+            if branches.is_empty() {
+                writeln!(
+                    self.out,
+                    r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                )?;
+            } else {
+                let covered = branches.iter().filter(|hits| **hits > 0).count();
+                let percentage = covered * 100 / branches.len();
+                writeln!(
+                    self.out,
+                    r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{percentage}% ({covered}/{})"/>"#,
+                    branches.len()
+                )?;
+            }
+        }
+        writeln!(self.out, "          </lines>")?;
+        writeln!(self.out, "        </class>")?;
+        Ok(())
+    }
+}
+
+/// Returns the timestamp of the report in milliseconds.
+///
+/// The wall clock would make reports of identical runs differ, so the timestamp is taken from
+/// `SOURCE_DATE_EPOCH` if set, and is `0` otherwise.
+fn report_timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<u64>().ok())
+        .map_or(0, |epoch| epoch.saturating_mul(1000))
+}
+
+/// Returns the ratio of hits to total as a Cobertura rate, where an empty total is fully covered.
+fn rate(hits: usize, total: usize) -> String {
+    let rate = if total == 0 { 1. } else { hits as f64 / total as f64 };
+    format!("{rate:.4}")
+}
+
+/// Writes the coverage report as JSON.
+///
+/// The report contains the coverage summary of every source file and of the entire project, and
+/// every coverage item with its hit count and source location.
+pub struct JsonReporter<'a> {
+    out: &'a mut (dyn Write + 'a),
+}
+
+impl<'a> JsonReporter<'a> {
+    /// Create a new JSON reporter.
+    pub fn new(out: &'a mut (dyn Write + 'a)) -> Self {
+        Self { out }
+    }
+}
+
+impl CoverageReporter for JsonReporter<'_> {
+    fn report(self, report: &CoverageReport) -> eyre::Result<()> {
+        let mut total = CoverageSummary::default();
+        let mut files = Vec::new();
+        for (path, items) in report.items_by_file() {
+            let summary = CoverageSummary::from_items(items.iter().copied());
+            total.merge(&summary);
+            files.push(serde_json::json!({
+                "path": path,
+                "summary": summary_json(&summary),
+                "items": items.iter().map(|item| item_json(item)).collect::<Vec<_>>(),
+            }));
+        }
+
+        let json = serde_json::json!({
+            "files": files,
+            "total": summary_json(&total),
+        });
+        serde_json::to_writer_pretty(&mut *self.out, &json)?;
+        writeln!(self.out)?;
+
+        sh_println!("Wrote JSON report.")?;
+
+        Ok(())
+    }
+}
+
+fn summary_json(summary: &CoverageSummary) -> serde_json::Value {
+    serde_json::json!({
+        "lines": { "hits": summary.line_hits, "total": summary.line_count },
+        "statements": { "hits": summary.statement_hits, "total": summary.statement_count },
+        "branches": { "hits": summary.branch_hits, "total": summary.branch_count },
+        "functions": { "hits": summary.function_hits, "total": summary.function_count },
+    })
+}
+
+fn item_json(item: &CoverageItem) -> serde_json::Value {
+    let mut json = match &item.kind {
+        CoverageItemKind::Line => serde_json::json!({ "kind": "line" }),
+        CoverageItemKind::Statement => serde_json::json!({ "kind": "statement" }),
+        CoverageItemKind::Branch { branch_id, path_id, .. } => serde_json::json!({
+            "kind": "branch",
+            "branchId": branch_id,
+            "pathId": path_id,
+        }),
+        CoverageItemKind::Function { name } => {
+            serde_json::json!({ "kind": "function", "name": name })
+        }
+    };
+    json["contract"] = item.loc.contract_name.as_ref().into();
+    json["hits"] = item.hits.into();
+    // `lines` is half-open, so we need to subtract 1 to get the last included line.
+    json["lines"] =
+        serde_json::json!({ "start": item.loc.lines.start, "end": item.loc.lines.end - 1 });
+    json["bytes"] = serde_json::json!({ "start": item.loc.bytes.start, "end": item.loc.bytes.end });
+    json
+}
+
+/// The lines added or modified relative to a base revision, by source file path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangedLines(pub BTreeMap<PathBuf, BTreeSet<u32>>);
+
+impl ChangedLines {
+    /// Parses the changed lines from the output of `git diff --unified=0`.
+    ///
+    /// Only the lines of the new revision are collected; removed lines are not part of the
+    /// coverage report.
+    pub fn from_git_diff(diff: &str) -> Self {
+        let mut changed = BTreeMap::<PathBuf, BTreeSet<u32>>::new();
+        let mut current = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // Deleted files have no new revision.
+                current = path.strip_prefix("b/").map(PathBuf::from);
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                let Some(path) = &current else { continue };
+                // `@@ -<start>[,<count>] +<start>[,<count>] @@`
+                let Some(new) = hunk.split_whitespace().find_map(|s| s.strip_prefix('+')) else {
+                    continue
+                };
+                let (start, count) = match new.split_once(',') {
+                    Some((start, count)) => (start.parse::<u32>(), count.parse::<u32>()),
+                    None => (new.parse::<u32>(), Ok(1)),
+                };
+                let (Ok(start), Ok(count)) = (start, count) else { continue };
+                changed.entry(path.clone()).or_default().extend(start..start + count);
+            }
+        }
+        Self(changed)
+    }
+
+    /// Marks every line of a file which is not tracked by git as changed.
+    ///
+    /// `git diff` only lists tracked files, so new files have to be added separately.
+    pub fn add_untracked(&mut self, path: PathBuf, source: &str) {
+        let line_count = source.lines().count() as u32;
+        self.0.entry(path).or_default().extend(1..=line_count);
+    }
+
+    /// Returns the changed lines of the source file, if any.
+    pub fn get(&self, path: &Path) -> Option<&BTreeSet<u32>> {
+        self.0.get(path)
+    }
+}
+
+/// The coverage of the executable lines changed relative to a base revision.
+#[derive(Clone, Debug, Default)]
+pub struct DiffCoverage {
+    /// The changed line coverage by source file, for files with changed executable lines.
+    pub files: Vec<DiffCoverageFile>,
+}
+
+/// The coverage of the executable lines changed in a source file.
+#[derive(Clone, Debug)]
+pub struct DiffCoverageFile {
+    /// The path of the source file.
+    pub path: PathBuf,
+    /// The number of changed executable lines.
+    pub line_count: usize,
+    /// The number of changed executable lines that were hit.
+    pub line_hits: usize,
+    /// The changed executable lines that were not hit.
+    pub uncovered: Vec<u32>,
+}
+
+impl DiffCoverage {
+    /// Computes the coverage of the changed lines from the line items of the report.
+    ///
+    /// Changed lines which are not executable, such as comments and declarations, are ignored.
+    pub fn new(report: &CoverageReport, changed: &ChangedLines) -> Self {
+        let mut files = Vec::new();
+        for (path, items) in report.items_by_file() {
+            let Some(changed_lines) = changed.get(path) else { continue };
+
+            let mut line_hits = BTreeMap::<u32, u32>::new();
+            for item in items {
+                if matches!(item.kind, CoverageItemKind::Line) &&
+                    changed_lines.contains(&item.loc.lines.start)
+                {
+                    let hits = line_hits.entry(item.loc.lines.start).or_default();
+                    *hits = (*hits).max(item.hits);
+                }
+            }
+            if line_hits.is_empty() {
+                continue
+            }
+
+            let uncovered: Vec<_> =
+                line_hits.iter().filter(|(_, hits)| **hits == 0).map(|(line, _)| *line).collect();
+            files.push(DiffCoverageFile {
+                path: path.to_path_buf(),
+                line_count: line_hits.len(),
+                line_hits: line_hits.len() - uncovered.len(),
+                uncovered,
+            });
+        }
+        Self { files }
+    }
+
+    /// Returns the number of changed executable lines that were hit, and the total number of
+    /// changed executable lines.
+    pub fn total(&self) -> (usize, usize) {
+        self.files
+            .iter()
+            .fold((0, 0), |(hits, count), file| (hits + file.line_hits, count + file.line_count))
+    }
+
+    /// Returns the percentage of changed executable lines that were hit.
+    ///
+    /// A change without executable lines is considered fully covered.
+    pub fn percentage(&self) -> f64 {
+        let (hits, count) = self.total();
+        if count == 0 {
+            100.
+        } else {
+            hits as f64 / count as f64 * 100.
+        }
+    }
+
+    /// Prints the changed line coverage of every file in a table.
+    pub fn print(&self) -> eyre::Result<()> {
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(vec![
+            Cell::new("File"),
+            Cell::new("% Changed Lines"),
+            Cell::new("Uncovered Lines"),
+        ]);
+        for file in &self.files {
+            let uncovered =
+                file.uncovered.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+            table.add_row(vec![
+                Cell::new(file.path.display()),
+                format_cell(file.line_hits, file.line_count),
+                Cell::new(uncovered),
+            ]);
+        }
+        let (hits, count) = self.total();
+        table.add_row(vec![Cell::new("Total"), format_cell(hits, count), Cell::new("")]);
+        sh_println!("\n{table}")?;
+        Ok(())
+    }
+}
+
+/// A coverage metric below its configured minimum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThresholdFailure {
+    /// The source file, or `None` for the entire project.
+    pub path: Option<PathBuf>,
+    /// The name of the metric.
+    pub metric: &'static str,
+    /// The covered percentage.
+    pub percentage: f64,
+    /// The required percentage.
+    pub minimum: f64,
+}
+
+/// Checks the coverage report against the minimums in the `[coverage]` config.
+///
+/// The global minimums apply to the coverage of the entire project, and the minimums of the
+/// `[[coverage.paths]]` globs apply to each matching source file individually.
+pub fn check_thresholds(report: &CoverageReport, config: &CoverageConfig) -> Vec<ThresholdFailure> {
+    let mut failures = Vec::new();
+    let mut total = CoverageSummary::default();
+    for (path, summary) in report.summary_by_file() {
+        total.merge(&summary);
+        if let Some(thresholds) = config.thresholds_for(path) {
+            failures.extend(threshold_failures(Some(path), &summary, &thresholds));
+        }
+    }
+    failures.extend(threshold_failures(None, &total, &config.thresholds));
+    failures
+}
+
+fn threshold_failures(
+    path: Option<&Path>,
+    summary: &CoverageSummary,
+    thresholds: &CoverageThresholds,
+) -> Vec<ThresholdFailure> {
+    [
+        ("lines", thresholds.min_lines, summary.line_hits, summary.line_count),
+        ("statements", thresholds.min_statements, summary.statement_hits, summary.statement_count),
+        ("branches", thresholds.min_branches, summary.branch_hits, summary.branch_count),
+        ("functions", thresholds.min_functions, summary.function_hits, summary.function_count),
+    ]
+    .into_iter()
+    .filter_map(|(metric, minimum, hits, total)| {
+        let minimum = minimum?;
+        let percentage = if total == 0 { 100. } else { hits as f64 / total as f64 * 100. };
+        (percentage < minimum).then(|| ThresholdFailure {
+            path: path.map(Path::to_path_buf),
+            metric,
+            percentage,
+            minimum,
+        })
+    })
+    .collect()
+}
+
+/// Prints the coverage metrics below their minimums in a table.
+pub fn print_threshold_failures(failures: &[ThresholdFailure]) -> eyre::Result<()> {
+    let mut table = Table::new();
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(vec![
+        Cell::new("File"),
+        Cell::new("Metric"),
+        Cell::new("Coverage"),
+        Cell::new("Minimum"),
+    ]);
+    for failure in failures {
+        table.add_row(vec![
+            Cell::new(
+                failure
+                    .path
+                    .as_ref()
+                    .map_or_else(|| "Total".to_string(), |p| p.display().to_string()),
+            ),
+            Cell::new(failure.metric),
+            Cell::new(format!("{:.2}%", failure.percentage)).fg(Color::Red),
+            Cell::new(format!("{:.2}%", failure.minimum)),
+        ]);
+    }
+    sh_println!("\n{table}")?;
+    Ok(())
+}
+
 /// A super verbose reporter for debugging coverage while it is still unstable.
 pub struct DebugReporter;
 
@@ -285,6 +757,248 @@ impl CoverageReporter for BytecodeReporter {
     }
 }
 
+/// Writes the coverage report as a self-contained HTML site.
+///
+/// The site consists of an `index.html` page with the coverage summary of every source file, and
+/// one page per source file rendering its lines annotated with their hit counts, the branch paths
+/// taken on each line, and a table of the functions in the file.
+pub struct HtmlReporter {
+    root: PathBuf,
+    destdir: PathBuf,
+}
+
+impl HtmlReporter {
+    /// Create a new HTML reporter which reads sources relative to `root` and writes the report to
+    /// `destdir`.
+    pub fn new(root: PathBuf, destdir: PathBuf) -> Self {
+        Self { root, destdir }
+    }
+
+    /// Returns the path of the page for the given source file, relative to the report directory.
+    fn page_path(path: &Path) -> PathBuf {
+        let relative = path
+            .components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .collect::<PathBuf>();
+        let mut name = relative.into_os_string();
+        name.push(".html");
+        name.into()
+    }
+
+    fn write_source_page(
+        &self,
+        path: &Path,
+        items: &[&CoverageItem],
+        summary: &CoverageSummary,
+    ) -> eyre::Result<()> {
+        use std::fmt::Write;
+
+        let page_path = Self::page_path(path);
+        let index_href = "../".repeat(page_path.components().count() - 1) + "index.html";
+        let source = fs::read_to_string(self.root.join(path))?;
+
+        let mut line_hits: HashMap<usize, usize> = HashMap::default();
+        let mut line_branches: HashMap<usize, Vec<&CoverageItem>> = HashMap::default();
+        let mut functions = Vec::new();
+        for item in items {
+            let line = item.loc.lines.start;
+            match item.kind {
+                CoverageItemKind::Line => {
+                    let hits = line_hits.entry(line).or_default();
+                    *hits = (*hits).max(item.hits);
+                }
+                CoverageItemKind::Branch { .. } => {
+                    line_branches.entry(line).or_default().push(item);
+                }
+                CoverageItemKind::Function { ref name } => {
+                    functions.push((format!("{}.{name}", item.loc.contract_name), line, item.hits));
+                }
+                CoverageItemKind::Statement => {}
+            }
+        }
+        functions.sort_by_key(|(_, line, _)| *line);
+
+        let title = escape_markup(&path.display().to_string());
+        let mut html = String::new();
+        write_html_header(&mut html, &title)?;
+        writeln!(html, "<p><a href=\"{index_href}\">All files</a> / {title}</p>")?;
+        write_summary_table(&mut html, std::iter::once((None, summary)))?;
+
+        writeln!(html, "<h2>Functions</h2>")?;
+        writeln!(html, "<table>")?;
+        writeln!(html, "<tr><th>Function</th><th>Line</th><th>Hits</th></tr>")?;
+        for (name, line, hits) in &functions {
+            let class = if *hits == 0 { "miss" } else { "hit" };
+            writeln!(
+                html,
+                "<tr class=\"{class}\"><td><a href=\"#L{line}\">{}</a></td><td>{line}</td><td>{hits}</td></tr>",
+                escape_markup(name)
+            )?;
+        }
+        writeln!(html, "</table>")?;
+
+        writeln!(html, "<h2>Source</h2>")?;
+        writeln!(html, "<table class=\"source\">")?;
+        for (idx, text) in source.lines().enumerate() {
+            let line = idx + 1;
+            let hits = line_hits.get(&line);
+            let class = match hits {
+                Some(0) => "miss",
+                Some(_) => "hit",
+                None => "",
+            };
+            let branches = line_branches.get(&line).map_or_else(String::new, |branches| {
+                branches
+                    .iter()
+                    .map(|item| {
+                        let CoverageItemKind::Branch { branch_id, path_id, .. } = item.kind else {
+                            unreachable!()
+                        };
+                        let (class, marker) = if item.hits == 0 { ("miss", "-") } else { ("hit", "+") };
+                        format!(
+                            "<span class=\"branch {class}\" title=\"branch {branch_id} path {path_id}: {} hits\">[{marker}]</span>",
+                            item.hits
+                        )
+                    })
+                    .collect()
+            });
+            writeln!(
+                html,
+                "<tr id=\"L{line}\" class=\"{class}\"><td class=\"line\">{line}</td><td class=\"hits\">{}</td><td class=\"branches\">{branches}</td><td class=\"code\"><pre>{}</pre></td></tr>",
+                hits.map(ToString::to_string).unwrap_or_default(),
+                escape_markup(text)
+            )?;
+        }
+        writeln!(html, "</table>")?;
+        writeln!(html, "</body>\n</html>")?;
+
+        let out = self.destdir.join(&page_path);
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(out, html)?;
+        Ok(())
+    }
+}
+
+impl CoverageReporter for HtmlReporter {
+    fn report(self, report: &CoverageReport) -> eyre::Result<()> {
+        fs::create_dir_all(&self.destdir)?;
+
+        let mut summaries = Vec::new();
+        let mut total = CoverageSummary::default();
+        for (path, items) in report.items_by_file() {
+            let summary = CoverageSummary::from_items(items.iter().copied());
+            self.write_source_page(path, &items, &summary)?;
+            total.merge(&summary);
+            summaries.push((path, summary));
+        }
+
+        let mut html = String::new();
+        write_html_header(&mut html, "Coverage report")?;
+        write_summary_table(
+            &mut html,
+            summaries
+                .iter()
+                .map(|(path, summary)| (Some(*path), summary))
+                .chain(std::iter::once((None, &total))),
+        )?;
+        html.push_str("</body>\n</html>\n");
+        fs::write(self.destdir.join("index.html"), html)?;
+
+        sh_println!("Wrote HTML report to {}.", self.destdir.display())?;
+
+        Ok(())
+    }
+}
+
+/// Inline stylesheet of the HTML report, so that the report does not depend on external assets.
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { padding: 2px 8px; text-align: left; border-bottom: 1px solid #ddd; }
+tr.hit td.hits, tr.hit td.line { background: #c8f0c8; }
+tr.miss td.hits, tr.miss td.line { background: #f5c2c2; }
+table.source td { border: none; }
+td.line, td.hits { text-align: right; color: #555; }
+td.code pre { margin: 0; }
+span.branch { font-family: monospace; margin-right: 2px; }
+span.branch.hit { color: #1a7f1a; }
+span.branch.miss { color: #c0392b; font-weight: bold; }
+td.low { background: #f5c2c2; }
+td.medium { background: #f9e79f; }
+td.high { background: #c8f0c8; }
+td.none { color: #999; }
+";
+
+fn write_html_header(html: &mut String, title: &str) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(html, "<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>")?;
+    writeln!(html, "<h1>{title}</h1>")
+}
+
+/// Writes a summary table with one row per source file. A row without a path is a total.
+fn write_summary_table<'a>(
+    html: &mut String,
+    rows: impl IntoIterator<Item = (Option<&'a Path>, &'a CoverageSummary)>,
+) -> std::fmt::Result {
+    use std::fmt::Write;
+
+    writeln!(html, "<table>")?;
+    writeln!(
+        html,
+        "<tr><th>File</th><th>% Lines</th><th>% Statements</th><th>% Branches</th><th>% Funcs</th></tr>"
+    )?;
+    for (path, summary) in rows {
+        let name = match path {
+            Some(path) => format!(
+                "<a href=\"{}\">{}</a>",
+                escape_markup(&HtmlReporter::page_path(path).to_string_lossy()),
+                escape_markup(&path.display().to_string())
+            ),
+            None => "Total".to_string(),
+        };
+        writeln!(
+            html,
+            "<tr><td>{name}</td>{}{}{}{}</tr>",
+            format_html_cell(summary.line_hits, summary.line_count),
+            format_html_cell(summary.statement_hits, summary.statement_count),
+            format_html_cell(summary.branch_hits, summary.branch_count),
+            format_html_cell(summary.function_hits, summary.function_count),
+        )?;
+    }
+    writeln!(html, "</table>")
+}
+
+fn format_html_cell(hits: usize, total: usize) -> String {
+    let percentage = if total == 0 { 1. } else { hits as f64 / total as f64 };
+    let class = match percentage {
+        _ if total == 0 => "none",
+        _ if percentage < 0.5 => "low",
+        _ if percentage < 0.75 => "medium",
+        _ => "high",
+    };
+    format!("<td class=\"{class}\">{:.2}% ({hits}/{total})</td>", percentage * 100.)
+}
+
+/// Escapes the characters with a special meaning in HTML and XML.
+fn escape_markup(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 /// Cache line number offsets for source files
 struct LineNumberCache {
     root: PathBuf,
@@ -317,3 +1031,39 @@ impl LineNumberCache {
         Ok((lo, pos))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_changed_lines() {
+        let diff = r#"diff --git a/src/Counter.sol b/src/Counter.sol
+index 1a2b3c4..5d6e7f8 100644
+--- a/src/Counter.sol
++++ b/src/Counter.sol
+@@ -3,0 +4,2 @@ pragma solidity ^0.8.13;
++// a comment
++// another comment
+@@ -12 +14 @@ contract Counter {
+-        number++;
++        number += 1;
+@@ -20,3 +21,0 @@ contract Counter {
+-    }
+diff --git a/src/Old.sol b/src/Old.sol
+deleted file mode 100644
+--- a/src/Old.sol
++++ /dev/null
+@@ -1,3 +0,0 @@
+-contract Old {}
+"#;
+        let changed = ChangedLines::from_git_diff(diff);
+        assert_eq!(
+            changed,
+            ChangedLines(BTreeMap::from([(
+                PathBuf::from("src/Counter.sol"),
+                BTreeSet::from([4, 5, 14])
+            )]))
+        );
+    }
+}
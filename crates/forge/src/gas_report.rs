@@ -11,7 +11,7 @@ use foundry_common::{
     reports::{report_kind, ReportKind},
     TestFunctionExt,
 };
-use foundry_evm::traces::CallKind;
+use foundry_evm::{inspectors::cheatcodes::AssertionResult, traces::CallKind};
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -33,6 +33,10 @@ pub struct GasReport {
     /// All contracts that were analyzed grouped by their identifier
     /// ``test/Counter.t.sol:CounterTest
     pub contracts: BTreeMap<String, ContractInfo>,
+    /// Gas used by assertion functions executed through `assertionEx`, grouped by assertion
+    /// contract label and function name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub assertions: BTreeMap<String, BTreeMap<String, GasInfo>>,
 }
 
 impl GasReport {
@@ -134,22 +138,39 @@ impl GasReport {
         }
     }
 
+    /// Analyzes the given assertion function results and adds their gas usage to the report.
+    pub fn analyze_assertions<'a>(
+        &mut self,
+        results: impl IntoIterator<Item = &'a AssertionResult>,
+    ) {
+        for result in results {
+            if !self.should_report(&result.label) {
+                continue;
+            }
+            let name = result.function_name.clone().unwrap_or_else(|| result.selector.to_string());
+            trace!(label = %result.label, %name, "adding assertion gas info");
+            self.assertions
+                .entry(result.label.clone())
+                .or_default()
+                .entry(name)
+                .or_default()
+                .frames
+                .push(result.gas_used);
+        }
+    }
+
     /// Finalizes the gas report by calculating the min, max, mean, and median for each function.
     #[must_use]
     pub fn finalize(mut self) -> Self {
         trace!("finalizing gas report");
         for contract in self.contracts.values_mut() {
             for sigs in contract.functions.values_mut() {
-                for func in sigs.values_mut() {
-                    func.frames.sort_unstable();
-                    func.min = func.frames.first().copied().unwrap_or_default();
-                    func.max = func.frames.last().copied().unwrap_or_default();
-                    func.mean = calc::mean(&func.frames);
-                    func.median = calc::median_sorted(&func.frames);
-                    func.calls = func.frames.len() as u64;
-                }
+                sigs.values_mut().for_each(GasInfo::finalize);
             }
         }
+        for functions in self.assertions.values_mut() {
+            functions.values_mut().for_each(GasInfo::finalize);
+        }
         self
     }
 }
@@ -167,6 +188,11 @@ impl Display for GasReport {
                     let table = self.format_table_output(contract, name);
                     writeln!(f, "\n{table}")?;
                 }
+
+                for (label, functions) in &self.assertions {
+                    let table = self.format_assertion_table_output(functions, label);
+                    writeln!(f, "\n{table}")?;
+                }
             }
             ReportKind::JSON => {
                 writeln!(f, "{}", &self.format_json_output())?;
@@ -209,6 +235,12 @@ impl GasReport {
                         "functions": functions,
                     }))
                 })
+                .chain(self.assertions.iter().map(|(label, functions)| {
+                    json!({
+                        "assertion_contract": label,
+                        "functions": functions,
+                    })
+                }))
                 .collect::<Vec<_>>(),
         )
         .unwrap()
@@ -260,6 +292,39 @@ impl GasReport {
 
         table
     }
+
+    fn format_assertion_table_output(
+        &self,
+        functions: &BTreeMap<String, GasInfo>,
+        label: &str,
+    ) -> Table {
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+
+        table.set_header(vec![Cell::new(format!("{label} Assertions")).fg(Color::Magenta)]);
+
+        table.add_row(vec![
+            Cell::new("Assertion Function"),
+            Cell::new("Min").fg(Color::Green),
+            Cell::new("Avg").fg(Color::Yellow),
+            Cell::new("Median").fg(Color::Yellow),
+            Cell::new("Max").fg(Color::Red),
+            Cell::new("# Calls").fg(Color::Cyan),
+        ]);
+
+        for (name, gas_info) in functions {
+            table.add_row(vec![
+                Cell::new(name),
+                Cell::new(gas_info.min.to_string()).fg(Color::Green),
+                Cell::new(gas_info.mean.to_string()).fg(Color::Yellow),
+                Cell::new(gas_info.median.to_string()).fg(Color::Yellow),
+                Cell::new(gas_info.max.to_string()).fg(Color::Red),
+                Cell::new(gas_info.calls.to_string()),
+            ]);
+        }
+
+        table
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -281,3 +346,15 @@ pub struct GasInfo {
     #[serde(skip)]
     pub frames: Vec<u64>,
 }
+
+impl GasInfo {
+    /// Calculates the min, max, mean, and median of the recorded frames.
+    fn finalize(&mut self) {
+        self.frames.sort_unstable();
+        self.min = self.frames.first().copied().unwrap_or_default();
+        self.max = self.frames.last().copied().unwrap_or_default();
+        self.mean = calc::mean(&self.frames);
+        self.median = calc::median_sorted(&self.frames);
+        self.calls = self.frames.len() as u64;
+    }
+}
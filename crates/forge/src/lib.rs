@@ -11,7 +11,11 @@ pub mod coverage;
 
 pub mod gas_report;
 
+pub mod lint;
+
 pub mod multi_runner;
+pub mod mutation;
+
 pub use multi_runner::{MultiContractRunner, MultiContractRunnerBuilder};
 
 mod runner;
@@ -0,0 +1,944 @@
+//! Solidity linting.
+//!
+//! Lints run on the Solang parse tree of a source file. A finding can be suppressed with a
+//! `// forgelint: disable-next-line` or `// forgelint: disable-line` comment, optionally followed
+//! by the ids of the lints to suppress in parentheses, e.g.
+//! `// forgelint: disable-next-line(unchecked-call, tx-origin)`.
+
+use eyre::Result;
+use forge_fmt::{FormatterError, Visitable, Visitor};
+use foundry_config::LintConfig;
+use serde_json::{json, Value};
+use solang_parser::pt::{
+    CatchClause, CodeLocation, Comment, ContractDefinition, ContractPart, ContractTy,
+    EnumDefinition, ErrorDefinition, EventDefinition, Expression, FunctionAttribute,
+    FunctionDefinition, FunctionTy, Identifier, IdentifierPath, Import, Loc, NamedArgument,
+    Parameter, SourceUnit, SourceUnitPart, Statement, StructDefinition, VariableAttribute,
+    VariableDeclaration, VariableDefinition, Visibility,
+};
+use std::{
+    convert::Infallible,
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A lint that `forge lint` can run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// An imported symbol that is never used.
+    UnusedImport,
+    /// A declaration that shadows a state variable or an earlier declaration.
+    Shadowing,
+    /// A public or external function without NatSpec documentation.
+    MissingNatspec,
+    /// A low-level call or `send` whose success is ignored.
+    UncheckedCall,
+    /// An ERC-20 `transfer`, `transferFrom` or `approve` whose returned boolean is ignored.
+    Erc20UncheckedTransfer,
+    /// A name that does not follow the naming conventions of the Solidity style guide.
+    Naming,
+    /// A use of `tx.origin`.
+    TxOrigin,
+}
+
+impl Lint {
+    /// All lints, in the order they are documented.
+    pub const ALL: [Self; 7] = [
+        Self::UnusedImport,
+        Self::Shadowing,
+        Self::MissingNatspec,
+        Self::UncheckedCall,
+        Self::Erc20UncheckedTransfer,
+        Self::Naming,
+        Self::TxOrigin,
+    ];
+
+    /// Returns the id of the lint, as used in the `[lint.rules]` config and `forgelint:` comments.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::UnusedImport => "unused-import",
+            Self::Shadowing => "shadowing",
+            Self::MissingNatspec => "missing-natspec",
+            Self::UncheckedCall => "unchecked-call",
+            Self::Erc20UncheckedTransfer => "erc20-unchecked-transfer",
+            Self::Naming => "naming",
+            Self::TxOrigin => "tx-origin",
+        }
+    }
+
+    /// Returns a short description of what the lint detects.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::UnusedImport => "Imported symbol is never used",
+            Self::Shadowing => "Declaration shadows a state variable or an earlier declaration",
+            Self::MissingNatspec => "Public or external function has no NatSpec documentation",
+            Self::UncheckedCall => "Success of a low-level call is not checked",
+            Self::Erc20UncheckedTransfer => "Return value of an ERC-20 transfer is not checked",
+            Self::Naming => "Name does not follow the Solidity style guide",
+            Self::TxOrigin => "`tx.origin` is used",
+        }
+    }
+
+    /// Returns the severity of the findings of the lint.
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::UncheckedCall | Self::Erc20UncheckedTransfer => Severity::High,
+            Self::Shadowing | Self::TxOrigin => Severity::Medium,
+            Self::UnusedImport => Severity::Low,
+            Self::MissingNatspec | Self::Naming => Severity::Info,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|lint| lint.id() == s)
+            .ok_or_else(|| format!("unknown lint `{s}`"))
+    }
+}
+
+/// How likely the findings of a lint are to be bugs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+impl Severity {
+    /// Returns the SARIF level of the severity.
+    pub fn sarif_level(self) -> &'static str {
+        match self {
+            Self::High => "error",
+            Self::Medium => "warning",
+            Self::Low | Self::Info => "note",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+            Self::Info => "info",
+        })
+    }
+}
+
+/// A lint finding in a source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The lint that produced the finding.
+    pub lint: Lint,
+    /// The path of the source file.
+    pub path: PathBuf,
+    /// The description of the finding.
+    pub message: String,
+    /// The 1-based line of the start of the finding.
+    pub line: usize,
+    /// The 1-based column of the start of the finding, in characters.
+    pub column: usize,
+    /// The 1-based line of the end of the finding.
+    pub end_line: usize,
+    /// The 1-based column just past the end of the finding, in characters.
+    pub end_column: usize,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.lint,
+            self.message
+        )
+    }
+}
+
+/// Runs the enabled lints on Solidity source files.
+#[derive(Clone, Debug)]
+pub struct Linter {
+    lints: Vec<Lint>,
+}
+
+impl Linter {
+    /// Creates a linter running the lints enabled in the `[lint]` config.
+    pub fn new(config: &LintConfig) -> Result<Self> {
+        for id in config.rules.keys() {
+            id.parse::<Lint>().map_err(|err| eyre::eyre!("{err} in `[lint.rules]`"))?;
+        }
+        let lints = Lint::ALL.into_iter().filter(|lint| config.is_enabled(lint.id())).collect();
+        Ok(Self { lints })
+    }
+
+    /// Lints the Solidity source file at `path`.
+    pub fn lint(&self, path: &Path, source: &str) -> Result<Vec<Finding>> {
+        let (mut pt, comments) = solang_parser::parse(source, 0).map_err(|diags| {
+            FormatterError::Parse(source.to_string(), Some(path.to_path_buf()), diags)
+        })?;
+        let suppressions = Suppressions::new(path, source, &comments)?;
+
+        let mut collector = LintCollector::new(source, &comments);
+        collector.unused_imports(&pt);
+        pt.visit(&mut collector).unwrap_or_else(|never| match never {});
+
+        let mut findings: Vec<_> = collector
+            .findings
+            .into_iter()
+            .filter(|(lint, ..)| self.lints.contains(lint))
+            .map(|(lint, loc, message)| {
+                let (line, column) = position(source, loc.start());
+                let (end_line, end_column) = position(source, loc.end());
+                Finding {
+                    lint,
+                    path: path.to_path_buf(),
+                    message,
+                    line,
+                    column,
+                    end_line,
+                    end_column,
+                }
+            })
+            .filter(|finding| !suppressions.is_suppressed(finding.lint, finding.line))
+            .collect();
+        findings.sort_by_key(|finding| (finding.line, finding.column, finding.lint));
+        Ok(findings)
+    }
+}
+
+/// Returns the 1-based line and character column of a byte offset in the source.
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// The lines on which findings are suppressed by `forgelint:` comments.
+struct Suppressions(Vec<(usize, Option<Vec<Lint>>)>);
+
+impl Suppressions {
+    fn new(path: &Path, source: &str, comments: &[Comment]) -> Result<Self> {
+        let mut lines = Vec::new();
+        for comment in comments {
+            let Comment::Line(loc, text) = comment else { continue };
+            let Some(directive) = text.trim_start_matches('/').trim().strip_prefix("forgelint:")
+            else {
+                continue
+            };
+            let (line, _) = position(source, loc.start());
+            let invalid = || eyre::eyre!("{}:{line}: invalid `forgelint` comment", path.display());
+
+            let (kind, lints) = match directive.split_once('(') {
+                Some((kind, ids)) => {
+                    let ids = ids.trim_end().strip_suffix(')').ok_or_else(invalid)?;
+                    let lints = ids
+                        .split(',')
+                        .map(|id| id.trim().parse::<Lint>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|err| eyre::eyre!("{}:{line}: {err}", path.display()))?;
+                    (kind.trim(), Some(lints))
+                }
+                None => (directive.trim(), None),
+            };
+            let line = match kind {
+                "disable-line" => line,
+                "disable-next-line" => line + 1,
+                _ => return Err(invalid()),
+            };
+            lines.push((line, lints));
+        }
+        Ok(Self(lines))
+    }
+
+    fn is_suppressed(&self, lint: Lint, line: usize) -> bool {
+        self.0.iter().any(|(suppressed, lints)| {
+            *suppressed == line && lints.as_ref().is_none_or(|lints| lints.contains(&lint))
+        })
+    }
+}
+
+/// A naming convention of the Solidity style guide.
+///
+/// Leading underscores, which mark private and internal members, are allowed in every convention.
+#[derive(Clone, Copy, Debug)]
+enum Case {
+    CapWords,
+    MixedCase,
+    UpperCase,
+}
+
+impl Case {
+    fn matches(self, name: &str) -> bool {
+        let name = name.trim_start_matches('_');
+        let Some(first) = name.chars().next() else { return true };
+        match self {
+            Self::CapWords => first.is_ascii_uppercase() && !name.contains('_'),
+            Self::MixedCase => !first.is_ascii_uppercase() && !name.contains('_'),
+            Self::UpperCase => !name.chars().any(|c| c.is_ascii_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CapWords => "CapWords",
+            Self::MixedCase => "mixedCase",
+            Self::UpperCase => "UPPER_CASE",
+        })
+    }
+}
+
+/// Returns true if `word` occurs in `code` as a whole identifier.
+fn contains_word(code: &[u8], word: &str) -> bool {
+    let is_ident = |b: &u8| b.is_ascii_alphanumeric() || *b == b'_' || *b == b'$';
+    let word = word.as_bytes();
+    !word.is_empty() &&
+        code.windows(word.len()).enumerate().any(|(i, window)| {
+            window == word &&
+                !code[..i].last().is_some_and(is_ident) &&
+                !code.get(i + word.len()).is_some_and(is_ident)
+        })
+}
+
+/// Collects the findings of all lints in a parse tree.
+struct LintCollector<'a> {
+    source: &'a str,
+    /// The source with all comments replaced by spaces.
+    code: Vec<u8>,
+    /// The byte ranges of the comments, and whether each is a NatSpec comment.
+    comments: Vec<(Range<usize>, bool)>,
+    findings: Vec<(Lint, Loc, String)>,
+    /// The state variables of the contract being visited.
+    state_variables: Vec<String>,
+    /// The names declared in each enclosing scope of the function being visited.
+    scopes: Vec<Vec<String>>,
+}
+
+impl<'a> LintCollector<'a> {
+    fn new(source: &'a str, comments: &[Comment]) -> Self {
+        let mut code = source.as_bytes().to_vec();
+        let comments: Vec<_> = comments
+            .iter()
+            .map(|comment| {
+                let (loc, natspec) = match comment {
+                    Comment::Line(loc, _) | Comment::Block(loc, _) => (loc, false),
+                    Comment::DocLine(loc, _) | Comment::DocBlock(loc, _) => (loc, true),
+                };
+                code[loc.start()..loc.end()].fill(b' ');
+                (loc.start()..loc.end(), natspec)
+            })
+            .collect();
+        Self {
+            source,
+            code,
+            comments,
+            findings: Vec::new(),
+            state_variables: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, lint: Lint, loc: Loc, message: String) {
+        self.findings.push((lint, loc, message));
+    }
+
+    /// Adds a finding for every imported symbol that is not used outside of import directives.
+    fn unused_imports(&mut self, unit: &SourceUnit) {
+        let mut code = self.code.clone();
+        let mut imported = Vec::new();
+        for part in &unit.0 {
+            let SourceUnitPart::ImportDirective(import) = part else { continue };
+            let loc = part.loc();
+            code[loc.start()..loc.end()].fill(b' ');
+            match import {
+                Import::Plain(..) => {}
+                Import::GlobalSymbol(_, alias, _) => imported.push(alias),
+                Import::Rename(_, symbols, _) => imported
+                    .extend(symbols.iter().map(|(symbol, alias)| alias.as_ref().unwrap_or(symbol))),
+            }
+        }
+        for ident in imported {
+            if !contains_word(&code, &ident.name) {
+                self.push(
+                    Lint::UnusedImport,
+                    ident.loc,
+                    format!("`{}` is imported but never used", ident.name),
+                );
+            }
+        }
+    }
+
+    /// Returns true if a NatSpec comment precedes the byte offset, skipping regular comments.
+    fn has_natspec(&self, start: usize) -> bool {
+        let mut end = start;
+        loop {
+            end = self.source[..end].trim_end().len();
+            let comment = self.comments.iter().find(|(range, _)| {
+                range.start < end &&
+                    range.end >= end &&
+                    self.source[end..range.end].trim().is_empty()
+            });
+            match comment {
+                Some((_, true)) => return true,
+                Some((range, false)) => end = range.start,
+                None => return false,
+            }
+        }
+    }
+
+    fn check_case(&mut self, ident: Option<&Identifier>, kind: &str, case: Case) {
+        if let Some(ident) = ident {
+            if !case.matches(&ident.name) {
+                self.push(
+                    Lint::Naming,
+                    ident.loc,
+                    format!("{kind} `{}` should use {case}", ident.name),
+                );
+            }
+        }
+    }
+
+    /// Declares a name in the current scope, adding a finding if it shadows another declaration.
+    fn declare(&mut self, ident: &Identifier) {
+        let name = &ident.name;
+        if self.scopes.iter().flatten().any(|declared| declared == name) {
+            self.push(
+                Lint::Shadowing,
+                ident.loc,
+                format!("`{name}` shadows an earlier declaration"),
+            );
+        } else if self.state_variables.contains(name) {
+            self.push(Lint::Shadowing, ident.loc, format!("`{name}` shadows a state variable"));
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(name.clone());
+        }
+    }
+
+    fn declare_params<'p>(&mut self, params: impl IntoIterator<Item = &'p Option<Parameter>>) {
+        for param in params {
+            if let Some(name) = param.as_ref().and_then(|param| param.name.as_ref()) {
+                self.declare(name);
+            }
+        }
+    }
+
+    /// Adds a finding if the statement is a call whose returned success is ignored.
+    fn check_statement(&mut self, statement: &Statement) {
+        let Statement::Expression(_, expr) = statement else { return };
+        let Expression::FunctionCall(loc, callee, args) = expr else { return };
+        let callee = match callee.as_ref() {
+            Expression::FunctionCallBlock(_, callee, _) => callee.as_ref(),
+            callee => callee,
+        };
+        let Expression::MemberAccess(_, _, member) = callee else { return };
+        match (member.name.as_str(), args.len()) {
+            ("call" | "delegatecall" | "staticcall" | "send", _) => self.push(
+                Lint::UncheckedCall,
+                *loc,
+                format!("the success of low-level `{}` is not checked", member.name),
+            ),
+            ("transfer" | "approve", 2) | ("transferFrom", 3) => self.push(
+                Lint::Erc20UncheckedTransfer,
+                *loc,
+                format!("the return value of ERC-20 `{}` is not checked", member.name),
+            ),
+            _ => {}
+        }
+    }
+}
+
+impl Visitor for LintCollector<'_> {
+    type Error = Infallible;
+
+    fn visit_source_unit(&mut self, source_unit: &mut SourceUnit) -> Result<(), Self::Error> {
+        source_unit.0.visit(self)
+    }
+
+    fn visit_contract(&mut self, contract: &mut ContractDefinition) -> Result<(), Self::Error> {
+        let kind = match contract.ty {
+            ContractTy::Abstract(_) | ContractTy::Contract(_) => "contract",
+            ContractTy::Interface(_) => "interface",
+            ContractTy::Library(_) => "library",
+        };
+        self.check_case(contract.name.as_ref(), kind, Case::CapWords);
+
+        self.state_variables = contract
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                ContractPart::VariableDefinition(var) => Some(var.name.as_ref()?.name.clone()),
+                _ => None,
+            })
+            .collect();
+        contract.parts.visit(self)?;
+        self.state_variables.clear();
+        Ok(())
+    }
+
+    fn visit_function(&mut self, func: &mut FunctionDefinition) -> Result<(), Self::Error> {
+        match func.ty {
+            FunctionTy::Function => {
+                self.check_case(func.name.as_ref(), "function", Case::MixedCase)
+            }
+            FunctionTy::Modifier => {
+                self.check_case(func.name.as_ref(), "modifier", Case::MixedCase)
+            }
+            _ => {}
+        }
+
+        let public = func.attributes.iter().any(|attr| {
+            matches!(
+                attr,
+                FunctionAttribute::Visibility(Visibility::Public(_) | Visibility::External(_))
+            )
+        });
+        if public && matches!(func.ty, FunctionTy::Function) && !self.has_natspec(func.loc.start())
+        {
+            let (loc, name) = match &func.name {
+                Some(name) => (name.loc, name.name.as_str()),
+                None => (func.loc, ""),
+            };
+            self.push(
+                Lint::MissingNatspec,
+                loc,
+                format!("function `{name}` has no NatSpec documentation"),
+            );
+        }
+
+        self.scopes.push(Vec::new());
+        self.declare_params(func.params.iter().chain(&func.returns).map(|(_, param)| param));
+        func.body.visit(self)?;
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn visit_var_definition(&mut self, var: &mut VariableDefinition) -> Result<(), Self::Error> {
+        if var.attrs.iter().any(|attr| matches!(attr, VariableAttribute::Constant(_))) {
+            self.check_case(var.name.as_ref(), "constant", Case::UpperCase);
+        } else if !var.attrs.iter().any(|attr| matches!(attr, VariableAttribute::Immutable(_))) {
+            self.check_case(var.name.as_ref(), "state variable", Case::MixedCase);
+        }
+        var.initializer.visit(self)
+    }
+
+    fn visit_struct(&mut self, structure: &mut StructDefinition) -> Result<(), Self::Error> {
+        self.check_case(structure.name.as_ref(), "struct", Case::CapWords);
+        Ok(())
+    }
+
+    fn visit_event(&mut self, event: &mut EventDefinition) -> Result<(), Self::Error> {
+        self.check_case(event.name.as_ref(), "event", Case::CapWords);
+        Ok(())
+    }
+
+    fn visit_error(&mut self, error: &mut ErrorDefinition) -> Result<(), Self::Error> {
+        self.check_case(error.name.as_ref(), "error", Case::CapWords);
+        Ok(())
+    }
+
+    fn visit_enum(&mut self, enumeration: &mut EnumDefinition) -> Result<(), Self::Error> {
+        self.check_case(enumeration.name.as_ref(), "enum", Case::CapWords);
+        Ok(())
+    }
+
+    fn visit_var_definition_stmt(
+        &mut self,
+        _loc: Loc,
+        declaration: &mut VariableDeclaration,
+        expr: &mut Option<Expression>,
+    ) -> Result<(), Self::Error> {
+        expr.visit(self)?;
+        if let Some(name) = &declaration.name {
+            self.declare(name);
+        }
+        Ok(())
+    }
+
+    fn visit_block(
+        &mut self,
+        _loc: Loc,
+        _unchecked: bool,
+        statements: &mut Vec<Statement>,
+    ) -> Result<(), Self::Error> {
+        self.scopes.push(Vec::new());
+        for statement in statements.iter_mut() {
+            self.check_statement(statement);
+            statement.visit(self)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn visit_args(&mut self, _loc: Loc, args: &mut Vec<NamedArgument>) -> Result<(), Self::Error> {
+        for arg in args {
+            arg.expr.visit(self)?;
+        }
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, loc: Loc, expr: &mut Expression) -> Result<(), Self::Error> {
+        match expr {
+            Expression::MemberAccess(_, base, member) => {
+                if member.name == "origin" &&
+                    matches!(base.as_ref(), Expression::Variable(ident) if ident.name == "tx")
+                {
+                    self.push(
+                        Lint::TxOrigin,
+                        loc,
+                        "`tx.origin` is used; use `msg.sender` for authorization".to_string(),
+                    );
+                }
+                return base.visit(self)
+            }
+            Expression::FunctionCall(_, callee, args) => {
+                callee.visit(self)?;
+                return args.visit(self)
+            }
+            Expression::NamedFunctionCall(_, callee, args) => {
+                callee.visit(self)?;
+                return self.visit_args(loc, args)
+            }
+            Expression::FunctionCallBlock(_, callee, block) => {
+                callee.visit(self)?;
+                return block.visit(self)
+            }
+            Expression::ArraySubscript(_, array, index) => {
+                array.visit(self)?;
+                return index.visit(self)
+            }
+            Expression::ArraySlice(_, array, start, end) => {
+                array.visit(self)?;
+                start.visit(self)?;
+                return end.visit(self)
+            }
+            Expression::ArrayLiteral(_, exprs) => return exprs.visit(self),
+            Expression::ConditionalOperator(_, cond, first, second) => {
+                cond.visit(self)?;
+                first.visit(self)?;
+                return second.visit(self)
+            }
+            Expression::List(_, params) => {
+                for (_, param) in params.iter_mut() {
+                    match param {
+                        Some(Parameter { name: Some(name), .. }) => self.declare(name),
+                        Some(param) => param.ty.visit(self)?,
+                        None => {}
+                    }
+                }
+                return Ok(())
+            }
+            _ => {}
+        }
+
+        match expr.components_mut() {
+            (Some(left), Some(right)) => {
+                left.visit(self)?;
+                right.visit(self)
+            }
+            (Some(inner), None) | (None, Some(inner)) => inner.visit(self),
+            (None, None) => Ok(()),
+        }
+    }
+
+    fn visit_emit(&mut self, _loc: Loc, event: &mut Expression) -> Result<(), Self::Error> {
+        event.visit(self)
+    }
+
+    fn visit_return(
+        &mut self,
+        _loc: Loc,
+        expr: &mut Option<Expression>,
+    ) -> Result<(), Self::Error> {
+        expr.visit(self)
+    }
+
+    fn visit_revert(
+        &mut self,
+        _loc: Loc,
+        _error: &mut Option<IdentifierPath>,
+        args: &mut Vec<Expression>,
+    ) -> Result<(), Self::Error> {
+        args.visit(self)
+    }
+
+    fn visit_revert_named_args(
+        &mut self,
+        loc: Loc,
+        _error: &mut Option<IdentifierPath>,
+        args: &mut Vec<NamedArgument>,
+    ) -> Result<(), Self::Error> {
+        self.visit_args(loc, args)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn visit_try(
+        &mut self,
+        _loc: Loc,
+        expr: &mut Expression,
+        returns: &mut Option<(Vec<(Loc, Option<Parameter>)>, Box<Statement>)>,
+        clauses: &mut Vec<CatchClause>,
+    ) -> Result<(), Self::Error> {
+        expr.visit(self)?;
+        if let Some((params, body)) = returns {
+            self.scopes.push(Vec::new());
+            self.declare_params(params.iter().map(|(_, param)| param));
+            body.visit(self)?;
+            self.scopes.pop();
+        }
+        for clause in clauses {
+            let (param, body) = match clause {
+                CatchClause::Simple(_, param, body) => (param.as_ref(), body),
+                CatchClause::Named(_, _, param, body) => (Some(&*param), body),
+            };
+            self.scopes.push(Vec::new());
+            if let Some(name) = param.and_then(|param| param.name.as_ref()) {
+                self.declare(name);
+            }
+            body.visit(self)?;
+            self.scopes.pop();
+        }
+        Ok(())
+    }
+
+    fn visit_if(
+        &mut self,
+        _loc: Loc,
+        cond: &mut Expression,
+        if_branch: &mut Box<Statement>,
+        else_branch: &mut Option<Box<Statement>>,
+        _is_first_stmt: bool,
+    ) -> Result<(), Self::Error> {
+        cond.visit(self)?;
+        self.check_statement(if_branch);
+        if_branch.visit(self)?;
+        if let Some(else_branch) = else_branch {
+            self.check_statement(else_branch);
+            else_branch.visit(self)?;
+        }
+        Ok(())
+    }
+
+    fn visit_do_while(
+        &mut self,
+        _loc: Loc,
+        body: &mut Statement,
+        cond: &mut Expression,
+    ) -> Result<(), Self::Error> {
+        self.check_statement(body);
+        body.visit(self)?;
+        cond.visit(self)
+    }
+
+    fn visit_while(
+        &mut self,
+        _loc: Loc,
+        cond: &mut Expression,
+        body: &mut Statement,
+    ) -> Result<(), Self::Error> {
+        cond.visit(self)?;
+        self.check_statement(body);
+        body.visit(self)
+    }
+
+    fn visit_for(
+        &mut self,
+        _loc: Loc,
+        init: &mut Option<Box<Statement>>,
+        cond: &mut Option<Box<Expression>>,
+        update: &mut Option<Box<Expression>>,
+        body: &mut Option<Box<Statement>>,
+    ) -> Result<(), Self::Error> {
+        self.scopes.push(Vec::new());
+        init.visit(self)?;
+        cond.visit(self)?;
+        update.visit(self)?;
+        if let Some(body) = body {
+            self.check_statement(body);
+            body.visit(self)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+}
+
+/// Returns the findings as a JSON array.
+pub fn json_report(findings: &[Finding]) -> Value {
+    findings
+        .iter()
+        .map(|finding| {
+            json!({
+                "lint": finding.lint.id(),
+                "severity": finding.lint.severity().to_string(),
+                "message": finding.message,
+                "path": finding.path,
+                "line": finding.line,
+                "column": finding.column,
+                "endLine": finding.end_line,
+                "endColumn": finding.end_column,
+            })
+        })
+        .collect()
+}
+
+/// Returns the findings as a SARIF 2.1.0 log.
+pub fn sarif_report(findings: &[Finding]) -> Value {
+    let rules: Vec<_> = Lint::ALL
+        .iter()
+        .map(|lint| {
+            json!({
+                "id": lint.id(),
+                "shortDescription": { "text": lint.description() },
+                "defaultConfiguration": { "level": lint.severity().sarif_level() },
+            })
+        })
+        .collect();
+    let results: Vec<_> = findings
+        .iter()
+        .map(|finding| {
+            json!({
+                "ruleId": finding.lint.id(),
+                "ruleIndex": Lint::ALL.iter().position(|lint| *lint == finding.lint),
+                "level": finding.lint.severity().sarif_level(),
+                "message": { "text": finding.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": finding.path.to_string_lossy().replace('\\', "/"),
+                        },
+                        "region": {
+                            "startLine": finding.line,
+                            "startColumn": finding.column,
+                            "endLine": finding.end_line,
+                            "endColumn": finding.end_column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "forge-lint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/foundry-rs/foundry",
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(source: &str) -> Vec<(Lint, usize, String)> {
+        Linter::new(&LintConfig::default())
+            .unwrap()
+            .lint(Path::new("src/A.sol"), source)
+            .unwrap()
+            .into_iter()
+            .map(|finding| (finding.lint, finding.line, finding.message))
+            .collect()
+    }
+
+    #[test]
+    fn finds_issues() {
+        let source = r#"
+import {IERC20} from "./IERC20.sol";
+import {Unused} from "./Unused.sol";
+
+contract vault {
+    uint256 public total;
+    uint256 constant maxAmount = 10;
+
+    /// @notice Withdraws the balance.
+    function withdraw(IERC20 token, uint256 total) external {
+        require(tx.origin == msg.sender);
+        token.transfer(msg.sender, total);
+        payable(msg.sender).call{value: 1}("");
+    }
+
+    function Deposit() public {}
+}
+"#;
+        let m = |lint, line, message: &str| (lint, line, message.to_string());
+        assert_eq!(
+            lint(source),
+            vec![
+                m(Lint::UnusedImport, 3, "`Unused` is imported but never used"),
+                m(Lint::Naming, 5, "contract `vault` should use CapWords"),
+                m(Lint::Naming, 7, "constant `maxAmount` should use UPPER_CASE"),
+                m(Lint::Shadowing, 10, "`total` shadows a state variable"),
+                m(Lint::TxOrigin, 11, "`tx.origin` is used; use `msg.sender` for authorization"),
+                m(
+                    Lint::Erc20UncheckedTransfer,
+                    12,
+                    "the return value of ERC-20 `transfer` is not checked"
+                ),
+                m(Lint::UncheckedCall, 13, "the success of low-level `call` is not checked"),
+                m(Lint::MissingNatspec, 16, "function `Deposit` has no NatSpec documentation"),
+                m(Lint::Naming, 16, "function `Deposit` should use mixedCase"),
+            ]
+        );
+    }
+
+    #[test]
+    fn suppresses_findings() {
+        let source = r#"
+contract A {
+    /// @notice Pays out.
+    function pay(address to) external {
+        // forgelint: disable-next-line(unchecked-call)
+        to.call("");
+        to.call(""); // forgelint: disable-line
+        require(tx.origin == to); // forgelint: disable-line(unchecked-call)
+    }
+}
+"#;
+        assert_eq!(
+            lint(source).into_iter().map(|(lint, line, _)| (lint, line)).collect::<Vec<_>>(),
+            vec![(Lint::TxOrigin, 8)]
+        );
+    }
+
+    #[test]
+    fn disables_lints() {
+        let config: LintConfig =
+            serde_json::from_str(r#"{ "rules": { "tx-origin": false } }"#).unwrap();
+        let findings = Linter::new(&config)
+            .unwrap()
+            .lint(Path::new("src/A.sol"), "contract A { function f() internal { tx.origin; } }")
+            .unwrap();
+        assert!(findings.is_empty());
+
+        let config: LintConfig = serde_json::from_str(r#"{ "rules": { "typo": false } }"#).unwrap();
+        assert!(Linter::new(&config).is_err());
+    }
+}
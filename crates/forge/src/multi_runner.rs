@@ -5,11 +5,11 @@ use crate::{
     TestFilter,
 };
 use alloy_json_abi::{Function, JsonAbi};
-use alloy_primitives::{Address, Bytes, U256};
+use alloy_primitives::{map::HashMap, Address, Bytes, U256};
 use eyre::Result;
 use foundry_common::{get_contract_name, shell::verbosity, ContractsByArtifact, TestFunctionExt};
 use foundry_compilers::{
-    artifacts::{Contract, Libraries},
+    artifacts::{Contract, Libraries, StorageLayout},
     compilers::Compiler,
     Artifact, ArtifactId, ProjectCompileOutput,
 };
@@ -52,6 +52,9 @@ pub struct MultiContractRunner {
     pub contracts: DeployableContracts,
     /// Known contracts linked with computed library addresses.
     pub known_contracts: ContractsByArtifact,
+    /// Storage layouts of the known contracts by artifact identifier, used to name the slots in
+    /// the state diffs of invariant counterexamples.
+    pub storage_layouts: HashMap<String, StorageLayout>,
     /// Revert decoder. Contains all known errors and their selectors.
     pub revert_decoder: RevertDecoder,
     /// Libraries to deploy.
@@ -86,7 +89,8 @@ impl MultiContractRunner {
         &'a self,
         filter: &'b dyn TestFilter,
     ) -> impl Iterator<Item = (&'a ArtifactId, &'a TestContract)> + 'b {
-        self.contracts.iter().filter(|&(id, c)| matches_contract(id, &c.abi, filter))
+        let symbolic = self.tcfg.config.symbolic.enabled;
+        self.contracts.iter().filter(move |&(id, c)| matches_contract(id, &c.abi, filter, symbolic))
     }
 
     /// Returns an iterator over all test functions that match the filter.
@@ -96,7 +100,7 @@ impl MultiContractRunner {
     ) -> impl Iterator<Item = &'a Function> + 'b {
         self.matching_contracts(filter)
             .flat_map(|(_, c)| c.abi.functions())
-            .filter(|func| is_matching_test(func, filter))
+            .filter(|func| is_matching_test(func, filter, self.tcfg.config.symbolic.enabled))
     }
 
     /// Returns an iterator over all test functions in contracts that match the filter.
@@ -120,7 +124,9 @@ impl MultiContractRunner {
                 let tests = c
                     .abi
                     .functions()
-                    .filter(|func| is_matching_test(func, filter))
+                    .filter(|func| {
+                        is_matching_test(func, filter, self.tcfg.config.symbolic.enabled)
+                    })
                     .map(|func| func.name.clone())
                     .collect::<Vec<_>>();
                 (source, name, tests)
@@ -498,7 +504,10 @@ impl MultiContractRunnerBuilder {
 
             // if it's a test, link it and add to deployable contracts
             if abi.constructor.as_ref().map(|c| c.inputs.is_empty()).unwrap_or(true) &&
-                abi.functions().any(|func| func.name.is_any_test())
+                abi.functions().any(|func| {
+                    func.name.is_any_test() ||
+                        (self.config.symbolic.enabled && func.name.is_symbolic_test())
+                })
             {
                 let Some(bytecode) =
                     contract.get_bytecode_bytes().map(|b| b.into_owned()).filter(|b| !b.is_empty())
@@ -513,10 +522,19 @@ impl MultiContractRunnerBuilder {
 
         let known_contracts = ContractsByArtifact::new(linked_contracts);
 
+        let mut storage_layouts = HashMap::default();
+        if self.config.invariant.show_state_diffs {
+            storage_layouts.extend(output.artifact_ids().filter_map(|(id, artifact)| {
+                let layout = artifact.storage_layout.clone()?;
+                Some((id.with_stripped_file_prefixes(root).identifier(), layout))
+            }));
+        }
+
         Ok(MultiContractRunner {
             contracts: deployable_contracts,
             revert_decoder,
             known_contracts,
+            storage_layouts,
             libs_to_deploy,
             libraries,
 
@@ -541,12 +559,20 @@ impl MultiContractRunnerBuilder {
     }
 }
 
-pub fn matches_contract(id: &ArtifactId, abi: &JsonAbi, filter: &dyn TestFilter) -> bool {
+pub fn matches_contract(
+    id: &ArtifactId,
+    abi: &JsonAbi,
+    filter: &dyn TestFilter,
+    symbolic: bool,
+) -> bool {
     (filter.matches_path(&id.source) && filter.matches_contract(&id.name)) &&
-        abi.functions().any(|func| is_matching_test(func, filter))
+        abi.functions().any(|func| is_matching_test(func, filter, symbolic))
 }
 
 /// Returns `true` if the function is a test function that matches the given filter.
-pub(crate) fn is_matching_test(func: &Function, filter: &dyn TestFilter) -> bool {
-    func.is_any_test() && filter.matches_test(&func.signature())
+///
+/// `check_` functions are only tests if symbolic testing is enabled.
+pub(crate) fn is_matching_test(func: &Function, filter: &dyn TestFilter, symbolic: bool) -> bool {
+    (func.is_any_test() || (symbolic && func.is_symbolic_test())) &&
+        filter.matches_test(&func.signature())
 }
@@ -0,0 +1,538 @@
+//! Mutation testing.
+//!
+//! Mutants are generated from the Solang parse tree of a source file. Each mutant is a single,
+//! small change to the source that is expected to break at least one test.
+
+use alloy_primitives::{keccak256, B256, U256};
+use eyre::Result;
+use forge_fmt::{FormatterError, Visitable, Visitor};
+use foundry_common::fs;
+use serde::{Deserialize, Serialize};
+use solang_parser::pt::{
+    CodeLocation, ContractDefinition, Expression, FunctionDefinition, Loc, SourceUnit, Statement,
+    VariableDeclaration, VariableDefinition,
+};
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// The kind of change a mutant makes to the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MutationKind {
+    /// An operator replaced with a different one, e.g. `+` with `-`.
+    Operator,
+    /// A condition of a branch, loop, `require` or `assert` negated.
+    NegatedCondition,
+    /// A literal replaced with a different value.
+    Constant,
+    /// An expression or `emit` statement deleted.
+    DeletedStatement,
+}
+
+impl fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Operator => "operator",
+            Self::NegatedCondition => "negated condition",
+            Self::Constant => "constant",
+            Self::DeletedStatement => "deleted statement",
+        })
+    }
+}
+
+/// A single change to a source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutant {
+    /// The path of the mutated source file.
+    pub path: PathBuf,
+    /// The kind of change.
+    pub kind: MutationKind,
+    /// The byte range of the source that is replaced.
+    pub span: Range<usize>,
+    /// The 1-based line of the start of the replaced source.
+    pub line: usize,
+    /// The replaced source.
+    pub original: String,
+    /// The source that replaces the original.
+    pub replacement: String,
+}
+
+impl Mutant {
+    /// Returns the source with the mutation applied.
+    pub fn apply(&self, source: &str) -> String {
+        let mut mutated = String::with_capacity(source.len() + self.replacement.len());
+        mutated.push_str(&source[..self.span.start]);
+        mutated.push_str(&self.replacement);
+        mutated.push_str(&source[self.span.end..]);
+        mutated
+    }
+
+    /// Returns the key of the mutant in the [`MutationCache`], given the hash of the unmutated
+    /// project sources.
+    pub fn cache_key(&self, sources_hash: B256) -> B256 {
+        let mut data = sources_hash.to_vec();
+        data.extend_from_slice(self.path.to_string_lossy().as_bytes());
+        data.extend_from_slice(&(self.span.start as u64).to_be_bytes());
+        data.extend_from_slice(&(self.span.end as u64).to_be_bytes());
+        data.extend_from_slice(self.replacement.as_bytes());
+        keccak256(data)
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}: ", self.path.display(), self.line, self.kind)?;
+        if self.replacement.is_empty() {
+            write!(f, "deleted `{}`", self.original)
+        } else {
+            write!(f, "`{}` -> `{}`", self.original, self.replacement)
+        }
+    }
+}
+
+/// Generates all mutants of the Solidity source file at `path`.
+pub fn find_mutants(path: &Path, source: &str) -> Result<Vec<Mutant>> {
+    let (mut pt, comments) = solang_parser::parse(source, 0).map_err(|diagnostics| {
+        FormatterError::Parse(source.to_string(), Some(path.to_path_buf()), diagnostics)
+    })?;
+    let comments = comments.iter().map(|comment| comment.loc()).collect();
+    let mut collector = MutantCollector { path, source, comments, mutants: Vec::new() };
+    pt.visit(&mut collector).unwrap_or_else(|never| match never {});
+    let mut mutants = collector.mutants;
+    mutants.sort_by(|a, b| (a.span.start, a.kind).cmp(&(b.span.start, b.kind)));
+    Ok(mutants)
+}
+
+/// Returns the replacements of an operator.
+fn operator_replacements(op: &str) -> &'static [&'static str] {
+    match op {
+        "+" => &["-"],
+        "-" => &["+"],
+        "*" => &["/"],
+        "/" => &["*"],
+        "%" => &["*"],
+        "**" => &["*"],
+        "<" => &["<="],
+        "<=" => &["<"],
+        ">" => &[">="],
+        ">=" => &[">"],
+        "==" => &["!="],
+        "!=" => &["=="],
+        "&&" => &["||"],
+        "||" => &["&&"],
+        "&" => &["|"],
+        "|" => &["&"],
+        "^" => &["&"],
+        "<<" => &[">>"],
+        ">>" => &["<<"],
+        "+=" => &["-="],
+        "-=" => &["+="],
+        "*=" => &["/="],
+        "/=" => &["*="],
+        "++" => &["--"],
+        "--" => &["++"],
+        _ => &[],
+    }
+}
+
+/// Collects the mutants of a parse tree.
+struct MutantCollector<'a> {
+    path: &'a Path,
+    source: &'a str,
+    /// Locations of the comments in the source.
+    comments: Vec<Loc>,
+    mutants: Vec<Mutant>,
+}
+
+impl MutantCollector<'_> {
+    fn push(&mut self, kind: MutationKind, span: Range<usize>, replacement: String) {
+        let original = self.source[span.clone()].to_string();
+        if original == replacement {
+            return
+        }
+        let line = self.source[..span.start].matches('\n').count() + 1;
+        self.mutants.push(Mutant {
+            path: self.path.to_path_buf(),
+            kind,
+            span,
+            line,
+            original,
+            replacement,
+        });
+    }
+
+    /// Adds the mutants swapping the operator found in the source between `start` and `end`.
+    ///
+    /// The operands are the only other tokens around the operator, so the operator is the first
+    /// occurrence in between them which is not part of a comment.
+    fn push_operator(&mut self, op: &str, start: usize, end: usize) {
+        let Some(gap) = self.source.get(start..end) else { return };
+        let Some(offset) = gap
+            .match_indices(op)
+            .map(|(offset, _)| start + offset)
+            .find(|offset| !self.in_comment(*offset))
+        else {
+            return
+        };
+        let span = offset..offset + op.len();
+        for replacement in operator_replacements(op) {
+            self.push(MutationKind::Operator, span.clone(), replacement.to_string());
+        }
+    }
+
+    /// Returns true if the byte offset is part of a comment.
+    fn in_comment(&self, offset: usize) -> bool {
+        self.comments.iter().any(|loc| loc.start() <= offset && offset < loc.end())
+    }
+
+    fn push_negation(&mut self, cond: &Expression) {
+        let loc = cond.loc();
+        let negated = format!("!({})", &self.source[loc.start()..loc.end()]);
+        self.push(MutationKind::NegatedCondition, loc.start()..loc.end(), negated);
+    }
+
+    /// Adds the mutant deleting the statement at `loc`, including its trailing semicolon.
+    fn push_deletion(&mut self, loc: Loc) {
+        let rest = &self.source[loc.end()..];
+        let end = match rest.trim_start().strip_prefix(';') {
+            Some(after) => self.source.len() - after.len(),
+            None => loc.end(),
+        };
+        self.push(MutationKind::DeletedStatement, loc.start()..end, String::new());
+    }
+}
+
+impl Visitor for MutantCollector<'_> {
+    type Error = Infallible;
+
+    fn visit_source_unit(&mut self, source_unit: &mut SourceUnit) -> Result<(), Self::Error> {
+        source_unit.0.visit(self)
+    }
+
+    fn visit_contract(&mut self, contract: &mut ContractDefinition) -> Result<(), Self::Error> {
+        contract.parts.visit(self)
+    }
+
+    fn visit_function(&mut self, func: &mut FunctionDefinition) -> Result<(), Self::Error> {
+        func.body.visit(self)
+    }
+
+    fn visit_var_definition(&mut self, var: &mut VariableDefinition) -> Result<(), Self::Error> {
+        var.initializer.visit(self)
+    }
+
+    fn visit_var_definition_stmt(
+        &mut self,
+        _loc: Loc,
+        _declaration: &mut VariableDeclaration,
+        expr: &mut Option<Expression>,
+    ) -> Result<(), Self::Error> {
+        expr.visit(self)
+    }
+
+    fn visit_block(
+        &mut self,
+        _loc: Loc,
+        _unchecked: bool,
+        statements: &mut Vec<Statement>,
+    ) -> Result<(), Self::Error> {
+        for statement in statements.iter() {
+            if let Statement::Expression(loc, _) | Statement::Emit(loc, _) = statement {
+                self.push_deletion(*loc);
+            }
+        }
+        statements.visit(self)
+    }
+
+    fn visit_expr(&mut self, loc: Loc, expr: &mut Expression) -> Result<(), Self::Error> {
+        let op = expr.operator();
+        match expr {
+            Expression::BoolLiteral(_, value) => {
+                self.push(MutationKind::Constant, loc.start()..loc.end(), (!*value).to_string());
+            }
+            Expression::NumberLiteral(_, value, exp, unit) => {
+                if exp.is_empty() && unit.is_none() {
+                    if let Ok(value) = U256::from_str_radix(&value.replace('_', ""), 10) {
+                        let span = loc.start()..loc.end();
+                        if value.is_zero() {
+                            self.push(MutationKind::Constant, span, "1".to_string());
+                        } else {
+                            self.push(MutationKind::Constant, span.clone(), "0".to_string());
+                            if let Some(next) = value.checked_add(U256::from(1)) {
+                                self.push(MutationKind::Constant, span, next.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            Expression::PostIncrement(_, inner) | Expression::PostDecrement(_, inner) => {
+                if let Some(op) = op {
+                    self.push_operator(op, inner.loc().end(), loc.end());
+                }
+                return inner.visit(self)
+            }
+            Expression::PreIncrement(_, inner) | Expression::PreDecrement(_, inner) => {
+                if let Some(op) = op {
+                    self.push_operator(op, loc.start(), inner.loc().start());
+                }
+                return inner.visit(self)
+            }
+            Expression::FunctionCall(_, callee, args) => {
+                if let Expression::Variable(ident) = &**callee {
+                    if matches!(ident.name.as_str(), "require" | "assert") {
+                        if let Some(cond) = args.first() {
+                            self.push_negation(cond);
+                        }
+                    }
+                }
+                callee.visit(self)?;
+                return args.visit(self)
+            }
+            Expression::NamedFunctionCall(_, callee, args) => {
+                callee.visit(self)?;
+                for arg in args {
+                    arg.expr.visit(self)?;
+                }
+                return Ok(())
+            }
+            Expression::FunctionCallBlock(_, callee, _) => return callee.visit(self),
+            Expression::ArraySubscript(_, array, index) => {
+                array.visit(self)?;
+                return index.visit(self)
+            }
+            Expression::ArraySlice(_, array, start, end) => {
+                array.visit(self)?;
+                start.visit(self)?;
+                return end.visit(self)
+            }
+            Expression::ArrayLiteral(_, exprs) => return exprs.visit(self),
+            Expression::ConditionalOperator(_, cond, first, second) => {
+                self.push_negation(cond);
+                cond.visit(self)?;
+                first.visit(self)?;
+                return second.visit(self)
+            }
+            _ => {}
+        }
+
+        match expr.components_mut() {
+            (Some(left), Some(right)) => {
+                if let Some(op) = op {
+                    self.push_operator(op, left.loc().end(), right.loc().start());
+                }
+                left.visit(self)?;
+                right.visit(self)
+            }
+            (Some(inner), None) | (None, Some(inner)) => inner.visit(self),
+            (None, None) => Ok(()),
+        }
+    }
+
+    fn visit_emit(&mut self, _loc: Loc, event: &mut Expression) -> Result<(), Self::Error> {
+        event.visit(self)
+    }
+
+    fn visit_return(
+        &mut self,
+        _loc: Loc,
+        expr: &mut Option<Expression>,
+    ) -> Result<(), Self::Error> {
+        expr.visit(self)
+    }
+
+    fn visit_if(
+        &mut self,
+        _loc: Loc,
+        cond: &mut Expression,
+        if_branch: &mut Box<Statement>,
+        else_branch: &mut Option<Box<Statement>>,
+        _is_first_stmt: bool,
+    ) -> Result<(), Self::Error> {
+        self.push_negation(cond);
+        cond.visit(self)?;
+        if_branch.visit(self)?;
+        else_branch.visit(self)
+    }
+
+    fn visit_do_while(
+        &mut self,
+        _loc: Loc,
+        body: &mut Statement,
+        cond: &mut Expression,
+    ) -> Result<(), Self::Error> {
+        self.push_negation(cond);
+        body.visit(self)?;
+        cond.visit(self)
+    }
+
+    fn visit_while(
+        &mut self,
+        _loc: Loc,
+        cond: &mut Expression,
+        body: &mut Statement,
+    ) -> Result<(), Self::Error> {
+        self.push_negation(cond);
+        cond.visit(self)?;
+        body.visit(self)
+    }
+
+    fn visit_for(
+        &mut self,
+        _loc: Loc,
+        init: &mut Option<Box<Statement>>,
+        cond: &mut Option<Box<Expression>>,
+        update: &mut Option<Box<Expression>>,
+        body: &mut Option<Box<Statement>>,
+    ) -> Result<(), Self::Error> {
+        if let Some(cond) = cond {
+            self.push_negation(cond);
+        }
+        init.visit(self)?;
+        cond.visit(self)?;
+        update.visit(self)?;
+        body.visit(self)
+    }
+}
+
+/// The outcome of running the tests against a mutant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MutantStatus {
+    /// At least one test failed.
+    Killed,
+    /// All tests passed.
+    Survived,
+    /// The mutated project failed to compile.
+    Invalid,
+}
+
+impl fmt::Display for MutantStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Killed => "KILLED",
+            Self::Survived => "SURVIVED",
+            Self::Invalid => "INVALID",
+        })
+    }
+}
+
+/// Outcomes of previous mutant runs, keyed by [`Mutant::cache_key`].
+///
+/// The key includes the hash of all project sources, so outcomes are only reused as long as the
+/// sources do not change.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MutationCache {
+    /// The mutant outcomes.
+    pub mutants: BTreeMap<B256, MutantStatus>,
+}
+
+impl MutationCache {
+    /// The file name of the cache in the project's cache directory.
+    pub const FILE_NAME: &'static str = "mutation-cache.json";
+
+    /// Reads the cache, returning an empty cache if it does not exist or is invalid.
+    pub fn read(path: &Path) -> Self {
+        fs::read_json_file(path).unwrap_or_default()
+    }
+
+    /// Writes the cache.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(path, self)?;
+        Ok(())
+    }
+}
+
+/// Returns the hash of the project sources, given as `(path, content)` pairs.
+pub fn sources_hash<'a>(sources: impl IntoIterator<Item = (&'a Path, &'a str)>) -> B256 {
+    let mut data = Vec::new();
+    for (path, content) in sources {
+        data.extend_from_slice(path.to_string_lossy().as_bytes());
+        data.push(0);
+        data.extend_from_slice(keccak256(content).as_slice());
+    }
+    keccak256(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mutants(source: &str) -> Vec<(MutationKind, String, String)> {
+        find_mutants(Path::new("src/A.sol"), source)
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.kind, m.original, m.replacement))
+            .collect()
+    }
+
+    #[test]
+    fn finds_mutants() {
+        let source = r#"
+contract A {
+    uint256 public x;
+
+    function f(uint256 a) public {
+        require(a > 0, "zero");
+        x += a * 2;
+        emit E(true);
+    }
+}
+"#;
+        let m = |kind, original: &str, replacement: &str| {
+            (kind, original.to_string(), replacement.to_string())
+        };
+        assert_eq!(
+            mutants(source),
+            vec![
+                m(MutationKind::DeletedStatement, r#"require(a > 0, "zero");"#, ""),
+                m(MutationKind::NegatedCondition, "a > 0", "!(a > 0)"),
+                m(MutationKind::Operator, ">", ">="),
+                m(MutationKind::Constant, "0", "1"),
+                m(MutationKind::DeletedStatement, "x += a * 2;", ""),
+                m(MutationKind::Operator, "+=", "-="),
+                m(MutationKind::Operator, "*", "/"),
+                m(MutationKind::Constant, "2", "0"),
+                m(MutationKind::Constant, "2", "3"),
+                m(MutationKind::DeletedStatement, "emit E(true);", ""),
+                m(MutationKind::Constant, "true", "false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_mutant() {
+        let source = "contract A { function f() public { x++; } }";
+        let mutant = find_mutants(Path::new("src/A.sol"), source)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.kind == MutationKind::Operator)
+            .unwrap();
+        assert_eq!(mutant.line, 1);
+        assert_eq!(mutant.apply(source), "contract A { function f() public { x--; } }");
+    }
+
+    #[test]
+    fn skips_operators_in_comments() {
+        let source = r#"
+contract A {
+    function f(uint256 a, uint256 b) public returns (bool) {
+        return a /* a > b */ > /* b > a */ b;
+    }
+}
+"#;
+        let mutant = find_mutants(Path::new("src/A.sol"), source)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.kind == MutationKind::Operator)
+            .unwrap();
+        assert_eq!(mutant.span.start, source.find("*/ >").unwrap() + 3);
+        assert!(mutant.apply(source).contains("a /* a > b */ >= /* b > a */ b;"));
+    }
+}
@@ -15,6 +15,7 @@ use foundry_evm::{
     decode::SkipReason,
     executors::{invariant::InvariantMetrics, RawCallResult},
     fuzz::{CounterExample, FuzzCase, FuzzFixtures, FuzzTestResult},
+    inspectors::cheatcodes::AssertionResult,
     traces::{CallTraceArena, CallTraceDecoder, TraceKind, Traces},
 };
 use serde::{Deserialize, Serialize};
@@ -417,6 +418,10 @@ pub struct TestResult {
     /// Any captured gas snapshots along the test's execution which should be accumulated.
     pub gas_snapshots: BTreeMap<String, BTreeMap<String, String>>,
 
+    /// Results of the assertion functions executed by `assertionEx` calls, one entry per call.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_results: Vec<Vec<AssertionResult>>,
+
     /// Deprecated cheatcodes (mapped to their replacements, if any) used in current test.
     #[serde(skip)]
     pub deprecated_cheatcodes: HashMap<&'static str, Option<&'static str>>,
@@ -450,6 +455,9 @@ impl fmt::Display for TestResult {
                                 s.push_str("]\n\t[Sequence]\n");
                                 for ex in sequence {
                                     writeln!(s, "\t\t{ex}").unwrap();
+                                    for diff in &ex.state_diff {
+                                        writeln!(s, "\t\t\t{diff}").unwrap();
+                                    }
                                 }
                             }
                         }
@@ -535,6 +543,7 @@ impl TestResult {
         if let Some(cheatcodes) = raw_call_result.cheatcodes {
             self.breakpoints = cheatcodes.breakpoints;
             self.gas_snapshots = cheatcodes.gas_snapshots;
+            self.assertion_results = cheatcodes.assertion_results;
             self.deprecated_cheatcodes = cheatcodes.deprecated;
         }
     }
@@ -12,7 +12,7 @@ use alloy_json_abi::Function;
 use alloy_primitives::{address, map::HashMap, Address, U256};
 use eyre::Result;
 use foundry_common::{contracts::ContractsByAddress, TestFunctionExt, TestFunctionKind};
-use foundry_config::Config;
+use foundry_config::{AssertionTarget, Config};
 use foundry_evm::{
     constants::CALLER,
     decode::RevertDecoder,
@@ -21,7 +21,7 @@ use foundry_evm::{
         invariant::{
             check_sequence, replay_error, replay_run, InvariantExecutor, InvariantFuzzError,
         },
-        CallResult, EvmError, Executor, ITest, RawCallResult,
+        CallResult, EvmError, Executor, FuzzAssertions, ITest, RawCallResult, SymbolicExecutor,
     },
     fuzz::{
         fixture_name,
@@ -354,7 +354,7 @@ impl<'a> ContractRunner<'a> {
             .contract
             .abi
             .functions()
-            .filter(|func| is_matching_test(func, filter))
+            .filter(|func| is_matching_test(func, filter, self.config.symbolic.enabled))
             .collect::<Vec<_>>();
         debug!(
             "Found {} test functions out of {} in {:?}",
@@ -492,6 +492,7 @@ impl<'a> FunctionRunner<'a> {
         match kind {
             TestFunctionKind::UnitTest { should_fail } => self.run_unit_test(func, should_fail),
             TestFunctionKind::FuzzTest { should_fail } => self.run_fuzz_test(func, should_fail),
+            TestFunctionKind::SymbolicTest => self.run_symbolic_test(func, false),
             TestFunctionKind::InvariantTest => {
                 self.run_invariant_test(func, call_after_invariant, identified_contracts.unwrap())
             }
@@ -561,6 +562,14 @@ impl<'a> FunctionRunner<'a> {
 
         let runner = self.invariant_runner();
         let invariant_config = &self.config.invariant;
+        let assertions =
+            match self.fuzz_assertions(&invariant_config.assertions, identified_contracts) {
+                Ok(assertions) => assertions,
+                Err(err) => {
+                    self.result.single_fail(Some(format!("failed to set up assertions: {err}")));
+                    return self.result;
+                }
+            };
 
         let mut evm = InvariantExecutor::new(
             self.clone_executor(),
@@ -568,6 +577,8 @@ impl<'a> FunctionRunner<'a> {
             invariant_config.clone(),
             identified_contracts,
             &self.cr.mcr.known_contracts,
+            assertions.clone(),
+            invariant_config.corpus_file(self.cr.name, &func.name),
         );
         let invariant_contract = InvariantContract {
             address: self.address,
@@ -580,7 +591,7 @@ impl<'a> FunctionRunner<'a> {
         let failure_file = failure_dir.join(&invariant_contract.invariant_function.name);
 
         // Try to replay recorded failure if any.
-        if let Ok(call_sequence) =
+        if let Ok(mut call_sequence) =
             foundry_common::fs::read_json_file::<Vec<BaseCounterExample>>(failure_file.as_path())
         {
             // Create calls from failed sequence and check if invariant still broken.
@@ -602,6 +613,7 @@ impl<'a> FunctionRunner<'a> {
                 invariant_contract.invariant_function.selector().to_vec().into(),
                 invariant_config.fail_on_revert,
                 invariant_contract.call_after_invariant,
+                assertions.as_ref(),
             ) {
                 if !success {
                     let _= sh_warn!("\
@@ -611,7 +623,7 @@ impl<'a> FunctionRunner<'a> {
                     );
                     // If sequence still fails then replay error to collect traces and
                     // exit without executing new runs.
-                    let _ = replay_run(
+                    if let Ok(replayed) = replay_run(
                         &invariant_contract,
                         self.clone_executor(),
                         &self.cr.mcr.known_contracts,
@@ -621,7 +633,13 @@ impl<'a> FunctionRunner<'a> {
                         &mut self.result.coverage,
                         &mut self.result.deprecated_cheatcodes,
                         &txes,
-                    );
+                        invariant_config.show_state_diffs.then_some(&self.cr.mcr.storage_layouts),
+                    ) {
+                        // Show the state diffs recorded while replaying the persisted sequence.
+                        for (step, replayed) in call_sequence.iter_mut().zip(replayed) {
+                            step.state_diff = replayed.state_diff;
+                        }
+                    }
                     self.result.invariant_replay_fail(
                         replayed_entirely,
                         &invariant_contract.invariant_function.name,
@@ -671,6 +689,7 @@ impl<'a> FunctionRunner<'a> {
                         &mut self.result.coverage,
                         &mut self.result.deprecated_cheatcodes,
                         progress.as_ref(),
+                        invariant_config.show_state_diffs.then_some(&self.cr.mcr.storage_layouts),
                     ) {
                         Ok(call_sequence) => {
                             if !call_sequence.is_empty() {
@@ -707,6 +726,7 @@ impl<'a> FunctionRunner<'a> {
                     &mut self.result.coverage,
                     &mut self.result.deprecated_cheatcodes,
                     &invariant_result.last_run_inputs,
+                    None,
                 ) {
                     error!(%err, "Failed to replay last invariant run");
                 }
@@ -742,13 +762,35 @@ impl<'a> FunctionRunner<'a> {
 
         let runner = self.fuzz_runner();
         let fuzz_config = self.config.fuzz.clone();
+        let assertions = if fuzz_config.assertions.is_empty() {
+            None
+        } else {
+            let deployed_contracts = load_contracts(
+                self.setup.traces.iter().map(|(_, t)| &t.arena),
+                &self.cr.mcr.known_contracts,
+            );
+            match self.fuzz_assertions(&fuzz_config.assertions, &deployed_contracts) {
+                Ok(assertions) => assertions,
+                Err(err) => {
+                    self.result.single_fail(Some(format!("failed to set up assertions: {err}")));
+                    return self.result;
+                }
+            }
+        };
 
         let progress =
             start_fuzz_progress(self.cr.progress, self.cr.name, &func.name, fuzz_config.runs);
 
         // Run fuzz test.
-        let fuzzed_executor =
-            FuzzedExecutor::new(self.executor.into_owned(), runner, self.tcfg.sender, fuzz_config);
+        let corpus_file = fuzz_config.corpus_file(self.cr.name, &func.name);
+        let fuzzed_executor = FuzzedExecutor::new(
+            self.executor.into_owned(),
+            runner,
+            self.tcfg.sender,
+            fuzz_config,
+            assertions,
+            corpus_file,
+        );
         let result = fuzzed_executor.fuzz(
             func,
             &self.setup.fuzz_fixtures,
@@ -762,6 +804,26 @@ impl<'a> FunctionRunner<'a> {
         self.result
     }
 
+    /// Runs a test symbolically, with symbolic arguments.
+    ///
+    /// Before test txes are applied like for fuzz tests. Paths failing the test are replayed
+    /// concretely and reported as the counterexample.
+    fn run_symbolic_test(mut self, func: &Function, should_fail: bool) -> TestResult {
+        if self.prepare_test(func).is_err() {
+            return self.result;
+        }
+
+        let symbolic_executor = SymbolicExecutor::new(
+            self.executor.into_owned(),
+            self.tcfg.sender,
+            self.config.symbolic.clone(),
+        );
+        let result =
+            symbolic_executor.explore(func, self.address, should_fail, &self.cr.mcr.revert_decoder);
+        self.result.fuzz_result(result);
+        self.result
+    }
+
     /// Prepares single unit test and fuzz test execution:
     /// - set up the test result and executor
     /// - check if before test txes are configured and apply them in order
@@ -838,6 +900,22 @@ impl<'a> FunctionRunner<'a> {
         fuzzer_with_cases(self.config.fuzz.seed, config.runs, config.max_assume_rejects, None)
     }
 
+    /// Deploys the targeted assertion contracts for the adopters among the contracts deployed by
+    /// the test setup, if any.
+    fn fuzz_assertions(
+        &self,
+        targets: &[AssertionTarget],
+        deployed_contracts: &ContractsByAddress,
+    ) -> Result<Option<FuzzAssertions>> {
+        FuzzAssertions::new(
+            &self.executor,
+            targets,
+            deployed_contracts,
+            &self.cr.mcr.known_contracts,
+            &self.config.credible,
+        )
+    }
+
     fn clone_executor(&self) -> Executor {
         self.executor.clone().into_owned()
     }
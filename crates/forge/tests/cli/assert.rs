@@ -0,0 +1,210 @@
+//! Contains tests for the `forge assert` subcommand
+
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{address, bytes, Address, TxHash};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{eth::EthApi, spawn, NodeConfig, NodeHandle};
+use foundry_test_utils::{util::OutputExt, TestCommand, TestProject};
+
+/// The address of the first contract deployed by the first dev account.
+const COUNTER: Address = address!("5FbDB2315678afecb367f032d93F642f64180aa3");
+
+/// Spawns a node, deploys the counter to it and increments the counter twice, returning the hashes
+/// of the increments. The second increment invalidates the assertion.
+async fn setup_counter(
+    prj: &TestProject,
+    cmd: &mut TestCommand,
+) -> (EthApi, NodeHandle, Vec<TxHash>) {
+    let (api, handle) = spawn(NodeConfig::test().silent()).await;
+    let rpc = handle.http_endpoint();
+    let dev = handle.dev_accounts().next().unwrap();
+
+    prj.add_source(
+        "Counter.sol",
+        r#"
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number++;
+    }
+}
+   "#,
+    )
+    .unwrap();
+    prj.add_source(
+        "CounterAssertion.sol",
+        r#"
+interface ICounter {
+    function number() external view returns (uint256);
+}
+
+contract CounterAssertion {
+    ICounter counter;
+
+    constructor(address counter_) {
+        counter = ICounter(counter_);
+    }
+
+    function fnSelectors() external pure returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertAtMostOne.selector;
+    }
+
+    function assertAtMostOne() external view {
+        require(counter.number() <= 1, "number above one");
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse()
+        .args([
+            "create",
+            "src/Counter.sol:Counter",
+            "--rpc-url",
+            rpc.as_str(),
+            "--from",
+            &dev.to_string(),
+            "--unlocked",
+            "--broadcast",
+        ])
+        .assert_success();
+
+    // Increment the counter twice, the second increment invalidates the assertion.
+    let provider = handle.http_provider();
+    let mut hashes: Vec<TxHash> = Vec::new();
+    for _ in 0..2 {
+        let tx = TransactionRequest::default()
+            .with_from(dev)
+            .with_to(COUNTER)
+            .with_input(bytes!("d09de08a"));
+        let receipt = provider
+            .send_transaction(WithOtherFields::new(tx))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        hashes.push(receipt.transaction_hash);
+    }
+
+    (api, handle, hashes)
+}
+
+/// Returns the arguments to run the counter assertion against the node at `rpc`.
+fn assert_args(rpc: &str) -> Vec<String> {
+    let counter = COUNTER.to_string();
+    [
+        "assert",
+        "CounterAssertion",
+        "--adopter",
+        counter.as_str(),
+        "--constructor-args",
+        counter.as_str(),
+        "--rpc-url",
+        rpc,
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+forgetest_async!(can_run_assertions_against_transactions, |prj, cmd| {
+    let (_api, handle, hashes) = setup_counter(&prj, &mut cmd).await;
+    let rpc = handle.http_endpoint();
+
+    let tx_args = |hash: TxHash| {
+        let mut args = assert_args(&rpc);
+        args.extend(["--tx".to_string(), hash.to_string()]);
+        args
+    };
+
+    let output = cmd
+        .forge_fuse()
+        .args(tx_args(hashes[0]))
+        .arg("--json")
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let outcomes: serde_json::Value = serde_json::from_str(output.lines().last().unwrap()).unwrap();
+    assert_eq!(outcomes[0]["tx_hash"], hashes[0].to_string());
+    assert_eq!(outcomes[0]["assertions_ran"], 1);
+    assert_eq!(outcomes[0]["valid"], true);
+
+    cmd.forge_fuse().args(tx_args(hashes[1])).assert_failure().stderr_eq(str![[r#"
+Error: 1 transaction(s) invalidated the assertions
+
+"#]]);
+
+    let output = cmd
+        .forge_fuse()
+        .args(tx_args(hashes[1]))
+        .arg("--json")
+        .assert_failure()
+        .get_output()
+        .stdout_lossy();
+    let outcomes: serde_json::Value = serde_json::from_str(output.lines().last().unwrap()).unwrap();
+    assert_eq!(outcomes[0]["valid"], false);
+    assert_eq!(outcomes[0]["failures"][0]["function"], "assertAtMostOne()");
+    assert_eq!(outcomes[0]["failures"][0]["reason"], "revert: number above one");
+});
+
+forgetest_async!(can_run_assertions_against_block_range, |prj, cmd| {
+    let (_api, handle, hashes) = setup_counter(&prj, &mut cmd).await;
+    let rpc = handle.http_endpoint();
+
+    // The counter is deployed in block 1 and incremented in blocks 2 and 3.
+    let output = cmd
+        .forge_fuse()
+        .args(assert_args(&rpc))
+        .args(["--from-block", "1", "--to-block", "2", "--json"])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let outcomes: serde_json::Value = serde_json::from_str(output.lines().last().unwrap()).unwrap();
+    let outcome = |outcomes: &serde_json::Value, hash: TxHash| {
+        outcomes
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|outcome| outcome["tx_hash"] == hash.to_string())
+            .cloned()
+            .unwrap()
+    };
+    assert_eq!(outcome(&outcomes, hashes[0])["valid"], true);
+
+    let output = cmd
+        .forge_fuse()
+        .args(assert_args(&rpc))
+        .args(["--from-block", "1", "--to-block", "3", "--json"])
+        .assert_failure()
+        .get_output()
+        .stdout_lossy();
+    let outcomes: serde_json::Value = serde_json::from_str(output.lines().last().unwrap()).unwrap();
+    assert_eq!(outcome(&outcomes, hashes[0])["valid"], true);
+    let failed = outcome(&outcomes, hashes[1]);
+    assert_eq!(failed["block"], 3);
+    assert_eq!(failed["valid"], false);
+    assert_eq!(failed["failures"][0]["function"], "assertAtMostOne()");
+
+    cmd.forge_fuse()
+        .args(assert_args(&rpc))
+        .args(["--from-block", "3", "--to-block", "2"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: --from-block must not be greater than --to-block
+
+"#]]);
+
+    cmd.forge_fuse()
+        .args(assert_args(&rpc))
+        .args(["--from-block", "0", "--to-block", "0"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: cannot run assertions against transactions of the genesis block
+
+"#]]);
+});
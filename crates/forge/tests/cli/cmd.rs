@@ -3342,3 +3342,104 @@ Ran 1 test suite [ELAPSED]: 1 tests passed, 0 failed, 0 skipped (1 total tests)
             .is_json(),
         );
 });
+
+// tests that assertion functions executed through `assertionEx` are included in gas reports
+forgetest!(gas_report_assertions, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.insert_vm();
+    prj.add_source(
+        "Assertions.t.sol",
+        r#"
+import {Vm} from "./Vm.sol";
+import {DSTest} from "./test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number++;
+    }
+}
+
+contract CounterAssertion {
+    Counter counter;
+
+    constructor(address counter_) {
+        counter = Counter(counter_);
+    }
+
+    function fnSelectors() external pure returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertAtMostFive.selector;
+    }
+
+    function assertAtMostFive() external view {
+        require(counter.number() <= 5, "number above five");
+    }
+}
+
+contract AssertionsTest is DSTest {
+    Vm vm = Vm(HEVM_ADDRESS);
+
+    struct SimpleTransaction {
+        address from;
+        address to;
+        uint256 value;
+        bytes data;
+    }
+
+    function testAssertions() public {
+        Counter counter = new Counter();
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(0xdead),
+            to: address(counter),
+            value: 0,
+            data: abi.encodeWithSelector(Counter.increment.selector)
+        });
+        bytes memory assertion =
+            abi.encodePacked(type(CounterAssertion).creationCode, abi.encode(address(counter)));
+        vm.assertionEx(abi.encode(transaction), address(counter), assertion, "CounterAssertion");
+        counter.increment();
+        vm.assertionEx(abi.encode(transaction), address(counter), assertion, "CounterAssertion");
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    prj.write_config(Config {
+        gas_reports: (vec!["CounterAssertion".to_string()]),
+        ..Default::default()
+    });
+
+    cmd.forge_fuse().arg("test").arg("--gas-report").assert_success().stdout_eq(str![[r#"
+...
+╭[..]╮
+| CounterAssertion Assertions [..]|
++[..]+
+| Assertion Function [..]| Min [..]| Avg [..]| Median [..]| Max [..]| # Calls |
+|[..]|
+| assertAtMostFive [..]| 2       |
+╰[..]╯
+
+
+Ran 1 test suite [ELAPSED]: 1 tests passed, 0 failed, 0 skipped (1 total tests)
+
+"#]]);
+
+    let output = cmd
+        .forge_fuse()
+        .arg("test")
+        .arg("--gas-report")
+        .arg("--json")
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let report: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(report.as_array().unwrap().len(), 1);
+    assert_eq!(report[0]["assertion_contract"], "CounterAssertion");
+    let gas = &report[0]["functions"]["assertAtMostFive"];
+    assert_eq!(gas["calls"], 2);
+    assert!(gas["min"].as_u64().unwrap() > 0);
+    assert!(gas["min"].as_u64() <= gas["max"].as_u64());
+});
@@ -86,6 +86,10 @@ forgetest!(can_extract_config_values, |prj, cmd| {
             failure_persist_dir: Some("test-cache/fuzz".into()),
             ..Default::default()
         },
+        symbolic: Default::default(),
+        credible: Default::default(),
+        coverage: Default::default(),
+        lint: Default::default(),
         ffi: true,
         always_use_create_2_factory: false,
         prompt_timeout: 0,
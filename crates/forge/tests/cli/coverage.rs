@@ -1,4 +1,5 @@
 use foundry_common::fs;
+use foundry_config::Config;
 use foundry_test_utils::{
     snapbox::{Data, IntoData},
     TestCommand, TestProject,
@@ -182,6 +183,68 @@ forgetest_init!(basic_crlf, |prj, cmd| {
     basic_base(prj, cmd);
 });
 
+forgetest_init!(html, |prj, cmd| {
+    cmd.args(["coverage", "--report=html"]).assert_success();
+
+    let report = prj.root().join("coverage-report");
+    let index = fs::read_to_string(report.join("index.html")).unwrap();
+    assert!(index.contains(r#"<a href="src/Counter.sol.html">src/Counter.sol</a>"#));
+    assert!(index.contains(r#"<td class="high">100.00% (4/4)</td>"#));
+    assert!(index.contains(r#"<td class="low">44.44% (4/9)</td>"#));
+
+    let counter = fs::read_to_string(report.join("src/Counter.sol.html")).unwrap();
+    assert!(counter.contains(r#"<a href="../index.html">All files</a>"#));
+    assert!(counter.contains(r##"<a href="#L7">Counter.setNumber</a></td><td>7</td><td>258</td>"##));
+    assert!(counter
+        .contains(r#"<tr id="L12" class="hit"><td class="line">12</td><td class="hits">1</td>"#));
+
+    let script = fs::read_to_string(report.join("script/Counter.s.sol.html")).unwrap();
+    assert!(script
+        .contains(r#"<tr id="L13" class="miss"><td class="line">13</td><td class="hits">0</td>"#));
+});
+
+forgetest_init!(cobertura_and_json, |prj, cmd| {
+    cmd.args(["coverage", "--report=cobertura", "--report=json"]).assert_success();
+
+    let cobertura = fs::read_to_string(prj.root().join("cobertura.xml")).unwrap();
+    assert!(cobertura
+        .contains(r#"lines-covered="4" lines-valid="9" branches-covered="0" branches-valid="0""#));
+    assert!(cobertura.contains(
+        r#"<package name="src" line-rate="1.0000" branch-rate="1.0000" complexity="0">"#
+    ));
+    assert!(cobertura.contains(r#"<class name="src/Counter.sol" filename="src/Counter.sol" line-rate="1.0000" branch-rate="1.0000" complexity="0">"#));
+    assert!(cobertura.contains(
+        r#"<method name="Counter.setNumber" signature="" line-rate="1" branch-rate="1">"#
+    ));
+    assert!(cobertura.contains(r#"<line number="7" hits="258" branch="false"/>"#));
+    assert!(cobertura.contains(r#"timestamp="0">"#));
+
+    // The timestamp is taken from `SOURCE_DATE_EPOCH` so that reports are reproducible.
+    cmd.env("SOURCE_DATE_EPOCH", "1700000000").assert_success();
+    let reproducible = fs::read_to_string(prj.root().join("cobertura.xml")).unwrap();
+    assert_eq!(
+        reproducible,
+        cobertura.replace(r#"timestamp="0">"#, r#"timestamp="1700000000000">"#)
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(prj.root().join("coverage.json")).unwrap())
+            .unwrap();
+    assert_eq!(json["total"]["lines"], serde_json::json!({ "hits": 4, "total": 9 }));
+    let counter = &json["files"][1];
+    assert_eq!(counter["path"], "src/Counter.sol");
+    assert_eq!(counter["summary"]["functions"], serde_json::json!({ "hits": 2, "total": 2 }));
+    let set_number = counter["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["kind"] == "function" && item["name"] == "setNumber")
+        .unwrap();
+    assert_eq!(set_number["contract"], "Counter");
+    assert_eq!(set_number["hits"], 258);
+    assert_eq!(set_number["lines"]["start"], 7);
+});
+
 forgetest!(setup, |prj, cmd| {
     prj.insert_ds_test();
     prj.add_source(
@@ -238,6 +301,130 @@ contract AContractTest is DSTest {
 "#]]);
 });
 
+forgetest_init!(thresholds, |prj, cmd| {
+    let config = prj.root().join(Config::FILE_NAME);
+    let mut toml = fs::read_to_string(&config).unwrap();
+    toml.push_str(
+        r#"
+[coverage]
+min_lines = 50
+
+[[coverage.paths]]
+path = "src/**"
+min_lines = 100
+
+[[coverage.paths]]
+path = "script/**"
+min_functions = 10
+"#,
+    );
+    fs::write(&config, toml).unwrap();
+
+    cmd.arg("coverage")
+        .assert_failure()
+        .stdout_eq(str![[r#"
+...
+╭----------------------+-----------+----------+---------╮
+| File                 | Metric    | Coverage | Minimum |
++=======================================================+
+| script/Counter.s.sol | functions | 0.00%    | 10.00%  |
+|----------------------+-----------+----------+---------|
+| Total                | lines     | 44.44%   | 50.00%  |
+╰----------------------+-----------+----------+---------╯
+
+"#]])
+        .stderr_eq(str![[r#"
+Error: 2 coverage thresholds not met
+
+"#]]);
+});
+
+forgetest!(diff_base, |prj, cmd| {
+    prj.insert_ds_test();
+    let contract = |body: &str| {
+        prj.add_source(
+            "AContract.sol",
+            &format!(
+                r#"
+contract AContract {{
+    int public i;
+
+    function foo() public {{
+        {body}
+    }}
+}}
+    "#
+            ),
+        )
+        .unwrap();
+    };
+    contract("i = 1;");
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    function testFoo() public {
+        new AContract().foo();
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.git_init();
+    cmd.git_add();
+    cmd.git_commit("base");
+
+    // Change the covered line and add an uncovered function.
+    contract(
+        r#"i = 2;
+    }
+
+    function bar() public {
+        i = 3;"#,
+    );
+
+    let output =
+        cmd.args(["coverage", "--diff-base", "HEAD"]).assert_success().get_output().stdout_lossy();
+    assert!(output.contains("% Changed Lines"), "{output}");
+    assert!(output.contains("33.33% (1/3)"), "{output}");
+
+    cmd.forge_fuse()
+        .args(["coverage", "--diff-base", "HEAD", "--diff-fail-under", "80"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: coverage of lines changed since `HEAD` is 33.33%, below the required 80.00%
+
+"#]]);
+
+    // New files which are not tracked yet are fully changed.
+    prj.add_source(
+        "BContract.sol",
+        r#"
+contract BContract {
+    int public i;
+
+    function foo() public {
+        i = 1;
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    let output = cmd
+        .forge_fuse()
+        .args(["coverage", "--diff-base", "HEAD"])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    assert!(output.contains("src/BContract.sol"), "{output}");
+    assert!(output.contains("25.00% (1/4)"), "{output}");
+});
+
 forgetest!(no_match, |prj, cmd| {
     prj.insert_ds_test();
     prj.add_source(
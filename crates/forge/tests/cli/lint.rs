@@ -0,0 +1,72 @@
+use foundry_config::{Config, LintConfig};
+
+const VAULT: &str = r#"
+/// @notice Holds ether.
+contract Vault {
+    /// @notice Sends the balance to the caller.
+    function withdraw() external {
+        require(tx.origin == msg.sender);
+        payable(msg.sender).call{value: address(this).balance}("");
+    }
+
+    function deposit() external payable {}
+}
+"#;
+
+forgetest!(text, |prj, cmd| {
+    prj.add_source("Vault.sol", VAULT).unwrap();
+
+    cmd.arg("lint").assert_code(1).stdout_eq(str![[r#"
+medium[tx-origin]: `tx.origin` is used; use `msg.sender` for authorization
+  --> src/Vault.sol:[..]
+
+high[unchecked-call]: the success of low-level `call` is not checked
+  --> src/Vault.sol:[..]
+
+info[missing-natspec]: function `deposit` has no NatSpec documentation
+  --> src/Vault.sol:[..]
+
+3 findings
+
+"#]]);
+});
+
+forgetest!(config_and_inline_comments, |prj, cmd| {
+    prj.add_source(
+        "Vault.sol",
+        &VAULT.replace(
+            "payable(",
+            "// forgelint: disable-next-line(unchecked-call)\n        payable(",
+        ),
+    )
+    .unwrap();
+    let mut lint = LintConfig::default();
+    lint.rules.insert("missing-natspec".to_string(), false);
+    prj.write_config(Config { lint: lint.clone(), ..Default::default() });
+
+    cmd.args(["lint", "--format", "json"]).assert_code(1).stdout_eq(
+        str![[r#"
+[
+  {
+    "column": [..],
+    "endColumn": [..],
+    "endLine": [..],
+    "line": [..],
+    "lint": "tx-origin",
+    "message": "`tx.origin` is used; use `msg.sender` for authorization",
+    "path": "src/Vault.sol",
+    "severity": "medium"
+  }
+]
+"#]]
+        .is_json(),
+    );
+
+    lint.ignore.push("src/Vault.sol".to_string());
+    prj.write_config(Config { lint, ..Default::default() });
+    cmd.forge_fuse().args(["lint", "--format", "sarif"]).assert_success().stdout_eq(str![[r#"
+...
+      "results": [],
+...
+"#]]);
+});
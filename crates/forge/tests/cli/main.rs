@@ -4,6 +4,7 @@ extern crate foundry_test_utils;
 pub mod constants;
 pub mod utils;
 
+mod assert;
 mod bind_json;
 mod build;
 mod cache;
@@ -18,7 +19,9 @@ mod doc;
 mod eip712;
 mod geiger;
 mod inline_config;
+mod lint;
 mod multi_script;
+mod mutate;
 mod odyssey;
 mod script;
 mod soldeer;
@@ -0,0 +1,53 @@
+forgetest!(survived, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Counter.sol",
+        r#"
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number += 1;
+    }
+}
+    "#,
+    )
+    .unwrap();
+    prj.add_source(
+        "CounterTest.sol",
+        r#"
+import "./test.sol";
+import {Counter} from "./Counter.sol";
+
+contract CounterTest is DSTest {
+    function testIncrement() public {
+        Counter counter = new Counter();
+        counter.increment();
+        assertGt(counter.number(), 0);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["mutate", "--mutate-path", "src/Counter.sol"]).assert_success().stdout_eq(str![[
+        r#"
+...
+Testing 4 mutants of 1 files (0 cached)...
+[SURVIVED] src/Counter.sol:[..]: constant: `1` -> `2`
+
+Mutation testing: 3 killed, 1 survived, 0 invalid; mutation score 75.00%
+
+"#
+    ]]);
+
+    // Outcomes are cached until the sources change.
+    cmd.assert_success().stdout_eq(str![[r#"
+...
+Testing 4 mutants of 1 files (4 cached)...
+[SURVIVED] src/Counter.sol:[..]: constant: `1` -> `2`
+
+Mutation testing: 3 killed, 1 survived, 0 invalid; mutation score 75.00%
+
+"#]]);
+});
@@ -2,7 +2,7 @@
 
 use alloy_primitives::U256;
 use anvil::{spawn, NodeConfig};
-use foundry_config::{Config, FuzzConfig};
+use foundry_config::{Config, CredibleConfig, FuzzConfig, InvariantConfig, SymbolicConfig};
 use foundry_test_utils::{
     rpc, str,
     util::{OutputExt, OTHER_SOLC_VERSION, SOLC_VERSION},
@@ -2834,3 +2834,443 @@ forgetest!(test_fail_deprecation_warning, |prj, cmd| {
         .stderr_eq(r#"Warning: `testFail*` has been deprecated and will be removed in the next release. Consider changing to test_Revert[If|When]_Condition and expecting a revert. Found deprecated testFail* function(s): testFail_deprecated, testFail_deprecated2.
 "#);
 });
+
+// checks that coverage-guided fuzzing persists the inputs reaching new code in a corpus
+forgetest_init!(should_persist_fuzz_corpus, |prj, cmd| {
+    prj.wipe_contracts();
+    let config = Config {
+        fuzz: FuzzConfig { coverage_guided: true, ..FuzzConfig::new("cache/fuzz".into()) },
+        ..Default::default()
+    };
+    prj.write_config(config);
+
+    prj.add_test(
+        "CorpusFuzz.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract CorpusFuzzTest is Test {
+    function testFuzz_Branches(uint8 x) public pure {
+        if (x < 128) {
+            assertLt(x, 128);
+        } else {
+            assertGe(x, 128);
+        }
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]).assert_success();
+
+    let corpus_file = prj.root().join("cache/fuzz/corpus/CorpusFuzzTest/testFuzz_Branches.json");
+    let corpus: Vec<String> = foundry_common::fs::read_json_file(&corpus_file).unwrap();
+    assert!(!corpus.is_empty());
+
+    // the persisted corpus is replayed and extended by later runs
+    cmd.assert_success();
+    let replayed: Vec<String> = foundry_common::fs::read_json_file(&corpus_file).unwrap();
+    assert!(replayed.len() >= corpus.len());
+});
+
+// checks that invariant call sequences reaching new code are persisted in a shareable corpus
+forgetest_init!(should_persist_invariant_corpus, |prj, cmd| {
+    prj.wipe_contracts();
+    let config = Config {
+        invariant: InvariantConfig {
+            runs: 16,
+            depth: 10,
+            coverage_guided: true,
+            corpus_dir: Some("test/corpus".into()),
+            ..InvariantConfig::new("cache/invariant".into())
+        },
+        ..Default::default()
+    };
+    prj.write_config(config);
+
+    prj.add_test(
+        "CorpusInvariant.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function inc() external {
+        number += 1;
+    }
+
+    function reset() external {
+        if (number > 2) {
+            number = 0;
+        }
+    }
+}
+
+contract CorpusInvariantTest is Test {
+    Counter public counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function invariant_Bounded() public view {
+        assertLt(counter.number(), type(uint256).max);
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]).assert_success();
+
+    let corpus_file = prj.root().join("test/corpus/CorpusInvariantTest/invariant_Bounded.json");
+    let corpus: Vec<serde_json::Value> = foundry_common::fs::read_json_file(&corpus_file).unwrap();
+    assert!(!corpus.is_empty());
+
+    // the corpus is kept by `forge clean` and extended by later campaigns
+    cmd.forge_fuse().arg("clean").assert_success();
+    cmd.forge_fuse().arg("test").assert_success();
+    let replayed: Vec<serde_json::Value> =
+        foundry_common::fs::read_json_file(&corpus_file).unwrap();
+    assert!(replayed.len() >= corpus.len());
+});
+
+// checks that the storage changes of each call of an invariant counterexample are displayed
+forgetest_init!(should_show_invariant_state_diffs, |prj, cmd| {
+    prj.wipe_contracts();
+    let config = Config {
+        invariant: InvariantConfig {
+            depth: 10,
+            show_state_diffs: true,
+            ..InvariantConfig::new("cache/invariant".into())
+        },
+        ..Default::default()
+    };
+    prj.write_config(config);
+
+    prj.add_test(
+        "StateDiffInvariant.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function inc() external {
+        number += 1;
+    }
+}
+
+contract StateDiffInvariantTest is Test {
+    Counter public counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function invariant_Below2() public view {
+        assertLt(counter.number(), 2);
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]).assert_failure().stdout_eq(str![[r#"
+...
+[FAIL: [..]]
+	[Sequence]
+		sender=[..] addr=[test/StateDiffInvariant.t.sol:Counter]0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f calldata=inc() args=[]
+			@ number (slot 0) on 0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f: 0 → 1
+		sender=[..] addr=[test/StateDiffInvariant.t.sol:Counter]0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f calldata=inc() args=[]
+			@ number (slot 0) on 0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f: 1 → 2
+...
+"#]]);
+});
+
+// tests that `check_` tests are explored symbolically and report what cannot be explored
+forgetest_init!(should_report_unsupported_symbolic_tests, |prj, cmd| {
+    prj.wipe_contracts();
+    let config = Config {
+        symbolic: SymbolicConfig {
+            enabled: true,
+            solver: "missing-smt-solver".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    prj.write_config(config);
+    prj.add_test(
+        "Symbolic.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract SymbolicTest is Test {
+    function check_Name(string memory name) public pure {
+        assertGt(bytes(name).length, 0);
+    }
+
+    function check_Amount(uint256 amount) public pure {
+        assertLe(amount / 2, amount);
+    }
+
+    function testFuzz_Name(string memory name) public pure {
+        assertEq(bytes(name).length, bytes(name).length);
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]).assert_failure().stdout_eq(str![[r#"
+...
+[FAIL: symbolic execution failed: failed to start the SMT solver `missing-smt-solver`[..]] check_Amount(uint256) [..]
+[FAIL: symbolic execution failed: parameters of type `string` cannot be symbolic] check_Name(string) [..]
+[PASS] testFuzz_Name(string) (runs: [..])
+...
+"#]]);
+});
+
+// tests that `check_` functions are not run unless symbolic testing is enabled
+forgetest_init!(should_ignore_check_functions_without_symbolic, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Symbolic.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract SymbolicTest is Test {
+    function check_Fails(uint256 amount) public pure {
+        assertGt(amount, amount);
+    }
+
+    function test_Passes() public pure {
+        assertTrue(true);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]).assert_success().stdout_eq(str![[r#"
+...
+Ran 1 test for test/Symbolic.t.sol:SymbolicTest
+[PASS] test_Passes() ([GAS])
+...
+"#]]);
+});
+
+// tests that `check_` tests are proven or refuted with a concrete counterexample by z3, and that
+// unsupported instructions and cheatcodes are reported per test
+forgetest_init!(
+    #[ignore = "requires the z3 SMT solver"]
+    should_prove_and_refute_symbolic_tests,
+    |prj, cmd| {
+        prj.wipe_contracts();
+        let config = Config {
+            symbolic: SymbolicConfig { enabled: true, ..Default::default() },
+            ..Default::default()
+        };
+        prj.write_config(config);
+        prj.add_test(
+            "Symbolic.t.sol",
+            r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Vault {
+    address public immutable owner = msg.sender;
+    uint256 public withdrawn;
+
+    function withdraw() external {
+        require(msg.sender == owner, "not owner");
+        require(block.timestamp >= 100, "too early");
+        withdrawn = address(this).balance;
+    }
+}
+
+contract SymbolicTest is Test {
+    Vault vault;
+
+    function setUp() public {
+        vault = new Vault();
+    }
+
+    function check_Halves(uint256 amount) public pure {
+        assertLe(amount / 2, amount);
+    }
+
+    function check_Refuted(uint256 amount) public pure {
+        assertNotEq(amount * 3, 126);
+    }
+
+    function check_OnlyOwner(address caller) public {
+        vm.assume(caller != address(this));
+        vm.warp(100);
+        vm.prank(caller);
+        vm.expectRevert(bytes("not owner"));
+        vault.withdraw();
+    }
+
+    function check_Deadline(uint256 timestamp) public {
+        vm.warp(timestamp);
+        vm.deal(address(vault), 1 ether);
+        vault.withdraw();
+        assertEq(vault.withdrawn(), 1 ether);
+    }
+
+    function check_Create(uint256) public {
+        new Vault();
+    }
+
+    function check_MockCall(uint256 amount) public {
+        vm.mockCall(address(vault), abi.encodeCall(Vault.withdrawn, ()), abi.encode(amount));
+        assertEq(vault.withdrawn(), amount);
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        cmd.args(["test"]).assert_failure().stdout_eq(str![[r#"
+...
+[FAIL: symbolic execution does not support the CREATE instruction] check_Create(uint256) [..]
+[FAIL: too early; counterexample: calldata=[..] args=[..]] check_Deadline(uint256) [..]
+[PASS] check_Halves(uint256) [..]
+[FAIL: symbolic execution does not support the `mockCall(address,bytes,bytes)` cheatcode] check_MockCall(uint256) [..]
+[PASS] check_OnlyOwner(address) [..]
+[FAIL: assertion failed: 126 == 126; counterexample: calldata=[..] args=[42]] check_Refuted(uint256) [..]
+...
+"#]]);
+    }
+);
+
+static FAILING_ASSERTION_TEST: &str = r#"
+import {Vm} from "./Vm.sol";
+import {DSTest} from "./test.sol";
+
+contract Counter {
+    uint256 public number = 1;
+
+    function increment() public {
+        number++;
+    }
+}
+
+contract CounterAssertion {
+    Counter counter;
+
+    constructor(address counter_) {
+        counter = Counter(counter_);
+    }
+
+    function fnSelectors() external pure returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertAtMostOne.selector;
+    }
+
+    function assertAtMostOne() external view {
+        require(counter.number() <= 1, "number above one");
+    }
+}
+
+contract AssertionTest is DSTest {
+    Vm vm = Vm(HEVM_ADDRESS);
+
+    struct SimpleTransaction {
+        address from;
+        address to;
+        uint256 value;
+        bytes data;
+    }
+
+    function testAssertionFails() public {
+        Counter counter = new Counter();
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(0xdead),
+            to: address(counter),
+            value: 0,
+            data: abi.encodeWithSelector(Counter.increment.selector)
+        });
+        bytes memory assertion =
+            abi.encodePacked(type(CounterAssertion).creationCode, abi.encode(address(counter)));
+        vm.assertionEx(abi.encode(transaction), address(counter), assertion, "CounterAssertion");
+    }
+}
+"#;
+
+// tests that the triggering transaction and the executed assertion functions are traced when an
+// assertion fails
+forgetest_init!(should_trace_failing_assertions, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.insert_ds_test();
+    prj.insert_vm();
+    prj.clear();
+
+    prj.add_source("Assertion.t.sol", FAILING_ASSERTION_TEST).unwrap();
+
+    cmd.args(["test", "-vvvv"]).assert_failure().stdout_eq(str![[r#"
+...
+[FAIL: Assertions Reverted in Transaction: CounterAssertion::assertAtMostOne()] testAssertionFails() ([GAS])
+Traces:
+...
+    ├─ [..] VM::assertionEx([..])
+    │   ├─ [..] Counter::increment()
+    │   │   └─ ← [Stop] 
+    │   ├─ [..] CounterAssertion::assertAtMostOne()
+    │   │   └─ ← [Revert] revert: number above one
+    │   └─ ← [Revert] Assertions Reverted in Transaction: CounterAssertion::assertAtMostOne()
+...
+"#]]);
+});
+
+// tests that a failed `assertionEx` call writes a replay file that `forge replay-assertion` re-runs
+forgetest_init!(should_replay_failing_assertions, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.insert_ds_test();
+    prj.insert_vm();
+    prj.clear();
+    prj.write_config(Config {
+        credible: CredibleConfig { replay_dir: Some("replays".into()), ..Default::default() },
+        ..Default::default()
+    });
+    prj.add_source("Assertion.t.sol", FAILING_ASSERTION_TEST).unwrap();
+
+    cmd.args(["test"]).assert_failure();
+
+    let replays = std::fs::read_dir(prj.root().join("replays"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(replays.len(), 1);
+    let replay = replays[0].to_str().unwrap();
+
+    cmd.forge_fuse().args(["replay-assertion", replay]).assert_failure().stdout_eq(str![[r#"
+Transaction succeeded (gas: [..])
+Traces:
+  [..] Counter::increment()
+    └─ ← [Stop] 
+
+  [..] CounterAssertion::assertAtMostOne()
+    └─ ← [Revert] revert: number above one
+
+[FAIL: revert: number above one] CounterAssertion::assertAtMostOne (gas: [..])
+
+"#]]);
+
+    let output = cmd
+        .forge_fuse()
+        .args(["replay-assertion", replay, "--json"])
+        .assert_failure()
+        .get_output()
+        .stdout_lossy();
+    let transactions: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(transactions.as_array().unwrap().len(), 1);
+    assert_eq!(transactions[0]["success"], true);
+    let assertion = &transactions[0]["assertions"][0];
+    assert_eq!(assertion["label"], "CounterAssertion");
+    assert_eq!(assertion["function_name"], "assertAtMostOne");
+    assert_eq!(assertion["success"], false);
+    assert_eq!(assertion["revert_reason"], "revert: number above one");
+});
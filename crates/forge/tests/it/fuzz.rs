@@ -7,6 +7,7 @@ use forge::{
     fuzz::CounterExample,
     result::{SuiteResult, TestStatus},
 };
+use foundry_config::AssertionTarget;
 use foundry_test_utils::{forgetest_init, str, Filter};
 use std::collections::BTreeMap;
 
@@ -76,6 +77,35 @@ async fn test_successful_fuzz_cases() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_assertions() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzAssertions.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.assertions = vec![AssertionTarget {
+            adopter: "AssertionCounter".to_string(),
+            assertion: "BelowThreeAssertion".to_string(),
+        }];
+    });
+    let results = runner.test_collect(&filter);
+
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            "default/fuzz/FuzzAssertions.t.sol:FuzzAssertionsTest",
+            vec![(
+                "testFuzz_incrementBelowThree(uint8)",
+                false,
+                Some(
+                    "BelowThreeAssertion::assertBelowThree assertion failed: revert: value too high"
+                        .into(),
+                ),
+                None,
+                None,
+            )],
+        )]),
+    );
+}
+
 /// Test that showcases PUSH collection on normal fuzzing. Ignored until we collect them in a
 /// smarter way.
 #[tokio::test(flavor = "multi_thread")]
@@ -3,7 +3,7 @@
 use crate::{config::*, test_helpers::TEST_DATA_DEFAULT};
 use alloy_primitives::U256;
 use forge::fuzz::CounterExample;
-use foundry_config::{Config, InvariantConfig};
+use foundry_config::{AssertionTarget, Config, InvariantConfig};
 use foundry_test_utils::{forgetest_init, str, Filter};
 use std::collections::BTreeMap;
 
@@ -46,6 +46,69 @@ async fn test_invariant_with_alias() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_workers() {
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.invariant.workers = 4;
+    });
+
+    // The failure of the first failing worker is reported.
+    assert_multiple(
+        &runner.test_collect(&Filter::new(
+            ".*",
+            ".*",
+            ".*fuzz/invariant/common/InvariantTest1.t.sol",
+        )),
+        BTreeMap::from([(
+            "default/fuzz/invariant/common/InvariantTest1.t.sol:InvariantTest",
+            vec![
+                ("invariant_neverFalse()", false, Some("revert: false".into()), None, None),
+                (
+                    "statefulFuzz_neverFalseWithInvariantAlias()",
+                    false,
+                    Some("revert: false".into()),
+                    None,
+                    None,
+                ),
+            ],
+        )]),
+    );
+
+    // The runs of all workers are reported.
+    let results = runner.test_collect(&Filter::new(
+        ".*",
+        ".*",
+        ".*fuzz/invariant/target/ExcludeContracts.t.sol",
+    ));
+    let result = results.values().next().unwrap().test_results.values().next().unwrap();
+    assert!(result.status.is_success());
+    match result.kind {
+        forge::result::TestKind::Invariant { runs, .. } => assert_eq!(runs, 256),
+        _ => panic!("Expected an invariant test result."),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_workers_stop_on_failure() {
+    let filter =
+        Filter::new("invariant_neverFalse", ".*", ".*fuzz/invariant/common/InvariantTest1.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.seed = Some(U256::from(119u32));
+        config.invariant.runs = 10_000;
+        config.invariant.workers = 4;
+    });
+
+    // The invariant breaks long before any worker completed its share of runs, the other workers
+    // stop as soon as it does instead of running their full share.
+    let results = runner.test_collect(&filter);
+    let result = results.values().next().unwrap().test_results.values().next().unwrap();
+    assert!(result.status.is_failure());
+    match result.kind {
+        forge::result::TestKind::Invariant { runs, .. } => assert!(runs < 10_000 / 4, "{runs}"),
+        _ => panic!("Expected an invariant test result."),
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_invariant_filters() {
     let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
@@ -524,6 +587,42 @@ async fn test_invariant_decode_custom_error() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_assertions() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantAssertions.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.invariant.runs = 10;
+        config.invariant.depth = 10;
+        config.invariant.assertions = vec![AssertionTarget {
+            adopter: "AssertionCounter".to_string(),
+            assertion: "BelowThreeAssertion".to_string(),
+        }];
+    });
+    let results = runner.test_collect(&filter);
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            "default/fuzz/invariant/common/InvariantAssertions.t.sol:InvariantAssertions",
+            vec![(
+                "invariant_assertion_holds()",
+                false,
+                Some(
+                    "BelowThreeAssertion::assertBelowThree assertion failed: revert: value too high"
+                        .into(),
+                ),
+                None,
+                None,
+            )],
+        )]),
+    );
+
+    // The failing sequence is shrunk to the calls needed to invalidate the assertion.
+    match get_counterexample!(runner, &filter) {
+        CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+        CounterExample::Sequence(sequence) => assert_eq!(sequence.len(), 3),
+    };
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_invariant_fuzzed_selected_targets() {
     let filter = Filter::new(".*", ".*", ".*fuzz/invariant/target/FuzzedTargetContracts.t.sol");
@@ -121,6 +121,9 @@ impl ForgeTestProfile {
             failure_persist_file: Some("testfailure".to_string()),
             show_logs: false,
             timeout: None,
+            coverage_guided: false,
+            corpus_dir: None,
+            assertions: vec![],
         };
         config.invariant = InvariantConfig {
             runs: 256,
@@ -145,7 +148,12 @@ impl ForgeTestProfile {
                     .into_path(),
             ),
             show_metrics: false,
+            show_state_diffs: false,
             timeout: None,
+            workers: 1,
+            coverage_guided: false,
+            corpus_dir: None,
+            assertions: vec![],
         };
 
         config.sanitized()
@@ -24,6 +24,8 @@ interface Vm {
     struct DebugStep { uint256[] stack; bytes memoryInput; uint8 opcode; uint64 depth; bool isOutOfGas; address contractAddr; }
     struct BroadcastTxSummary { bytes32 txHash; BroadcastTxType txType; address contractAddress; uint64 blockNumber; bool success; }
     struct SignedDelegation { uint8 v; bytes32 r; bytes32 s; uint64 nonce; address implementation; }
+    struct AssertionExContract { address adopter; bytes assertionContract; string label; }
+    struct AssertionExResult { uint256 txIndex; address adopter; string label; bytes4 selector; string functionName; uint64 gasUsed; bool success; bytes revertData; string revertReason; }
     function _expectCheatcodeRevert() external;
     function _expectCheatcodeRevert(bytes4 revertData) external;
     function _expectCheatcodeRevert(bytes calldata revertData) external;
@@ -147,7 +149,9 @@ interface Vm {
     function assertNotEq(bytes32 left, bytes32 right, string calldata error) external pure;
     function assertTrue(bool condition) external pure;
     function assertTrue(bool condition, string calldata error) external pure;
+    function assertionExBatch(bytes[] calldata txs, AssertionExContract[] calldata assertions) external;
     function assertionEx(bytes calldata tx, address assertionAdopter, bytes calldata assertionContract, string calldata assertionContractLabel) external;
+    function assertionEx(bytes[] calldata txs, address assertionAdopter, bytes calldata assertionContract, string calldata assertionContractLabel) external;
     function assume(bool condition) external pure;
     function assumeNoRevert() external pure;
     function attachDelegation(SignedDelegation calldata signedDelegation) external;
@@ -268,6 +272,7 @@ interface Vm {
     function fsMetadata(string calldata path) external view returns (FsMetadata memory metadata);
     function getArtifactPathByCode(bytes calldata code) external view returns (string memory path);
     function getArtifactPathByDeployedCode(bytes calldata deployedCode) external view returns (string memory path);
+    function getAssertionExResults() external view returns (AssertionExResult[] memory results);
     function getBlobBaseFee() external view returns (uint256 blobBaseFee);
     function getBlobhashes() external view returns (bytes32[] memory hashes);
     function getBlockNumber() external view returns (uint256 height);
@@ -464,6 +469,8 @@ interface Vm {
     function skip(bool skipTest, string calldata reason) external;
     function sleep(uint256 duration) external;
     function snapshot() external returns (uint256 snapshotId);
+    function snapshotGasLastAssertionEx(string calldata name) external returns (uint256 gasUsed);
+    function snapshotGasLastAssertionEx(string calldata group, string calldata name) external returns (uint256 gasUsed);
     function snapshotGasLastCall(string calldata name) external returns (uint256 gasUsed);
     function snapshotGasLastCall(string calldata group, string calldata name) external returns (uint256 gasUsed);
     function snapshotState() external returns (uint256 snapshotId);
@@ -22,6 +22,36 @@ contract MockAssertion is Assertion {
     }
 }
 
+contract MockMaxAssertion is Assertion {
+    MockContract mockContract;
+
+    constructor(address mockContract_) {
+        mockContract = MockContract(mockContract_);
+    }
+
+    function fnSelectors() external pure override returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertAtMostTwo.selector;
+    }
+
+    function assertAtMostTwo() external view {
+        require(mockContract.value() <= 2, "value above two");
+    }
+}
+
+contract GasBurningAssertion is Assertion {
+    function fnSelectors() external pure override returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertBurnsGas.selector;
+    }
+
+    /// Burns more gas than the default assertion gas limit of 3_000_000.
+    function assertBurnsGas() external view {
+        uint256 start = gasleft();
+        while (start - gasleft() < 4_000_000) {}
+    }
+}
+
 contract MockContract {
     uint256 public value = 1;
 
@@ -69,4 +99,184 @@ contract CredibleTest is DSTest {
 
         vm.assertionEx(abi.encode(transaction), assertionAdopter, assertion, "MockAssertion");
     }
+
+    function testAssertionBundlePass() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        bytes[] memory transactions = new bytes[](2);
+        transactions[0] = abi.encode(transaction);
+        transactions[1] = abi.encode(transaction);
+
+        bytes memory assertion = abi.encodePacked(type(MockAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm.assertionEx(transactions, assertionAdopter, assertion, "MockAssertion");
+        assertTrue(MockContract(assertionAdopter).value() == 1);
+    }
+
+    function testAssertionBundleFail() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        // The second increment invalidates the assertion, the third one is never executed.
+        bytes[] memory transactions = new bytes[](3);
+        transactions[0] = abi.encode(transaction);
+        transactions[1] = abi.encode(transaction);
+        transactions[2] = abi.encode(transaction);
+
+        bytes memory assertion = abi.encodePacked(type(MockMaxAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm._expectCheatcodeRevert("Assertions Reverted in Transaction #1: MockMaxAssertion::assertAtMostTwo()");
+        vm.assertionEx(transactions, assertionAdopter, assertion, "MockMaxAssertion");
+
+        Vm.AssertionExResult[] memory results = vm.getAssertionExResults();
+        assertEq(results.length, 2);
+        assertEq(results[0].txIndex, 0);
+        assertTrue(results[0].success);
+        assertEq(results[1].txIndex, 1);
+        assertEq(results[1].functionName, "assertAtMostTwo");
+        assertTrue(!results[1].success);
+        assertEq(results[1].revertReason, "value above two");
+        assertEq(MockContract(assertionAdopter).value(), 1);
+    }
+
+    function testAssertionFail() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        // The triggering increment takes the value above two.
+        MockContract(assertionAdopter).increment();
+
+        bytes memory assertion = abi.encodePacked(type(MockMaxAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm._expectCheatcodeRevert("Assertions Reverted in Transaction: MockMaxAssertion::assertAtMostTwo()");
+        vm.assertionEx(abi.encode(transaction), assertionAdopter, assertion, "MockMaxAssertion");
+    }
+
+    function testAssertionBatchPass() public {
+        address otherAdopter = address(new MockContract());
+
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+        SimpleTransaction memory otherTransaction = SimpleTransaction({
+            from: address(caller),
+            to: address(otherAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        bytes[] memory transactions = new bytes[](2);
+        transactions[0] = abi.encode(transaction);
+        transactions[1] = abi.encode(otherTransaction);
+
+        Vm.AssertionExContract[] memory assertions = new Vm.AssertionExContract[](2);
+        assertions[0] = Vm.AssertionExContract({
+            adopter: assertionAdopter,
+            assertionContract: abi.encodePacked(type(MockAssertion).creationCode, abi.encode(assertionAdopter)),
+            label: "MockAssertion"
+        });
+        assertions[1] = Vm.AssertionExContract({
+            adopter: otherAdopter,
+            assertionContract: abi.encodePacked(type(MockAssertion).creationCode, abi.encode(otherAdopter)),
+            label: "OtherMockAssertion"
+        });
+
+        vm.assertionExBatch(transactions, assertions);
+    }
+
+    function testAssertionResults() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        bytes memory assertion = abi.encodePacked(type(MockAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm.assertionEx(abi.encode(transaction), assertionAdopter, assertion, "MockAssertion");
+
+        Vm.AssertionExResult[] memory results = vm.getAssertionExResults();
+        assertEq(results.length, 1);
+        assertEq(results[0].txIndex, 0);
+        assertEq(results[0].adopter, assertionAdopter);
+        assertEq(results[0].label, "MockAssertion");
+        assertTrue(results[0].selector == MockAssertion.assertIsOne.selector);
+        assertEq(results[0].functionName, "assertIsOne");
+        assertTrue(results[0].success);
+        assertEq(results[0].revertData.length, 0);
+    }
+
+    function testAssertionResultsAfterFailedCall() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        bytes memory assertion = abi.encodePacked(type(MockAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm.assertionEx(abi.encode(transaction), assertionAdopter, assertion, "MockAssertion");
+        assertEq(vm.getAssertionExResults().length, 1);
+
+        // A call failing before any assertion ran doesn't report the results of the previous one.
+        vm._expectCheatcodeRevert("no transactions provided");
+        vm.assertionEx(new bytes[](0), assertionAdopter, assertion, "MockAssertion");
+        assertEq(vm.getAssertionExResults().length, 0);
+    }
+
+    /// forge-config: default.credible.gas_report = false
+    /// forge-config: default.credible.assertion_gas_limit = 5000000
+    function testAssertionInlineConfig() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        // Only succeeds with the raised inline gas limit, it runs out of gas with the default one.
+        vm.assertionEx(
+            abi.encode(transaction), assertionAdopter, type(GasBurningAssertion).creationCode, "GasBurningAssertion"
+        );
+
+        Vm.AssertionExResult[] memory results = vm.getAssertionExResults();
+        assertEq(results.length, 1);
+        assertTrue(results[0].success);
+        assertTrue(results[0].gasUsed > 3000000);
+    }
+
+    function testSnapshotGasLastAssertionEx() public {
+        SimpleTransaction memory transaction = SimpleTransaction({
+            from: address(caller),
+            to: address(assertionAdopter),
+            value: 0,
+            data: abi.encodeWithSelector(MockContract.increment.selector)
+        });
+
+        bytes memory assertion = abi.encodePacked(type(MockAssertion).creationCode, abi.encode(assertionAdopter));
+
+        vm.assertionEx(abi.encode(transaction), assertionAdopter, assertion, "MockAssertion");
+
+        Vm.AssertionExResult[] memory results = vm.getAssertionExResults();
+        uint256 gasUsed = vm.snapshotGasLastAssertionEx("CredibleTest", "assertIsOne");
+        assertEq(gasUsed, results[0].gasUsed);
+    }
 }
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import {Assertion} from "credible-std/Assertion.sol";
+
+contract AssertionCounter {
+    uint256 public value;
+
+    function increment() public {
+        value++;
+    }
+}
+
+contract BelowThreeAssertion is Assertion {
+    AssertionCounter counter;
+
+    constructor(address counter_) {
+        counter = AssertionCounter(counter_);
+    }
+
+    function fnSelectors() external pure override returns (bytes4[] memory selectors) {
+        selectors = new bytes4[](1);
+        selectors[0] = this.assertBelowThree.selector;
+    }
+
+    function assertBelowThree() external view {
+        require(counter.value() < 3, "value too high");
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+import {AssertionCounter} from "./Assertions.sol";
+
+contract FuzzAssertionsTest is DSTest {
+    AssertionCounter counter;
+
+    function setUp() public {
+        counter = new AssertionCounter();
+    }
+
+    function testFuzz_incrementBelowThree(uint8 times) public {
+        for (uint256 i; i < times % 5; i++) {
+            counter.increment();
+        }
+    }
+}
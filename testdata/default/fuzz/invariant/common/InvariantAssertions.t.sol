@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+import {AssertionCounter} from "../../Assertions.sol";
+
+contract InvariantAssertions is DSTest {
+    AssertionCounter counter;
+
+    function setUp() public {
+        counter = new AssertionCounter();
+    }
+
+    function targetContracts() public view returns (address[] memory targets) {
+        targets = new address[](1);
+        targets[0] = address(counter);
+    }
+
+    function invariant_assertion_holds() public {}
+}